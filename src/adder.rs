@@ -1,4 +1,15 @@
-use crate::{and, or, xor};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::backend::{GateBackend, NandBackend};
+use crate::wiring::BitSliceError;
+use crate::xor;
+#[cfg(feature = "alloc")]
+use crate::{and, not, or};
+#[cfg(feature = "alloc")]
+use crate::bus::{mux_bus, not_n};
+#[cfg(feature = "alloc")]
+use crate::convert::bits_from_u64_n;
 
 /// 半加算器
 /// 
@@ -9,8 +20,15 @@ use crate::{and, or, xor};
 /// 
 /// 戻り値は (sum, carry) のタプル
 pub fn half_adder(a: bool, b: bool) -> (bool, bool) {
-    let sum = xor(a, b);    // 和は XOR
-    let carry = and(a, b);  // 桁上げは AND
+    half_adder_with(&NandBackend, a, b)
+}
+
+/// 半加算器（バックエンド指定版）
+/// 
+/// half_adder と同じ回路を、指定したゲートバックエンドで評価する
+pub fn half_adder_with<G: GateBackend>(g: &G, a: bool, b: bool) -> (bool, bool) {
+    let sum = g.xor(a, b);    // 和は XOR
+    let carry = g.and(a, b);  // 桁上げは AND
     (sum, carry)
 }
 
@@ -24,9 +42,16 @@ pub fn half_adder(a: bool, b: bool) -> (bool, bool) {
 /// 
 /// 戻り値は (sum, carry_out) のタプル
 pub fn full_adder(a: bool, b: bool, carry_in: bool) -> (bool, bool) {
-    let (sum1, carry1) = half_adder(a, b);
-    let (sum, carry2) = half_adder(sum1, carry_in);
-    let carry_out = or(carry1, carry2);
+    full_adder_with(&NandBackend, a, b, carry_in)
+}
+
+/// 全加算器（バックエンド指定版）
+/// 
+/// full_adder と同じ回路を、指定したゲートバックエンドで評価する
+pub fn full_adder_with<G: GateBackend>(g: &G, a: bool, b: bool, carry_in: bool) -> (bool, bool) {
+    let (sum1, carry1) = half_adder_with(g, a, b);
+    let (sum, carry2) = half_adder_with(g, sum1, carry_in);
+    let carry_out = g.or(carry1, carry2);
     (sum, carry_out)
 }
 
@@ -40,45 +65,705 @@ pub fn full_adder(a: bool, b: bool, carry_in: bool) -> (bool, bool) {
 /// 戻り値は (sum, carry) のタプル
 /// - sum: nビットの和（LSB→MSB順）
 /// - carry: 最終桁上げ
+#[cfg(feature = "alloc")]
 pub fn n_bit_adder(a: &[bool], b: &[bool]) -> (Vec<bool>, bool) {
-    // aとbの長さが異なる場合は、短い方を0で埋める
+    n_bit_adder_with_carry(a, b, false)
+}
+
+/// nビット加算器（バックエンド指定版）
+/// 
+/// n_bit_adder と同じ回路を、指定したゲートバックエンドで評価する
+#[cfg(feature = "alloc")]
+pub fn n_bit_adder_with<G: GateBackend>(g: &G, a: &[bool], b: &[bool]) -> (Vec<bool>, bool) {
+    n_bit_adder_with_carry_with(g, a, b, false)
+}
+
+/// 桁上げ入力付きのnビット加算器
+/// 
+/// a + b + carry_in を計算する。下位の加算器の桁上げを carry_in につなげば、
+/// 幅の広い加算器を組み立てられる
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// * `carry_in` - 最下位ビットへの桁上げ
+/// 
+/// 長さが異なる場合は短い方を0で埋める。戻り値は n_bit_adder と同じ (sum, carry) のタプル
+#[cfg(feature = "alloc")]
+pub fn n_bit_adder_with_carry(a: &[bool], b: &[bool], carry_in: bool) -> (Vec<bool>, bool) {
+    n_bit_adder_with_carry_with(&NandBackend, a, b, carry_in)
+}
+
+/// 桁上げ入力付きのnビット加算器（バックエンド指定版）
+/// 
+/// n_bit_adder_with_carry と同じ回路を、指定したゲートバックエンドで評価する
+#[cfg(feature = "alloc")]
+pub fn n_bit_adder_with_carry_with<G: GateBackend>(
+    g: &G,
+    a: &[bool],
+    b: &[bool],
+    carry_in: bool,
+) -> (Vec<bool>, bool) {
+    let mut sum = vec![false; a.len().max(b.len())];
+    let carry = add_into_with(g, a, b, carry_in, &mut sum).expect("sum buffer has the full width");
+    (sum, carry)
+}
+
+/// 出力先を指定するnビット加算器
+/// 
+/// a + b + carry_in の和を、呼び出し側が用意したバッファ out に書き込む。ヒープ確保をしない
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// * `carry_in` - 最下位ビットへの桁上げ
+/// * `out` - 和の書き込み先（max(a.len(), b.len()) ビット以上）
+/// 
+/// 長さが異なる場合は短い方を0で埋める。out が和の幅 n より長い場合、out[n..] は0にする
+/// （桁上げは out には書かず、戻り値で返す）
+/// 
+/// 戻り値は最終桁上げ。out が短すぎる場合は BitSliceError::BufferTooShort を返し、out は変更しない
+pub fn add_into(a: &[bool], b: &[bool], carry_in: bool, out: &mut [bool]) -> Result<bool, BitSliceError> {
+    add_into_with(&NandBackend, a, b, carry_in, out)
+}
+
+/// 出力先を指定するnビット加算器（バックエンド指定版）
+/// 
+/// add_into と同じ回路を、指定したゲートバックエンドで評価する
+pub fn add_into_with<G: GateBackend>(
+    g: &G,
+    a: &[bool],
+    b: &[bool],
+    carry_in: bool,
+    out: &mut [bool],
+) -> Result<bool, BitSliceError> {
     let n = a.len().max(b.len());
+    if out.len() < n {
+        return Err(BitSliceError::BufferTooShort { needed: n, len: out.len() });
+    }
+
+    let (sum, upper) = out.split_at_mut(n);
+    let (carry, _carry_into_msb) = ripple_carry_into_with(g, a, b, carry_in, sum);
+    upper.fill(false);
+    Ok(carry)
+}
+
+/// nビット加算の結果
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdderResult {
+    /// nビットの和（LSB→MSB順）
+    pub sum: Vec<bool>,
+    /// 符号なしの桁上げ（最上位ビットからの桁上げ）
+    pub carry: bool,
+    /// 符号付きオーバーフロー（最上位ビットへの桁上げと、最上位ビットからの桁上げが異なる）
+    pub overflow: bool,
+}
+
+/// 桁上げとオーバーフローを返すnビット加算器
+/// 
+/// a + b + carry_in を計算し、和・符号なしの桁上げ・符号付きオーバーフローをまとめて返す
+/// オーバーフローは、最上位ビットの全加算器に入る桁上げと出る桁上げの XOR で求める
+/// 
+/// 長さが異なる場合は短い方を0で埋める。空の入力では overflow はfalse
+/// 
+/// ```
+/// use logic_gates::adder::n_bit_adder_full;
+/// 
+/// // 4ビットの 7 + 1 = 8: 符号付きでは 7 + 1 = -8 になりオーバーフローするが、桁上げは出ない
+/// let r = n_bit_adder_full(&[true, true, true, false], &[true, false, false, false], false);
+/// assert_eq!((r.sum, r.carry, r.overflow), (vec![false, false, false, true], false, true));
+/// 
+/// // 4ビットの 15 + 1 = 0: 桁上げが出るが、符号付きでは -1 + 1 = 0 でオーバーフローしない
+/// let r = n_bit_adder_full(&[true; 4], &[true, false, false, false], false);
+/// assert_eq!((r.sum, r.carry, r.overflow), (vec![false; 4], true, false));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn n_bit_adder_full(a: &[bool], b: &[bool], carry_in: bool) -> AdderResult {
+    n_bit_adder_full_with(&NandBackend, a, b, carry_in)
+}
+
+/// 桁上げとオーバーフローを返すnビット加算器（バックエンド指定版）
+#[cfg(feature = "alloc")]
+pub fn n_bit_adder_full_with<G: GateBackend>(g: &G, a: &[bool], b: &[bool], carry_in: bool) -> AdderResult {
+    let (sum, carry, carry_into_msb) = ripple_carry_with(g, a, b, carry_in);
+    let overflow = if sum.is_empty() { false } else { g.xor(carry_into_msb, carry) };
+    AdderResult { sum, carry, overflow }
+}
+
+/// 全加算器の連鎖（リップルキャリー）
+/// 
+/// 和のバッファを確保して ripple_carry_into_with を呼ぶ
+/// 戻り値は (sum, carry, carry_into_msb) のタプル（carry_into_msb は最上位ビットの全加算器に入る桁上げ）
+#[cfg(feature = "alloc")]
+fn ripple_carry_with<G: GateBackend>(g: &G, a: &[bool], b: &[bool], carry_in: bool) -> (Vec<bool>, bool, bool) {
+    let mut sum = vec![false; a.len().max(b.len())];
+    let (carry, carry_into_msb) = ripple_carry_into_with(g, a, b, carry_in, &mut sum);
+    (sum, carry, carry_into_msb)
+}
+
+/// 全加算器の連鎖の和を sum に書き込む
+/// 
+/// リップルキャリーの可変幅の加算器の実装はこの関数だけで、他の加算器はこれを呼ぶ
+/// sum の長さを加算の幅nとし、aとbの足りないビットは0で埋める
+/// 戻り値は (carry, carry_into_msb) のタプル
+fn ripple_carry_into_with<G: GateBackend>(
+    g: &G,
+    a: &[bool],
+    b: &[bool],
+    carry_in: bool,
+    sum: &mut [bool],
+) -> (bool, bool) {
+    let mut carry = carry_in;
+    let mut carry_into_msb = carry_in;
     
-    let mut sum = Vec::with_capacity(n);
-    let mut carry = false;
-    
-    for i in 0..n {
+    for (i, s) in sum.iter_mut().enumerate() {
         let bit_a = if i < a.len() { a[i] } else { false };
         let bit_b = if i < b.len() { b[i] } else { false };
         
-        let (bit_sum, bit_carry) = full_adder(bit_a, bit_b, carry);
-        sum.push(bit_sum);
+        carry_into_msb = carry;
+        let (bit_sum, bit_carry) = full_adder_with(g, bit_a, bit_b, carry);
+        *s = bit_sum;
         carry = bit_carry;
     }
     
+    (carry, carry_into_msb)
+}
+
+/// Kogge-Stone 加算器（並列プレフィックス加算器）
+/// 
+/// 各ビットの生成 g = a AND b と伝搬 p = a XOR b を求め、(G, P) の組を
+/// 距離 1, 2, 4, ... の位置と結合する段を重ねて、全ビットへの桁上げを並列に求める
+/// 結合は (G, P) ∘ (G', P') = (G OR (P AND G'), P AND P')
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// * `carry_in` - 最下位ビットへの桁上げ
+/// 
+/// 長さが異なる場合は短い方を0で埋める。幅が2の累乗でなくても、実際の幅のまま計算する
+/// 戻り値は n_bit_adder_with_carry と同じ (sum, carry) のタプル
+/// 
+/// carry_in はビット0の生成 g0 = (a0 AND b0) OR (p0 AND carry_in) にまとめるので、
+/// n レーンを結合する段数は ⌈log2 n⌉（16ビットで4段）で、リップルキャリー（n段）より
+/// 桁上げの遅延が短い。代わりにゲートは多く、CountingBackend で数えた NAND の数は、
+/// 16ビットでリップルキャリーの 240 に対して 508、24ビットで 360 に対して 868、
+/// 64ビットで 960 に対して 2892
+#[cfg(feature = "alloc")]
+pub fn kogge_stone_adder(a: &[bool], b: &[bool], carry_in: bool) -> (Vec<bool>, bool) {
+    kogge_stone_adder_with(&NandBackend, a, b, carry_in)
+}
+
+/// Kogge-Stone 加算器（バックエンド指定版）
+/// 
+/// kogge_stone_adder と同じ回路を、指定したゲートバックエンドで評価する
+#[cfg(feature = "alloc")]
+pub fn kogge_stone_adder_with<G: GateBackend>(g: &G, a: &[bool], b: &[bool], carry_in: bool) -> (Vec<bool>, bool) {
+    let n = a.len().max(b.len());
+    if n == 0 {
+        return (vec![], carry_in);
+    }
+    // carry_in はビット0の生成にまとめる（g0 = a0 b0 + p0 carry_in）ので、レーンは n 本
+    let mut generate = Vec::with_capacity(n);
+    let mut propagate = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = i < a.len() && a[i];
+        let bit_b = i < b.len() && b[i];
+        generate.push(g.and(bit_a, bit_b));
+        propagate.push(g.xor(bit_a, bit_b));
+    }
+    generate[0] = g.or(generate[0], g.and(propagate[0], carry_in));
+    let half_sum = propagate.clone();
+
+    // プレフィックスの段: レーン i に、距離 d だけ下のレーンの (G, P) を結合する
+    // 距離がレーン数 n に達するまでの ⌈log2 n⌉ 段
+    let mut distance = 1;
+    while distance < n {
+        let (prev_g, prev_p) = (generate.clone(), propagate.clone());
+        for i in distance..n {
+            generate[i] = g.or(prev_g[i], g.and(prev_p[i], prev_g[i - distance]));
+            propagate[i] = g.and(prev_p[i], prev_p[i - distance]);
+        }
+        distance *= 2;
+    }
+
+    // generate[i] はビット i からの桁上げ（carry_in とビット 0..=i の結合）
+    let sum = (0..n)
+        .map(|i| g.xor(half_sum[i], if i == 0 { carry_in } else { generate[i - 1] }))
+        .collect();
+    (sum, generate[n - 1])
+}
+
+/// 複数ワードの総和
+/// 
+/// n_bit_adder で先頭のワードから順に累積する
+/// 
+/// * `words` - 加算するワードの一覧（いずれもLSB→MSB順）
+/// 
+/// 短いワードは最も長いワードの幅まで0で埋める
+/// 
+/// 戻り値は (sum, carry) のタプル
+/// - sum: 最も長いワードと同じ幅の和（空の一覧には幅0の空のVecを返す）
+/// - carry: 本当の総和がその幅に収まらなかった場合にtrue（途中の加算の桁上げのOR）
+#[cfg(feature = "alloc")]
+pub fn sum_words(words: &[Vec<bool>]) -> (Vec<bool>, bool) {
+    let width = words.iter().map(|w| w.len()).max().unwrap_or(0);
+    let mut sum = vec![false; width];
+    let mut carry = false;
+    for word in words {
+        let (s, c) = n_bit_adder(&sum, word);
+        sum = s;
+        carry = or(carry, c);
+    }
+    (sum, carry)
+}
+
+/// 固定幅のnビット加算器（ヒープ確保なし）
+///
+/// n_bit_adder と同じ全加算器の連鎖を、幅Nの配列のまま計算する
+///
+/// 戻り値は (sum, carry) のタプル
+pub fn n_bit_adder_const<const N: usize>(a: &[bool; N], b: &[bool; N]) -> ([bool; N], bool) {
+    let (sum, carry, _carry_into_msb) = ripple_carry_const(a, b, false);
+    (sum, carry)
+}
+
+/// 桁上げとオーバーフローを返す固定幅のnビット加算器（ヒープ確保なし）
+///
+/// n_bit_adder_full と同じ値を、幅Nの配列のまま計算する
+///
+/// 戻り値は (sum, carry, overflow) のタプル。N=0 では overflow はfalse
+pub fn n_bit_adder_full_const<const N: usize>(a: &[bool; N], b: &[bool; N], carry_in: bool) -> ([bool; N], bool, bool) {
+    let (sum, carry, carry_into_msb) = ripple_carry_const(a, b, carry_in);
+    let overflow = N > 0 && xor(carry_into_msb, carry);
+    (sum, carry, overflow)
+}
+
+/// 16ビット加算器（ヒープ確保なし）
+/// 
+/// a + b + carry_in を全加算器の連鎖で計算し、結果をスタック上の配列に書き込む
+/// n_bit_adder_with_carry と同じ値を返す
+/// 
+/// 戻り値は (sum, carry) のタプル
+pub fn add16(a: &[bool; 16], b: &[bool; 16], carry_in: bool) -> ([bool; 16], bool) {
+    let (sum, carry, _carry_into_msb) = ripple_carry_const(a, b, carry_in);
     (sum, carry)
 }
 
+/// 16ビット加算器を4つつないだ64ビット加算器
+/// 
+/// 64ビットを16ビットずつのリム（下位ワードが先）に分け、add16 のブロックを
+/// 下位から順に並べて、各ブロックの桁上げを次のブロックの carry_in につなぐ
+/// 
+/// * `a`, `b` - 64ビットの入力（[0] がビット0〜15、[3] がビット48〜63。各リムはLSB→MSB順）
+/// 
+/// 戻り値は (sum, carry) のタプル
+/// - sum: 入力と同じ形の64ビットの和
+/// - carry: 最上位のブロック（[3]）からの桁上げ
+pub fn add64_from_16(a: &[[bool; 16]; 4], b: &[[bool; 16]; 4]) -> ([[bool; 16]; 4], bool) {
+    let mut sum = [[false; 16]; 4];
+    let mut carry = false;
+    for (i, limb) in sum.iter_mut().enumerate() {
+        let (limb_sum, limb_carry) = add16(&a[i], &b[i], carry);
+        *limb = limb_sum;
+        carry = limb_carry;
+    }
+    (sum, carry)
+}
+
+/// 固定幅の全加算器の連鎖（n_bit_adder_const・n_bit_adder_full_const・add16 の共通部分）
+///
+/// 戻り値は (sum, carry, carry_into_msb) のタプル
+fn ripple_carry_const<const N: usize>(a: &[bool; N], b: &[bool; N], carry_in: bool) -> ([bool; N], bool, bool) {
+    let mut sum = [false; N];
+    let mut carry = carry_in;
+    let mut carry_into_msb = carry_in;
+    for (i, s) in sum.iter_mut().enumerate() {
+        carry_into_msb = carry;
+        let (bit_sum, bit_carry) = full_adder(a[i], b[i], carry);
+        *s = bit_sum;
+        carry = bit_carry;
+    }
+    (sum, carry, carry_into_msb)
+}
+
+/// ポピュレーションカウント（1の個数）
+///
+/// 全加算器の連鎖（n_bit_adder）で、入力を1ビットずつ累積カウンタに加算する
+///
+/// * `inputs` - n本の入力
+///
+/// 戻り値は n を表せる幅（最低1ビット）の2進数（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn popcount(inputs: &[bool]) -> Vec<bool> {
+    let width = (usize::BITS - inputs.len().leading_zeros()).max(1) as usize;
+    let mut count = vec![false; width];
+    for &bit in inputs {
+        let (sum, _carry) = n_bit_adder(&count, &[bit]);
+        count = sum;
+    }
+    count
+}
+
 /// インクリメンタ
 /// 
 /// nビット入力に1を加算する
 /// 
 /// * `a` - nビット入力（LSB→MSB順）
 /// 
-/// 戻り値は (result, overflow) のタプル
+/// 戻り値は (result, carry) のタプル
 /// - result: 加算結果（LSB→MSB順）
-/// - overflow: オーバーフロー発生フラグ（すべてのビットが1の場合にtrueになる）
+/// - carry: 符号なしの桁上げ（最上位ビットからの桁上げ。すべてのビットが1の場合にだけtrueになる）
+/// 
+/// 空の入力には空のVecを返し、carry はtrue
+/// 
+/// 符号付きとして見たときのオーバーフロー（0111...1 → 1000...0）は incrementer_signed を使う
+#[cfg(feature = "alloc")]
 pub fn incrementer(a: &[bool]) -> (Vec<bool>, bool) {
-    // 加算する1をビット配列として表現: [true]（LSBのみ1）
-    let increment = [true];
+    add_const(a, 1)
+}
+
+/// インクリメンタのフラグ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncFlags {
+    /// 符号なしの桁上げ（すべてのビットが1だった）
+    pub carry: bool,
+    /// 符号付きオーバーフロー（最大値 0111...1 が最小値 1000...0 になった）
+    pub signed_overflow: bool,
+}
+
+/// インクリメンタ（符号付きオーバーフロー付き）
+/// 
+/// incrementer と同じく1を加算し、桁上げに加えて符号付きオーバーフローを返す
+/// 
+/// 符号付きオーバーフローは「入力のMSBが0で、結果のMSBが1」として検出する
+/// （正の数に1を足して負になるのは、最大値からの桁あふれだけ）
+/// 
+/// * `a` - nビット入力（LSB→MSB順）
+/// 
+/// 戻り値は (result, flags) のタプル
+#[cfg(feature = "alloc")]
+pub fn incrementer_signed(a: &[bool]) -> (Vec<bool>, IncFlags) {
+    let (result, carry) = incrementer(a);
+    let signed_overflow = match (a.last(), result.last()) {
+        (Some(&a_msb), Some(&r_msb)) => and(not(a_msb), r_msb),
+        _ => false,
+    };
+    (result, IncFlags { carry, signed_overflow })
+}
+
+/// 固定幅のインクリメンタ（桁あふれは捨てる）
+/// 
+/// Nビットの配列に1を加算し、同じ幅の配列を返す（すべて1の入力は0に戻る）
+/// 半加算器の連鎖で計算するので、プログラムカウンタのようにヒープ確保なしで使える
+pub fn incrementer_wrapping_n<const N: usize>(a: &[bool; N]) -> [bool; N] {
+    let mut out = [false; N];
+    let mut carry = true;
+    for i in 0..N {
+        let (sum, carry_out) = half_adder(a[i], carry);
+        out[i] = sum;
+        carry = carry_out;
+    }
+    out
+}
+
+/// デクリメンタ
+/// 
+/// nビット入力から1を減算する
+/// 
+/// 同じ幅のすべてのビットが1のワード（2の補数で-1）を加算することで実現する
+/// 
+/// * `a` - nビット入力（LSB→MSB順）
+/// 
+/// 戻り値は (result, underflow) のタプル
+/// - result: 減算結果（LSB→MSB順、入力と同じ幅）
+/// - underflow: 符号なしとして0を下回った場合にtrue（入力がすべて0の場合）
+/// 
+/// 空の入力には空のVecを返し、underflow はtrue（すべてのビットが0の入力として扱う）
+#[cfg(feature = "alloc")]
+pub fn decrementer(a: &[bool]) -> (Vec<bool>, bool) {
+    let minus_one = vec![true; a.len()];
     
-    // n_bit_adderを利用して加算
-    n_bit_adder(a, &increment)
+    // 0以外の値に-1を加えると必ず桁上げが出るので、桁上げがなければアンダーフロー
+    let (result, carry) = n_bit_adder(a, &minus_one);
+    (result, not(carry))
+}
+
+/// 2の補数の符号反転
+/// 
+/// すべてのビットを反転（not_n）してから incrementer で1を加え、-a を計算する
+/// 
+/// * `a` - nビット入力（LSB→MSB順、2の補数）
+/// 
+/// 戻り値は (result, overflow) のタプル
+/// - result: -a（LSB→MSB順、入力と同じ幅。空の入力には空のVecを返す）
+/// - overflow: 最小値（1000...0）を反転した場合にtrue（結果は最小値のまま）
+#[cfg(feature = "alloc")]
+pub fn negate(a: &[bool]) -> (Vec<bool>, bool) {
+    if a.is_empty() {
+        return (Vec::new(), false);
+    }
+    let (result, _carry) = incrementer(&not_n(a));
+
+    // 符号反転しても負のままなのは最小値だけ（0は正に、負の数は正になる）
+    let overflow = and(a[a.len() - 1], result[result.len() - 1]);
+    (result, overflow)
 }
 
-#[cfg(test)]
+/// nビット減算器
+/// 
+/// a - b を「bを反転して1を加える」2の補数の方法で計算する
+/// （全加算器の連鎖に carry_in=1 を与え、bの各ビットを反転して入力する）
+/// 
+/// * `a` - 被減数（LSB→MSB順）
+/// * `b` - 減数（LSB→MSB順）
+/// 
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+/// 
+/// 戻り値は (difference, borrow) のタプル
+/// - difference: nビットの差（LSB→MSB順）
+/// - borrow: 符号なしとして a < b の場合にtrue
+#[cfg(feature = "alloc")]
+pub fn n_bit_subtractor(a: &[bool], b: &[bool]) -> (Vec<bool>, bool) {
+    let n = a.len().max(b.len());
+    
+    // bを0で埋めてaと同じ幅にしてから反転する
+    let b_extended: Vec<bool> = (0..n).map(|i| i < b.len() && b[i]).collect();
+    let (difference, carry) = n_bit_adder_with_carry(a, &not_n(&b_extended), true);
+    
+    // 最終桁上げが出なければ借りが発生している
+    (difference, not(carry))
+}
+
+/// 定数を入力と同じ幅のビット列にする
+///
+/// 戻り値は (bits, truncated)。truncated は k が n ビットに収まらず上位を捨てた場合にtrue
+#[cfg(feature = "alloc")]
+fn const_bits(k: u64, n: usize) -> (Vec<bool>, bool) {
+    let truncated = n < 64 && k >> n != 0;
+    (bits_from_u64_n(k, n), truncated)
+}
+
+/// 定数の加算
+///
+/// a + k を計算する。k は a と同じ幅のビット列にしてから n_bit_adder で加える
+///
+/// * `a` - nビット入力（LSB→MSB順）
+/// * `k` - 加える定数
+///
+/// 戻り値は (sum, carry) のタプル
+/// - sum: a と同じ幅の和（k の n ビットに収まらない上位は捨て、結果は 2^n で回り込む）
+/// - carry: 本当の和 a + k が n ビットに収まらなかった場合にtrue
+///   （加算器の桁上げに加えて、k 自体が n ビットに収まらない場合も含む）
+///
+/// 空の入力には空のVecを返し、k が0でなければ carry はtrue
+#[cfg(feature = "alloc")]
+pub fn add_const(a: &[bool], k: u64) -> (Vec<bool>, bool) {
+    let (k_bits, truncated) = const_bits(k, a.len());
+    let (sum, carry) = n_bit_adder(a, &k_bits);
+    (sum, or(carry, truncated))
+}
+
+/// 定数の減算
+///
+/// a - k を n_bit_subtractor で計算する。k の扱いは add_const と同じ
+///
+/// 戻り値は (difference, borrow) のタプル
+/// - difference: a と同じ幅の差（2^n で回り込む）
+/// - borrow: 符号なしとして a < k の場合にtrue（k が n ビットに収まらない場合は常にtrue）
+#[cfg(feature = "alloc")]
+pub fn sub_const(a: &[bool], k: u64) -> (Vec<bool>, bool) {
+    let (k_bits, truncated) = const_bits(k, a.len());
+    let (difference, borrow) = n_bit_subtractor(a, &k_bits);
+    (difference, or(borrow, truncated))
+}
+
+/// 平均（切り捨て）
+/// 
+/// (a + b) / 2 をオーバーフローなしで計算する
+/// 
+/// 加算の桁上げを捨てずに、和を1ビット右シフトする際のMSBとして取り込む
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の平均値（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn average_floor(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (sum, carry) = n_bit_adder(a, b);
+    
+    // 1ビット右シフト: 各ビットを1つ下の桁へ配線し、桁上げを最上位に入れる
+    let mut out = Vec::with_capacity(sum.len());
+    for &bit in sum.iter().skip(1) {
+        out.push(bit);
+    }
+    if !sum.is_empty() {
+        out.push(carry);
+    }
+    
+    out
+}
+
+/// 差の絶対値
+/// 
+/// 符号なし入力に対して |a - b| を計算する
+/// 
+/// a - b と b - a の両方を計算し、a - b の借りでどちらを出力するかをmuxで選択する
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の差の絶対値（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn abs_diff(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (a_minus_b, borrow) = n_bit_subtractor(a, b);
+    let (b_minus_a, _) = n_bit_subtractor(b, a);
+    
+    // borrow=1（a < b）なら b - a を選ぶ
+    mux_bus(borrow, &a_minus_b, &b_minus_a)
+}
+
+/// 飽和加算（符号なし）
+/// 
+/// a + b を計算し、桁上げが出た場合はすべて1のワード（その幅の最大値）に張り付かせる
+/// 
+/// 加算器の桁上げでmuxを制御し、桁上げがあれば飽和値を選択する
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の和（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn saturating_add_unsigned(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (sum, carry) = n_bit_adder(a, b);
+    let max = vec![true; sum.len()];
+    mux_bus(carry, &sum, &max)
+}
+
+/// 飽和加算（符号付き、2の補数）
+/// 
+/// a + b を計算し、符号付きオーバーフローが起きた場合は
+/// その幅の最小値（負方向）または最大値（正方向）に張り付かせる
+/// 
+/// オーバーフローは n_bit_adder_full の overflow で検出する。オーバーフローするのは
+/// aとbの符号が同じときだけなので、飽和の向きはaの符号で決まる
+/// 
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+/// 
+/// 長さが異なる場合は短い方を0で埋める（符号拡張はしない）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の和（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn saturating_add_signed(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let AdderResult { sum, overflow, .. } = n_bit_adder_full(a, b, false);
+    let n = sum.len();
+    if n == 0 {
+        return sum;
+    }
+    
+    let sign_a = if n - 1 < a.len() { a[n - 1] } else { false };
+    
+    // aが負なら最小値 100...0、aが正なら最大値 011...1
+    let mut saturated = vec![not(sign_a); n];
+    saturated[n - 1] = sign_a;
+    
+    mux_bus(overflow, &sum, &saturated)
+}
+
+/// 飽和減算（符号なし）
+/// 
+/// a - b を計算し、b > a の場合は0に張り付かせる
+/// 
+/// 減算器の借りでmuxを制御し、借りがあればすべて0のワードを選択する
+/// 
+/// * `a` - 被減数（LSB→MSB順）
+/// * `b` - 減数（LSB→MSB順）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の差（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn saturating_sub_unsigned(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (difference, borrow) = n_bit_subtractor(a, b);
+    let zero = vec![false; difference.len()];
+    mux_bus(borrow, &difference, &zero)
+}
+
+/// 飽和減算（符号付き、2の補数）
+/// 
+/// a - b を計算し、符号付きオーバーフローが起きた場合は
+/// その幅の最小値（負方向）または最大値（正方向）に張り付かせる
+/// 
+/// オーバーフローは「aとbの符号が異なり、かつ差の符号がaと異なる」ことで検出する
+/// 
+/// * `a` - 被減数（LSB→MSB順）
+/// * `b` - 減数（LSB→MSB順）
+/// 
+/// 長さが異なる場合は短い方を0で埋める（符号拡張はしない）
+/// 
+/// 戻り値は入力（長い方）と同じ幅の差（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn saturating_sub_signed(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (difference, _borrow) = n_bit_subtractor(a, b);
+    let n = difference.len();
+    if n == 0 {
+        return difference;
+    }
+    
+    let sign_a = if n - 1 < a.len() { a[n - 1] } else { false };
+    let sign_b = if n - 1 < b.len() { b[n - 1] } else { false };
+    let sign_d = difference[n - 1];
+    let overflow = and(xor(sign_a, sign_b), xor(sign_d, sign_a));
+    
+    // aが負なら最小値 100...0、aが正なら最大値 011...1
+    let mut saturated = vec![not(sign_a); n];
+    saturated[n - 1] = sign_a;
+    
+    mux_bus(overflow, &difference, &saturated)
+}
+
+#[cfg(feature = "alloc")]
+fn word16(bits: Vec<bool>) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(bits) {
+        *o = bit;
+    }
+    out
+}
+
+/// 16ビット飽和加算（符号なし）
+/// 
+/// saturating_add_unsigned の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn saturating_add16_unsigned(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    word16(saturating_add_unsigned(a, b))
+}
+
+/// 16ビット飽和加算（符号付き）
+/// 
+/// saturating_add_signed の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn saturating_add16_signed(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    word16(saturating_add_signed(a, b))
+}
+
+/// 16ビット飽和減算（符号なし）
+/// 
+/// saturating_sub_unsigned の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn saturating_sub16_unsigned(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    word16(saturating_sub_unsigned(a, b))
+}
+
+/// 16ビット飽和減算（符号付き）
+/// 
+/// saturating_sub_signed の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn saturating_sub16_signed(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    word16(saturating_sub_signed(a, b))
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
+    use crate::convert::{bits_from_i16, bits_from_u16, bits_from_u16_n, bits_from_u64_n, i16_from_bits, limbs_from_u64, to_signed, u16_from_bits, u16_from_bits_slice, u64_from_limbs};
+    use crate::testutil::XorShift;
 
     #[test]
     fn test_half_adder() {
@@ -133,6 +818,270 @@ mod tests {
         );
     }
     
+    #[test]
+    fn test_n_bit_adder_with_carry() {
+        let mut rng = XorShift::new(0xCA11);
+        for _ in 0..200 {
+            let a = bits_from_u64_n(rng.next_bits(12), 12);
+            let b = bits_from_u64_n(rng.next_bits(12), 12);
+
+            // a + b + 1 は、a + b をインクリメントした値と同じ
+            let (sum, carry) = n_bit_adder(&a, &b);
+            let (inc, inc_carry) = incrementer(&sum);
+            assert_eq!(n_bit_adder_with_carry(&a, &b, true), (inc, carry || inc_carry));
+            assert_eq!(n_bit_adder_with_carry(&a, &b, false), (sum, carry));
+        }
+    }
+
+    #[test]
+    fn test_n_bit_adder_with_carry_chains_to_16bit() {
+        let mut rng = XorShift::new(0x816);
+        for _ in 0..200 {
+            let x = rng.next_bits(16) as u16;
+            let y = rng.next_bits(16) as u16;
+            let (a, b) = (bits_from_u16(x), bits_from_u16(y));
+
+            // 下位8ビットの桁上げを上位8ビットの carry_in につなぐ
+            let (mut sum, low_carry) = n_bit_adder_with_carry(&a[..8], &b[..8], false);
+            let (high, carry) = n_bit_adder_with_carry(&a[8..], &b[8..], low_carry);
+            sum.extend(high);
+            assert_eq!((sum, carry), n_bit_adder(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_n_bit_adder_with_carry_mismatched_widths() {
+        // 6ビット + 3ビット + 1: 短い方を0で埋めて6ビットで計算する
+        for x in 0..64u64 {
+            for y in 0..8u64 {
+                let (sum, carry) = n_bit_adder_with_carry(&bits_from_u64_n(x, 6), &bits_from_u64_n(y, 3), true);
+                let total = x + y + 1;
+                assert_eq!((u16_from_bits_slice(&sum) as u64, carry), (total & 0x3F, total > 0x3F), "{} + {} + 1", x, y);
+            }
+        }
+        assert_eq!(n_bit_adder_with_carry(&[], &[], true), (vec![], true));
+    }
+
+    #[test]
+    fn test_add64_from_16_random() {
+        let mut rng = XorShift::new(0x6416);
+        for _ in 0..200 {
+            let (x, y) = (rng.next_bits(64), rng.next_bits(64));
+            let (sum, carry) = add64_from_16(&limbs_from_u64(x), &limbs_from_u64(y));
+            let (expected, overflowed) = x.overflowing_add(y);
+            assert_eq!((u64_from_limbs(&sum), carry), (expected, overflowed), "{:#x} + {:#x}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_add64_from_16_carries_across_limbs() {
+        // 桁上げがすべてのリムの境界を越えて最上位まで伝わる
+        let (sum, carry) = add64_from_16(&limbs_from_u64(u64::MAX), &limbs_from_u64(1));
+        assert_eq!((u64_from_limbs(&sum), carry), (0, true));
+
+        // 各リムの境界をちょうど1つずつ越える
+        for shift in [16, 32, 48] {
+            let x = (1u64 << shift) - 1;
+            let (sum, carry) = add64_from_16(&limbs_from_u64(x), &limbs_from_u64(1));
+            assert_eq!((u64_from_limbs(&sum), carry), (1u64 << shift, false), "shift {}", shift);
+        }
+
+        let (sum, carry) = add64_from_16(&limbs_from_u64(0x8000_0000_0000_0000), &limbs_from_u64(0x8000_0000_0000_0000));
+        assert_eq!((u64_from_limbs(&sum), carry), (0, true));
+    }
+
+    #[test]
+    fn test_kogge_stone_adder_exhaustive_8bit() {
+        for x in 0..256u64 {
+            for y in 0..256u64 {
+                let (a, b) = (bits_from_u64_n(x, 8), bits_from_u64_n(y, 8));
+                for carry_in in [false, true] {
+                    assert_eq!(
+                        kogge_stone_adder(&a, &b, carry_in),
+                        n_bit_adder_with_carry(&a, &b, carry_in),
+                        "{} + {} + {}",
+                        x,
+                        y,
+                        carry_in
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_kogge_stone_adder_random_wide() {
+        let mut rng = XorShift::new(0x4B53);
+        for width in [16, 32, 64] {
+            for _ in 0..200 {
+                let a = bits_from_u64_n(rng.next_bits(width), width);
+                let b = bits_from_u64_n(rng.next_bits(width), width);
+                let carry_in = rng.next_bits(1) == 1;
+                assert_eq!(kogge_stone_adder(&a, &b, carry_in), n_bit_adder_with_carry(&a, &b, carry_in));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kogge_stone_adder_odd_widths() {
+        // 2の累乗でない幅や、長さの異なる入力
+        let mut rng = XorShift::new(0x0DD5);
+        for (wa, wb) in [(1, 1), (3, 3), (5, 2), (7, 12), (13, 13), (33, 20)] {
+            for _ in 0..50 {
+                let a = bits_from_u64_n(rng.next_bits(wa), wa);
+                let b = bits_from_u64_n(rng.next_bits(wb), wb);
+                let carry_in = rng.next_bits(1) == 1;
+                assert_eq!(kogge_stone_adder(&a, &b, carry_in), n_bit_adder_with_carry(&a, &b, carry_in));
+            }
+        }
+        assert_eq!(kogge_stone_adder(&[], &[], true), (vec![], true));
+    }
+
+    #[test]
+    fn test_kogge_stone_adder_nand_count() {
+        use crate::backend::CountingBackend;
+
+        let counter = CountingBackend::new();
+        // 2の累乗でない幅（17, 24）も次の2の累乗まで広げずに数える
+        for (width, ripple, kogge_stone) in [(16, 240, 508), (17, 255, 553), (24, 360, 868), (64, 960, 2892)] {
+            counter.reset();
+            n_bit_adder_with_carry_with(&counter, &vec![false; width], &vec![true; width], false);
+            assert_eq!(counter.count(), ripple);
+
+            counter.reset();
+            kogge_stone_adder_with(&counter, &vec![false; width], &vec![true; width], false);
+            assert_eq!(counter.count(), kogge_stone, "width {}", width);
+        }
+    }
+
+    #[test]
+    fn test_add_into_matches_n_bit_adder() {
+        let mut rng = XorShift::new(0xADD1);
+        for _ in 0..200 {
+            let (wa, wb) = (rng.next_bits(5) as usize + 1, rng.next_bits(5) as usize + 1);
+            let a = bits_from_u64_n(rng.next_bits(wa), wa);
+            let b = bits_from_u64_n(rng.next_bits(wb), wb);
+            let carry_in = rng.next_bits(1) == 1;
+
+            let mut out = vec![false; wa.max(wb)];
+            let carry = add_into(&a, &b, carry_in, &mut out).unwrap();
+            assert_eq!((out, carry), n_bit_adder_with_carry(&a, &b, carry_in));
+        }
+    }
+
+    #[test]
+    fn test_add_into_longer_buffer() {
+        // 余った上位のビットは0にする（前の内容は残さない）
+        let mut out = [true; 12];
+        let carry = add_into(&bits_from_u64_n(200, 8), &bits_from_u64_n(100, 8), false, &mut out).unwrap();
+        assert_eq!((u16_from_bits_slice(&out), carry), (44, true));
+
+        let mut out = [true; 4];
+        assert_eq!(add_into(&[], &[], true, &mut out), Ok(true));
+        assert_eq!(out, [false; 4]);
+    }
+
+    #[test]
+    fn test_add_into_buffer_too_short() {
+        let mut out = [true; 7];
+        assert_eq!(
+            add_into(&bits_from_u64_n(1, 8), &bits_from_u64_n(1, 4), false, &mut out),
+            Err(BitSliceError::BufferTooShort { needed: 8, len: 7 })
+        );
+        // エラーの場合は out を変更しない
+        assert_eq!(out, [true; 7]);
+        assert_eq!(
+            BitSliceError::BufferTooShort { needed: 8, len: 7 }.to_string(),
+            "output buffer has 7 bits, needs 8"
+        );
+    }
+
+    #[test]
+    fn test_n_bit_adder_full_flags() {
+        // 4ビットで (carry, overflow) の4通りをすべて確かめる
+        let cases = [
+            (3u64, 2u64, false, false), // 3 + 2 = 5
+            (7, 1, false, true),        // 7 + 1: 符号付きで正 + 正 = 負
+            (15, 1, true, false),       // -1 + 1 = 0
+            (8, 8, true, true),         // -8 + -8: 符号付きで負 + 負 = 正
+        ];
+        for (x, y, carry, overflow) in cases {
+            let r = n_bit_adder_full(&bits_from_u64_n(x, 4), &bits_from_u64_n(y, 4), false);
+            assert_eq!(r.sum, bits_from_u64_n((x + y) & 0xF, 4), "{} + {}", x, y);
+            assert_eq!((r.carry, r.overflow), (carry, overflow), "{} + {}", x, y);
+        }
+
+        // 桁上げ入力による最上位ビットへの桁上げもオーバーフローに数える
+        let r = n_bit_adder_full(&bits_from_u64_n(7, 4), &bits_from_u64_n(0, 4), true);
+        assert_eq!((u16_from_bits_slice(&r.sum), r.carry, r.overflow), (8, false, true));
+        assert_eq!(n_bit_adder_full(&[], &[], true), AdderResult { sum: vec![], carry: true, overflow: false });
+    }
+
+    #[test]
+    fn test_n_bit_adder_full_matches_checked_arithmetic() {
+        for x in 0..=255u8 {
+            for y in 0..=255u8 {
+                let r = n_bit_adder_full(&bits_from_u64_n(x as u64, 8), &bits_from_u64_n(y as u64, 8), false);
+                assert_eq!(u16_from_bits_slice(&r.sum), x.wrapping_add(y) as u16);
+                assert_eq!(r.carry, x.checked_add(y).is_none(), "{} + {}", x, y);
+                assert_eq!(r.overflow, (x as i8).checked_add(y as i8).is_none(), "{} + {}", x as i8, y as i8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add16_matches_n_bit_adder() {
+        let mut rng = XorShift::new(0xADD16);
+        for _ in 0..4000 {
+            let a = bits_from_u16(rng.next_bits(16) as u16);
+            let b = bits_from_u16(rng.next_bits(16) as u16);
+            let carry_in = rng.next_bits(1) == 1;
+            let (sum, carry) = add16(&a, &b, carry_in);
+            assert_eq!((sum.to_vec(), carry), n_bit_adder_with_carry(&a, &b, carry_in));
+        }
+    }
+
+    #[test]
+    fn test_add16_corner_cases() {
+        let add = |x: u16, y: u16| {
+            let (sum, carry) = add16(&bits_from_u16(x), &bits_from_u16(y), false);
+            (u16_from_bits(&sum), carry)
+        };
+        assert_eq!(add(0, 0), (0, false));
+        assert_eq!(add(0xFFFF, 1), (0, true));
+        assert_eq!(add(0x7FFF, 1), (0x8000, false));
+        assert_eq!(add16(&bits_from_u16(0xFFFF), &bits_from_u16(0), true), (bits_from_u16(0), true));
+    }
+
+    #[test]
+    fn test_sum_words() {
+        assert_eq!(sum_words(&[]), (vec![], false));
+        assert_eq!(sum_words(&[bits_from_u64_n(0b1011, 4)]), (bits_from_u64_n(0b1011, 4), false));
+
+        let mut rng = XorShift::new(0x5E);
+        for _ in 0..20 {
+            let values: Vec<u64> = (0..10).map(|_| rng.next_bits(8)).collect();
+            let words: Vec<Vec<bool>> = values.iter().map(|&v| bits_from_u64_n(v, 8)).collect();
+            let total: u64 = values.iter().sum();
+            assert_eq!(sum_words(&words), (bits_from_u64_n(total & 0xFF, 8), total > 0xFF), "{:?}", values);
+        }
+
+        // 短いワードは0で埋める。途中で桁上げが出れば、最後の加算で出なくても carry はtrue
+        let words = [bits_from_u64_n(200, 8), bits_from_u64_n(3, 2), bits_from_u64_n(60, 6)];
+        assert_eq!(sum_words(&words), (bits_from_u64_n(7, 8), true));
+        assert_eq!(sum_words(&[bits_from_u64_n(1, 8), bits_from_u64_n(2, 8)]), (bits_from_u64_n(3, 8), false));
+    }
+
+    #[test]
+    fn test_popcount() {
+        for v in [0u16, 1, 0b1011, 0xFF, 0x8001, 0xFFFF] {
+            assert_eq!(u16_from_bits_slice(&popcount(&bits_from_u16(v))), v.count_ones() as u16, "v = {:#x}", v);
+        }
+        // 幅は入力数を表せるだけのビット数
+        assert_eq!(popcount(&[true; 16]).len(), 5);
+        assert_eq!(popcount(&[]), vec![false]);
+    }
+
     #[test]
     fn test_incrementer() {
         // 0 + 1 = 1
@@ -167,21 +1116,339 @@ mod tests {
     }
     
     #[test]
-    fn test_specific_additions() {
-        // 15 + 3 = 18 のテスト
-        let x = vec![true, true, true, true];  // 15（0b1111）
-        let y = vec![true, true];              // 3（0b11）
+    fn test_incrementer_carry_semantics() {
+        // 桁上げはすべてのビットが1のときだけ立つ
+        for n in [4, 8, 16] {
+            assert_eq!(incrementer(&vec![true; n]), (vec![false; n], true));
+            
+            // 0111...1 + 1 は桁上げなし（符号付きでは桁あふれだが、incrementerは報告しない）
+            let max = bits_from_u64_n((1 << (n - 1)) - 1, n);
+            assert_eq!(incrementer(&max), (bits_from_u64_n(1 << (n - 1), n), false));
+        }
+    }
+    
+    #[test]
+    fn test_incrementer_signed() {
+        for n in [4, 8, 16] {
+            // 0111...1 → 1000...0 は符号付きオーバーフロー
+            let max = bits_from_u64_n((1 << (n - 1)) - 1, n);
+            let (result, flags) = incrementer_signed(&max);
+            assert_eq!(result, bits_from_u64_n(1 << (n - 1), n));
+            assert_eq!(flags, IncFlags { carry: false, signed_overflow: true }, "n = {}", n);
+            
+            // すべて1（-1）→ 0 は桁上げのみ
+            let (result, flags) = incrementer_signed(&vec![true; n]);
+            assert_eq!(result, vec![false; n]);
+            assert_eq!(flags, IncFlags { carry: true, signed_overflow: false }, "n = {}", n);
+            
+            // 5 → 6 はどちらも立たない
+            let (_, flags) = incrementer_signed(&bits_from_u64_n(5, n));
+            assert_eq!(flags, IncFlags { carry: false, signed_overflow: false });
+        }
+    }
+    
+    #[test]
+    fn test_incrementer_wrapping_n() {
+        // プログラムカウンタの桁あふれ: 0xFFFF → 0x0000
+        let mut pc = [true; 16];
+        pc = incrementer_wrapping_n(&pc);
+        assert_eq!(pc, [false; 16]);
+        pc = incrementer_wrapping_n(&pc);
+        assert_eq!(u16_from_bits(&pc), 1);
         
-        println!("Adding x: {:?} and y: {:?}", x, y);
+        // 4ビットのカウンタを1周させる
+        let mut counter = [false; 4];
+        for i in 1..=16u16 {
+            counter = incrementer_wrapping_n(&counter);
+            assert_eq!(u16_from_bits_slice(&counter), i % 16);
+        }
+    }
+    
+    #[test]
+    fn test_decrementer() {
+        // 1 - 1 = 0
+        assert_eq!(decrementer(&[true]), (vec![false], false));
         
-        let (sum, carry) = n_bit_adder(&x, &y);
+        // 0 - 1 = 1111（アンダーフロー）
+        assert_eq!(decrementer(&[false; 4]), (vec![true; 4], true));
+
+        // 100 - 1 = 011
+        assert_eq!(decrementer(&bits_from_u64_n(0b100, 3)), (bits_from_u64_n(0b011, 3), false));
+
+        // 空の入力
+        assert_eq!(decrementer(&[]), (vec![], true));
+    }
+
+    #[test]
+    fn test_decrementer_16bit_sweep() {
+        for v in (0..=u16::MAX).step_by(7).chain([0, 1, 0x8000, u16::MAX]) {
+            let (result, underflow) = decrementer(&bits_from_u16(v));
+            assert_eq!(u16_from_bits_slice(&result), v.wrapping_sub(1), "v = {:#06x}", v);
+            assert_eq!(result.len(), 16);
+            assert_eq!(underflow, v == 0);
+        }
+    }
+    
+    #[test]
+    fn test_negate() {
+        // -0 = 0
+        assert_eq!(negate(&[false; 4]), (vec![false; 4], false));
+
+        // -1 = 1111
+        assert_eq!(negate(&bits_from_u64_n(1, 4)), (vec![true; 4], false));
+
+        // 4ビットの最小値 1000（-8）は反転しても 1000 のまま
+        assert_eq!(negate(&bits_from_u64_n(0b1000, 4)), (bits_from_u64_n(0b1000, 4), true));
+        assert_eq!(negate(&[true]), (vec![true], true));
+        assert_eq!(negate(&[]), (vec![], false));
+
+        // 2回反転すると元に戻る（最小値を除く）
+        for v in 0..16u64 {
+            let a = bits_from_u64_n(v, 4);
+            let (once, overflow) = negate(&a);
+            assert_eq!(to_signed(&once), if overflow { -8 } else { -to_signed(&a) });
+            assert_eq!(negate(&once).0, a);
+            assert_eq!(overflow, v == 0b1000);
+        }
+    }
+
+    #[test]
+    fn test_n_bit_subtractor() {
+        // 0 - 1 = 1111（借りあり）
+        assert_eq!(n_bit_subtractor(&[false; 4], &[true]), (vec![true; 4], true));
         
-        println!("Sum: {:?}, Carry: {}", sum, carry);
+        // 5 - 3 = 2
+        assert_eq!(
+            n_bit_subtractor(&[true, false, true], &[true, true, false]),
+            (vec![false, true, false], false)
+        );
+    }
+
+    #[test]
+    fn test_n_bit_subtractor_random_u16() {
+        let mut rng = XorShift::new(0x5B);
+        for _ in 0..500 {
+            let x = rng.next_bits(16) as u16;
+            let y = rng.next_bits(16) as u16;
+            let (difference, borrow) = n_bit_subtractor(&bits_from_u16(x), &bits_from_u16(y));
+            assert_eq!(u16_from_bits_slice(&difference), x.wrapping_sub(y), "{} - {}", x, y);
+            assert_eq!(borrow, x < y, "{} - {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_n_bit_subtractor_edge_cases() {
+        // 0 - 1 は16ビットでも全ビット1になり、借りが出る
+        assert_eq!(n_bit_subtractor(&bits_from_u16(0), &bits_from_u16(1)), (vec![true; 16], true));
+
+        // 同じ値どうしの差は0で、借りは出ない
+        for v in [0u16, 1, 0x8000, 0xFFFF] {
+            assert_eq!(n_bit_subtractor(&bits_from_u16(v), &bits_from_u16(v)), (vec![false; 16], false));
+        }
+
+        // 5ビット - 3ビット: 短い方を0で埋めて5ビットで計算する
+        for x in 0..32u64 {
+            for y in 0..8u64 {
+                let (difference, borrow) = n_bit_subtractor(&bits_from_u64_n(x, 5), &bits_from_u64_n(y, 3));
+                assert_eq!(difference, bits_from_u64_n(x.wrapping_sub(y) & 0x1F, 5), "{} - {}", x, y);
+                assert_eq!(borrow, x < y);
+            }
+        }
+        // 3ビット - 5ビットでも幅は5ビット
+        assert_eq!(n_bit_subtractor(&bits_from_u64_n(2, 3), &bits_from_u64_n(20, 5)), (bits_from_u64_n(14, 5), true));
+    }
+
+    #[test]
+    fn test_add_const_identity_and_increment() {
+        for n in [1, 4, 8, 16] {
+            for v in [0u64, 1, (1 << n) - 1, (1 << (n - 1)) - 1] {
+                let a = bits_from_u64_n(v, n);
+                assert_eq!(add_const(&a, 0), (a.clone(), false), "n = {}, v = {}", n, v);
+                assert_eq!(sub_const(&a, 0), (a.clone(), false), "n = {}, v = {}", n, v);
+                assert_eq!(add_const(&a, 1), n_bit_adder_with_carry(&a, &[], true), "n = {}, v = {}", n, v);
+            }
+        }
+        assert_eq!(add_const(&[], 0), (vec![], false));
+        assert_eq!(add_const(&[], 1), (vec![], true));
+    }
+
+    #[test]
+    fn test_add_const_wide_constant() {
+        // 4ビットに 20（10100）を加えると下位4ビットの 4 だけが加わり、carry が立つ
+        assert_eq!(add_const(&bits_from_u64_n(3, 4), 20), (bits_from_u64_n(7, 4), true));
+        assert_eq!(sub_const(&bits_from_u64_n(15, 4), 16), (bits_from_u64_n(15, 4), true));
+        // 64ビットを超える幅では、k の上位は0として扱う
+        assert_eq!(add_const(&bits_from_u64_n(0, 70), u64::MAX), (bits_from_u64_n(u64::MAX, 70), false));
+    }
+
+    #[test]
+    fn test_add_const_matches_wrapping() {
+        let mut rng = XorShift::new(0xC0457);
+        for _ in 0..200 {
+            let (v, k) = (rng.next_bits(16), rng.next_bits(16));
+            let (sum, carry) = add_const(&bits_from_u16(v as u16), k);
+            assert_eq!((u16_from_bits_slice(&sum) as u64, carry), ((v + k) & 0xFFFF, v + k > 0xFFFF), "{} + {}", v, k);
+            let (diff, borrow) = sub_const(&bits_from_u16(v as u16), k);
+            assert_eq!((u16_from_bits_slice(&diff) as u64, borrow), (v.wrapping_sub(k) & 0xFFFF, v < k), "{} - {}", v, k);
+        }
+    }
+    
+    #[test]
+    fn test_saturating_sub_unsigned_exhaustive() {
+        for x in 0..32u8 {
+            for y in 0..32u8 {
+                let diff = saturating_sub_unsigned(&bits_from_u64_n(x as u64, 5), &bits_from_u64_n(y as u64, 5));
+                assert_eq!(diff.len(), 5);
+                assert_eq!(u16_from_bits_slice(&diff), x.saturating_sub(y) as u16, "x = {}, y = {}", x, y);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_saturating_sub_signed_exhaustive() {
+        // 5ビットの符号付き範囲は -16..=15
+        for x in -16..16i64 {
+            for y in -16..16i64 {
+                let a = bits_from_u64_n(x as u64 & 0x1F, 5);
+                let b = bits_from_u64_n(y as u64 & 0x1F, 5);
+                let diff = saturating_sub_signed(&a, &b);
+                assert_eq!(to_signed(&diff), (x - y).clamp(-16, 15), "x = {}, y = {}", x, y);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_saturating_sub_boundaries() {
+        // 符号なし 0 - 1 = 0
+        assert_eq!(saturating_sub_unsigned(&[false; 5], &bits_from_u64_n(1, 5)), vec![false; 5]);
+        
+        // 符号付き MIN - 1 = MIN
+        let min = bits_from_u64_n(0b10000, 5);
+        assert_eq!(saturating_sub_signed(&min, &bits_from_u64_n(1, 5)), min);
+        
+        // 符号付き MAX - (-1) = MAX
+        let max = bits_from_u64_n(0b01111, 5);
+        assert_eq!(saturating_sub_signed(&max, &[true; 5]), max);
+    }
+    
+    #[test]
+    fn test_saturating_add_exhaustive_4bit() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let sum = saturating_add_unsigned(&bits_from_u64_n(x, 4), &bits_from_u64_n(y, 4));
+                assert_eq!(u16_from_bits_slice(&sum) as u64, (x + y).min(15), "x = {}, y = {}", x, y);
+            }
+        }
+        // 4ビットの符号付き範囲は -8..=7
+        for x in -8..8i64 {
+            for y in -8..8i64 {
+                let a = bits_from_u64_n(x as u64 & 0xF, 4);
+                let b = bits_from_u64_n(y as u64 & 0xF, 4);
+                let sum = saturating_add_signed(&a, &b);
+                assert_eq!(sum.len(), 4);
+                assert_eq!(to_signed(&sum), (x + y).clamp(-8, 7), "x = {}, y = {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_saturating16_boundaries() {
+        let u = bits_from_u16;
+        let s = bits_from_i16;
+        let i = |b: [bool; 16]| i16_from_bits(&b);
+        let w = |b: [bool; 16]| u16_from_bits(&b);
+
+        assert_eq!(w(saturating_add16_unsigned(&u(0xFFFE), &u(1))), 0xFFFF);
+        assert_eq!(w(saturating_add16_unsigned(&u(0xFFFF), &u(1))), 0xFFFF);
+        assert_eq!(w(saturating_add16_unsigned(&u(0x8000), &u(0x8000))), 0xFFFF);
+        assert_eq!(w(saturating_sub16_unsigned(&u(1), &u(1))), 0);
+        assert_eq!(w(saturating_sub16_unsigned(&u(0), &u(1))), 0);
+
+        assert_eq!(i(saturating_add16_signed(&s(32766), &s(1))), 32767);
+        assert_eq!(i(saturating_add16_signed(&s(32767), &s(1))), i16::MAX);
+        assert_eq!(i(saturating_add16_signed(&s(-32767), &s(-1))), -32768);
+        assert_eq!(i(saturating_add16_signed(&s(-32768), &s(-1))), i16::MIN);
+        assert_eq!(i(saturating_add16_signed(&s(i16::MAX), &s(i16::MIN))), -1);
+        assert_eq!(i(saturating_sub16_signed(&s(i16::MIN), &s(1))), i16::MIN);
+        assert_eq!(i(saturating_sub16_signed(&s(i16::MAX), &s(-1))), i16::MAX);
+        assert_eq!(i(saturating_sub16_signed(&s(-32767), &s(1))), -32768);
+    }
+
+    #[test]
+    fn test_average_floor_exhaustive() {
+        // 6ビットの全組み合わせ（a + bが6ビットを超える場合も含む）
+        for x in 0..64u64 {
+            for y in 0..64u64 {
+                let avg = average_floor(&bits_from_u64_n(x, 6), &bits_from_u64_n(y, 6));
+                assert_eq!(avg.len(), 6);
+                assert_eq!(u16_from_bits_slice(&avg) as u64, (x + y) / 2, "x = {}, y = {}", x, y);
+            }
+        }
         
+        // 63 + 63 = 126 は6ビットに収まらないが、平均は63
+        assert_eq!(average_floor(&[true; 6], &[true; 6]), vec![true; 6]);
+    }
+    
+    #[test]
+    fn test_abs_diff_exhaustive() {
+        for x in 0..64u64 {
+            for y in 0..64u64 {
+                let diff = abs_diff(&bits_from_u64_n(x, 6), &bits_from_u64_n(y, 6));
+                assert_eq!(diff.len(), 6);
+                assert_eq!(u16_from_bits_slice(&diff) as u64, x.abs_diff(y), "x = {}, y = {}", x, y);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_specific_additions() {
+        // 15 + 3 = 18 のテスト
+        let x = bits_from_u16_n(15, 4);  // 15（0b1111）
+        let y = bits_from_u16_n(3, 2);   // 3（0b11）
+        let (sum, carry) = n_bit_adder(&x, &y);
+
         // 15 + 3 = 18（0b10010）
         // この場合、4ビットで表現すると「0010」（上位ビットの桁上がりはcarryに入る）
         // なので、sum = [0, 1, 0, 0], carry = true が期待値
-        assert_eq!(sum, vec![false, true, false, false]);
+        assert_eq!(sum, bits_from_u16_n(2, 4));
         assert_eq!(carry, true);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_n_bit_adder_16bit_sweep() {
+        let values = [0x0000u16, 0x0001, 0x00FF, 0x1234, 0x7FFF, 0x8000, 0xFFFF];
+        for &a in &values {
+            for &b in &values {
+                let (sum, carry) = n_bit_adder(&bits_from_u16(a), &bits_from_u16(b));
+                assert_eq!(u16_from_bits_slice(&sum), a.wrapping_add(b), "{:#06x} + {:#06x}", a, b);
+                assert_eq!(carry, a.checked_add(b).is_none(), "{:#06x} + {:#06x}", a, b);
+            }
+        }
+    }
+
+    fn check_adder_const_matches<const N: usize>(rng: &mut XorShift) {
+        for _ in 0..64 {
+            let a: [bool; N] = core::array::from_fn(|_| rng.next_bits(1) == 1);
+            let b: [bool; N] = core::array::from_fn(|_| rng.next_bits(1) == 1);
+            let (sum, carry) = n_bit_adder_const(&a, &b);
+            assert_eq!((sum.to_vec(), carry), n_bit_adder(&a, &b), "N = {}", N);
+
+            let carry_in = rng.next_bits(1) == 1;
+            let (sum, carry, overflow) = n_bit_adder_full_const(&a, &b, carry_in);
+            let r = n_bit_adder_full(&a, &b, carry_in);
+            assert_eq!((sum.to_vec(), carry, overflow), (r.sum, r.carry, r.overflow), "N = {}", N);
+        }
+    }
+
+    #[test]
+    fn test_n_bit_adder_const_matches_slice() {
+        let mut rng = XorShift::new(0xADD);
+        check_adder_const_matches::<1>(&mut rng);
+        check_adder_const_matches::<8>(&mut rng);
+        check_adder_const_matches::<16>(&mut rng);
+        check_adder_const_matches::<32>(&mut rng);
+
+        // すべて1 + 1 は0になり、桁上げが出る
+        assert_eq!(n_bit_adder_const(&[true; 8], &[true, false, false, false, false, false, false, false]), ([false; 8], true));
+    }
+}
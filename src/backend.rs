@@ -0,0 +1,184 @@
+use core::cell::Cell;
+
+/// ゲートの実装方式（バックエンド）
+///
+/// 2入力 NAND だけを実装すれば、他のゲートは lib.rs と同じ NAND 構成の
+/// デフォルト実装で得られる。NOR を基本にしたり、故障を注入したりする場合は
+/// このトレイトを実装したバックエンドを回路の `_with` 版の関数に渡す
+pub trait GateBackend {
+    /// 2入力 NAND
+    fn nand(&self, a: bool, b: bool) -> bool;
+
+    /// NOT  (￢A) = NAND(A, A)
+    fn not(&self, a: bool) -> bool {
+        self.nand(a, a)
+    }
+
+    /// AND  (A · B) = NOT (NAND(A, B))
+    fn and(&self, a: bool, b: bool) -> bool {
+        let n = self.nand(a, b);
+        self.nand(n, n)
+    }
+
+    /// OR   (A + B) = NAND(NOT A, NOT B)
+    fn or(&self, a: bool, b: bool) -> bool {
+        let na = self.nand(a, a);
+        let nb = self.nand(b, b);
+        self.nand(na, nb)
+    }
+
+    /// XOR  (A ⊕ B) = NAND(NAND(A, NAND(A, B)), NAND(B, NAND(A, B)))
+    fn xor(&self, a: bool, b: bool) -> bool {
+        let t1 = self.nand(a, b);
+        let t2 = self.nand(a, t1);
+        let t3 = self.nand(b, t1);
+        self.nand(t2, t3)
+    }
+
+    /// 2:1 マルチプレクサ（sel が 0 なら a、1 なら b）
+    fn mux(&self, sel: bool, a: bool, b: bool) -> bool {
+        let not_sel = self.not(sel);
+        let a_and_not_sel = self.and(a, not_sel);
+        let b_and_sel = self.and(b, sel);
+        self.or(a_and_not_sel, b_and_sel)
+    }
+
+    /// 1:2 デマルチプレクサ（sel=0→(d,0), sel=1→(0,d)）
+    fn demux(&self, sel: bool, d: bool) -> (bool, bool) {
+        let not_sel = self.not(sel);
+        (self.and(d, not_sel), self.and(d, sel))
+    }
+}
+
+/// 標準のバックエンド（crate::nand をそのまま使う）
+///
+/// `_with` 版でない通常の関数は、すべてこのバックエンドを使う
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NandBackend;
+
+impl GateBackend for NandBackend {
+    fn nand(&self, a: bool, b: bool) -> bool {
+        crate::nand(a, b)
+    }
+}
+
+/// NAND の評価回数を数えるバックエンド
+#[derive(Debug, Default)]
+pub struct CountingBackend {
+    count: Cell<usize>,
+}
+
+impl CountingBackend {
+    pub fn new() -> Self {
+        CountingBackend { count: Cell::new(0) }
+    }
+
+    /// これまでに評価した NAND の数
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+
+    /// カウンタを0に戻す
+    pub fn reset(&self) {
+        self.count.set(0);
+    }
+}
+
+impl GateBackend for CountingBackend {
+    fn nand(&self, a: bool, b: bool) -> bool {
+        self.count.set(self.count.get() + 1);
+        crate::nand(a, b)
+    }
+}
+
+/// 故障を注入するバックエンド
+///
+/// 0始まりで数えて `fault_at` 番目に評価される NAND の出力だけを反転させる
+/// （一過性の1ビット故障のモデル）
+#[derive(Debug)]
+pub struct FaultyBackend {
+    fault_at: usize,
+    count: Cell<usize>,
+}
+
+impl FaultyBackend {
+    pub fn new(fault_at: usize) -> Self {
+        FaultyBackend { fault_at, count: Cell::new(0) }
+    }
+}
+
+impl GateBackend for FaultyBackend {
+    fn nand(&self, a: bool, b: bool) -> bool {
+        let index = self.count.get();
+        self.count.set(index + 1);
+        let out = crate::nand(a, b);
+        if index == self.fault_at { !out } else { out }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::contract::*;
+    use crate::adder::{full_adder, full_adder_with, n_bit_adder, n_bit_adder_with};
+    use crate::convert::bits_from_u64_n;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_default_gates_match_tables() {
+        let b = NandBackend;
+        assert_eq!(check_contract1(|a| b.not(a), &NOT_TABLE), Ok(()));
+        assert_eq!(check_contract2(|x, y| b.and(x, y), &AND_TABLE), Ok(()));
+        assert_eq!(check_contract2(|x, y| b.or(x, y), &OR_TABLE), Ok(()));
+        assert_eq!(check_contract2(|x, y| b.xor(x, y), &XOR_TABLE), Ok(()));
+        assert_eq!(check_contract3(|s, x, y| b.mux(s, x, y), &MUX_TABLE), Ok(()));
+        assert_eq!(check_contract_demux(|s, d| b.demux(s, d), &DEMUX_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn test_full_adder_standard_backend() {
+        for v in 0..8 {
+            let (a, b, c) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+            assert_eq!(full_adder_with(&NandBackend, a, b, c), full_adder(a, b, c));
+        }
+
+        let mut rng = XorShift::new(0xBAC4);
+        for _ in 0..100 {
+            let a = bits_from_u64_n(rng.next_bits(16), 16);
+            let b = bits_from_u64_n(rng.next_bits(16), 16);
+            assert_eq!(n_bit_adder_with(&NandBackend, &a, &b), n_bit_adder(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_counting_backend() {
+        // 半加算器 = XOR(4) + AND(2)、全加算器 = 半加算器 × 2 + OR(3) = 15
+        let counter = CountingBackend::new();
+        full_adder_with(&counter, true, false, true);
+        assert_eq!(counter.count(), 15);
+
+        counter.reset();
+        n_bit_adder_with(&counter, &[false; 4], &[true; 4]);
+        assert_eq!(counter.count(), 60);
+    }
+
+    #[test]
+    fn test_faulty_backend_propagates() {
+        // 最初の NAND（a, b の XOR の先頭）を反転させると、1 + 0 + 0 の和が誤る
+        let faulty = FaultyBackend::new(0);
+        assert_ne!(full_adder_with(&faulty, true, false, false), full_adder(true, false, false));
+
+        // 故障位置がすべての NAND より後なら、結果は正しいまま
+        let late = FaultyBackend::new(15);
+        assert_eq!(full_adder_with(&late, true, false, false), full_adder(true, false, false));
+
+        // いずれかの位置の故障は、少なくとも1つの入力で出力に現れる
+        for fault_at in 0..15 {
+            let observable = (0..8).any(|v| {
+                let (a, b, c) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+                full_adder_with(&FaultyBackend::new(fault_at), a, b, c) != full_adder(a, b, c)
+            });
+            assert!(observable, "fault at NAND {} never reaches the outputs", fault_at);
+        }
+    }
+}
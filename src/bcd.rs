@@ -0,0 +1,154 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{and, or};
+use crate::adder::n_bit_adder_with_carry;
+
+/// BCD 加算のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BcdError {
+    /// 幅が4の倍数ではない（10進の桁に分けられない）
+    NotDigitMultiple { width: usize },
+}
+
+impl fmt::Display for BcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BcdError::NotDigitMultiple { width } => {
+                write!(f, "width {} is not a multiple of 4", width)
+            }
+        }
+    }
+}
+
+impl core::error::Error for BcdError {}
+
+/// BCD（2進化10進数）の1桁の加算器
+///
+/// 4ビットの2進加算の結果が9を超えたら6（0110）を加えて補正する、古典的な回路
+/// 補正が必要なのは、2進加算の桁上げが出たとき、または和が 1010〜1111 のとき
+/// （s3 AND (s2 OR s1)）で、このとき10進の桁上げも出る
+///
+/// * `a`, `b` - BCDの1桁（LSB→MSB順、0〜9）
+/// * `carry_in` - 下の桁からの10進の桁上げ
+///
+/// 戻り値は (digit, carry_out) のタプル
+///
+/// 入力の桁が9を超える場合は検査せず、同じ回路をそのまま通した結果を返す
+/// （値は決定的だが、10進数としての意味はない）
+pub fn bcd_digit_adder(a: &[bool; 4], b: &[bool; 4], carry_in: bool) -> ([bool; 4], bool) {
+    let (sum, carry) = n_bit_adder_with_carry(a, b, carry_in);
+
+    // 補正の検出: carry OR (s3 AND (s2 OR s1))
+    let correct = or(carry, and(sum[3], or(sum[2], sum[1])));
+
+    // 補正が必要なら 0110 を加える（この加算の桁上げは捨てる）
+    let six = [false, correct, correct, false];
+    let (corrected, _) = n_bit_adder_with_carry(&sum, &six, false);
+
+    let mut digit = [false; 4];
+    for (d, bit) in digit.iter_mut().zip(corrected) {
+        *d = bit;
+    }
+    (digit, correct)
+}
+
+/// 複数桁のBCD加算器
+///
+/// bcd_digit_adder を下の桁から順に並べ、10進の桁上げを次の桁へつなぐ
+///
+/// * `a`, `b` - BCDの数（LSB→MSB順。[0..4] が1の位、[4..8] が10の位、...）
+///
+/// 長さが異なる場合は短い方を0で埋める。幅が4の倍数でなければエラーを返す
+///
+/// 戻り値は (sum, carry) のタプル
+/// - sum: 入力（長い方）と同じ桁数の和
+/// - carry: 最上位の桁からの10進の桁上げ
+pub fn bcd_adder(a: &[bool], b: &[bool]) -> Result<(Vec<bool>, bool), BcdError> {
+    for width in [a.len(), b.len()] {
+        if !width.is_multiple_of(4) {
+            return Err(BcdError::NotDigitMultiple { width });
+        }
+    }
+
+    let n = a.len().max(b.len());
+    let digit = |x: &[bool], i: usize| -> [bool; 4] {
+        core::array::from_fn(|j| x.get(i * 4 + j).copied().unwrap_or(false))
+    };
+
+    let mut sum = Vec::with_capacity(n);
+    let mut carry = false;
+    for i in 0..n / 4 {
+        let (d, carry_out) = bcd_digit_adder(&digit(a, i), &digit(b, i), carry);
+        sum.extend(d);
+        carry = carry_out;
+    }
+    Ok((sum, carry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u64_n, u64_from_bits_slice};
+
+    fn bcd(v: u64, digits: usize) -> Vec<bool> {
+        (0..digits).flat_map(|i| bits_from_u64_n(v / 10u64.pow(i as u32) % 10, 4)).collect()
+    }
+
+    fn decimal(b: &[bool]) -> u64 {
+        b.chunks(4).rev().fold(0, |acc, d| acc * 10 + u64_from_bits_slice(d))
+    }
+
+    fn digit(v: u64) -> [bool; 4] {
+        core::array::from_fn(|i| (v >> i) & 1 == 1)
+    }
+
+    #[test]
+    fn test_bcd_digit_adder_basic() {
+        // 9 + 1 = 10 → 桁は0で桁上げ
+        assert_eq!(bcd_digit_adder(&digit(9), &digit(1), false), (digit(0), true));
+        // 5 + 5 = 10
+        assert_eq!(bcd_digit_adder(&digit(5), &digit(5), false), (digit(0), true));
+        // 4 + 3 = 7 は補正しない
+        assert_eq!(bcd_digit_adder(&digit(4), &digit(3), false), (digit(7), false));
+        // 9 + 9 + 1 = 19
+        assert_eq!(bcd_digit_adder(&digit(9), &digit(9), true), (digit(9), true));
+    }
+
+    #[test]
+    fn test_bcd_digit_adder_all_pairs() {
+        for x in 0..10u64 {
+            for y in 0..10u64 {
+                for carry_in in [false, true] {
+                    let total = x + y + carry_in as u64;
+                    let (d, carry) = bcd_digit_adder(&digit(x), &digit(y), carry_in);
+                    assert_eq!((u64_from_bits_slice(&d), carry), (total % 10, total >= 10), "{} + {} + {}", x, y, carry_in);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bcd_adder_multi_digit() {
+        // 99 + 1 = 100: 1の位の桁上げが10の位を通って桁あふれする
+        let (sum, carry) = bcd_adder(&bcd(99, 2), &bcd(1, 2)).unwrap();
+        assert_eq!((decimal(&sum), carry), (0, true));
+
+        // 短い方は0で埋める
+        let (sum, carry) = bcd_adder(&bcd(999, 3), &bcd(1, 1)).unwrap();
+        assert_eq!((sum.len(), decimal(&sum), carry), (12, 0, true));
+
+        for (x, y) in [(0, 0), (1234, 8766), (4321, 5678), (5000, 4999)] {
+            let (sum, carry) = bcd_adder(&bcd(x, 4), &bcd(y, 4)).unwrap();
+            assert_eq!(decimal(&sum) + 10_000 * carry as u64, x + y, "{} + {}", x, y);
+        }
+        assert_eq!(bcd_adder(&[], &[]), Ok((vec![], false)));
+    }
+
+    #[test]
+    fn test_bcd_adder_width_error() {
+        assert_eq!(bcd_adder(&bits_from_u64_n(0, 6), &bcd(1, 1)), Err(BcdError::NotDigitMultiple { width: 6 }));
+        assert_eq!(bcd_adder(&bcd(1, 1), &bits_from_u64_n(0, 3)), Err(BcdError::NotDigitMultiple { width: 3 }));
+        assert_eq!(BcdError::NotDigitMultiple { width: 6 }.to_string(), "width 6 is not a multiple of 4");
+    }
+}
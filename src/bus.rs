@@ -0,0 +1,1179 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{and, demux, mux, xor};
+use crate::backend::{GateBackend, NandBackend};
+#[cfg(feature = "alloc")]
+use crate::{and3, nand3, nor, or, or3, xnor, xor3};
+
+/// nビット NOT
+///
+/// nビット入力の各ビットに `not` ゲートを適用する
+///
+/// * `a` - nビット入力（LSB→MSB順）
+///
+/// 出力の長さは入力と同じ（空の入力には空のVecを返す）
+#[cfg(feature = "alloc")]
+pub fn not_n(a: &[bool]) -> Vec<bool> {
+    not_n_with(&NandBackend, a)
+}
+
+/// nビット NOT（バックエンド指定版）
+#[cfg(feature = "alloc")]
+pub fn not_n_with<G: GateBackend>(g: &G, a: &[bool]) -> Vec<bool> {
+    a.iter().map(|&bit| g.not(bit)).collect()
+}
+
+/// 16ビット NOT
+///
+/// not_n の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn not16(a: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(not_n(a)) {
+        *o = bit;
+    }
+    out
+}
+
+/// 条件付き反転
+///
+/// ctrl が1ならnビット入力のすべてのビットを反転し、0ならそのまま出力する
+/// 各ビットを xor(ctrl, bit) で計算する（ALUの nx, ny, no と同じ回路）
+///
+/// * `ctrl` - 反転制御
+/// * `a` - nビット入力（LSB→MSB順）
+///
+/// 出力の長さは入力と同じ
+#[cfg(feature = "alloc")]
+pub fn cond_invert(ctrl: bool, a: &[bool]) -> Vec<bool> {
+    cond_invert_with(&NandBackend, ctrl, a)
+}
+
+/// 条件付き反転（バックエンド指定版）
+#[cfg(feature = "alloc")]
+pub fn cond_invert_with<G: GateBackend>(g: &G, ctrl: bool, a: &[bool]) -> Vec<bool> {
+    a.iter().map(|&bit| g.xor(ctrl, bit)).collect()
+}
+
+/// 16ビット 条件付き反転
+///
+/// cond_invert の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn cond_invert16(ctrl: bool, a: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(cond_invert(ctrl, a)) {
+        *o = bit;
+    }
+    out
+}
+
+/// nビット AND
+///
+/// 2つのnビット入力の各ビットに `and` ゲートを適用する
+///
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+#[cfg(feature = "alloc")]
+pub fn and_n(a: &[bool], b: &[bool]) -> Vec<bool> {
+    and_n_with(&NandBackend, a, b)
+}
+
+/// nビット AND（バックエンド指定版）
+#[cfg(feature = "alloc")]
+pub fn and_n_with<G: GateBackend>(g: &G, a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(g.and(bit_a, bit_b));
+    }
+
+    out
+}
+
+/// 16ビット AND
+///
+/// and_n の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn and16(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(and_n(a, b)) {
+        *o = bit;
+    }
+    out
+}
+
+/// 固定幅の AND（ヒープ確保なし）
+///
+/// and_n と同じく各ビットに `and` ゲートを適用するが、幅Nをコンパイル時に決める
+pub fn and_bus<const N: usize>(a: &[bool; N], b: &[bool; N]) -> [bool; N] {
+    core::array::from_fn(|i| and(a[i], b[i]))
+}
+
+/// nビット OR
+///
+/// 2つのnビット入力の各ビットに `or` ゲートを適用する
+///
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+#[cfg(feature = "alloc")]
+pub fn or_n(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(or(bit_a, bit_b));
+    }
+
+    out
+}
+
+/// 16ビット OR
+///
+/// or_n の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn or16(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(or_n(a, b)) {
+        *o = bit;
+    }
+    out
+}
+
+/// nビット NOR
+///
+/// 2つのnビット入力の各ビットに `nor` ゲートを適用する
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+#[cfg(feature = "alloc")]
+pub fn nor_n(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(nor(bit_a, bit_b));
+    }
+
+    out
+}
+
+/// nビット XOR
+///
+/// 2つのnビット入力の各ビットに `xor` ゲートを適用する
+///
+/// * `a` - 1つ目のnビット入力（LSB→MSB順）
+/// * `b` - 2つ目のnビット入力（LSB→MSB順）
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+#[cfg(feature = "alloc")]
+pub fn xor_n(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(xor(bit_a, bit_b));
+    }
+
+    out
+}
+
+/// nビット XNOR（ビットごとの一致）
+///
+/// 2つのnビット入力の各ビットに `xnor` ゲートを適用する
+/// 出力がすべて1になるのは、2つの入力が等しいときだけ
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い方を0で埋める
+#[cfg(feature = "alloc")]
+pub fn xnor_n(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(xnor(bit_a, bit_b));
+    }
+
+    out
+}
+
+/// 16ビット XOR
+///
+/// xor_n の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn xor16(a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(xor_n(a, b)) {
+        *o = bit;
+    }
+    out
+}
+
+/// 3入力ゲートを3つのnビット入力の各ビットに適用する
+///
+/// 長さが異なる場合は、n_bit_adderと同様に短い入力を0で埋める
+#[cfg(feature = "alloc")]
+fn lanes3(a: &[bool], b: &[bool], c: &[bool], gate: fn(bool, bool, bool) -> bool) -> Vec<bool> {
+    let n = a.len().max(b.len()).max(c.len());
+    let bit = |x: &[bool], i: usize| if i < x.len() { x[i] } else { false };
+    (0..n).map(|i| gate(bit(a, i), bit(b, i), bit(c, i))).collect()
+}
+
+/// nビット 3入力 AND（各ビットに `and3` を適用、短い入力は0で埋める）
+#[cfg(feature = "alloc")]
+pub fn and3_n(a: &[bool], b: &[bool], c: &[bool]) -> Vec<bool> {
+    lanes3(a, b, c, and3)
+}
+
+/// nビット 3入力 NAND（各ビットに `nand3` を適用、短い入力は0で埋める）
+#[cfg(feature = "alloc")]
+pub fn nand3_n(a: &[bool], b: &[bool], c: &[bool]) -> Vec<bool> {
+    lanes3(a, b, c, nand3)
+}
+
+/// nビット 3入力 OR（各ビットに `or3` を適用、短い入力は0で埋める）
+#[cfg(feature = "alloc")]
+pub fn or3_n(a: &[bool], b: &[bool], c: &[bool]) -> Vec<bool> {
+    lanes3(a, b, c, or3)
+}
+
+/// nビット 3入力 XOR（各ビットに `xor3` を適用、短い入力は0で埋める）
+#[cfg(feature = "alloc")]
+pub fn xor3_n(a: &[bool], b: &[bool], c: &[bool]) -> Vec<bool> {
+    lanes3(a, b, c, xor3)
+}
+
+/// nビット 2:1 マルチプレクサ
+///
+/// 各ビットに `mux` を適用し、sel が 0 なら a、1 なら b を出力
+///
+/// * `sel` - 選択信号
+/// * `a` - sel=0 のときの入力（LSB→MSB順）
+/// * `b` - sel=1 のときの入力（LSB→MSB順）
+///
+/// 長さが異なる場合は短い方を0で埋め、長い方の幅で出力する
+#[cfg(feature = "alloc")]
+pub fn mux_bus(sel: bool, a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let bit_a = if i < a.len() { a[i] } else { false };
+        let bit_b = if i < b.len() { b[i] } else { false };
+        out.push(mux(sel, bit_a, bit_b));
+    }
+
+    out
+}
+
+/// マルチプレクサの入力エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MuxError {
+    /// 入力の本数が 2^sel.len() と一致しない
+    InputCount { expected: usize, actual: usize },
+    /// 入力バスの幅がそろっていない（index番目の幅がinputs[0]と異なる）
+    WidthMismatch { index: usize, expected: usize, actual: usize },
+    /// 選択信号が広すぎて 2^sel.len() を usize で表せない
+    SelectTooWide { bits: usize },
+}
+
+impl fmt::Display for MuxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MuxError::InputCount { expected, actual } => {
+                write!(f, "expected {} inputs for the select bus, got {}", expected, actual)
+            }
+            MuxError::WidthMismatch { index, expected, actual } => {
+                write!(f, "input {} has width {}, expected {}", index, actual, expected)
+            }
+            MuxError::SelectTooWide { bits } => {
+                write!(f, "select bus of {} bits is too wide", bits)
+            }
+        }
+    }
+}
+
+impl core::error::Error for MuxError {}
+
+/// 16ビット 2:1 マルチプレクサ
+///
+/// 16本の `mux` を並べ、sel が 0 なら a、1 なら b のワードを出力
+pub fn mux16(sel: bool, a: &[bool; 16], b: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for i in 0..16 {
+        out[i] = mux(sel, a[i], b[i]);
+    }
+    out
+}
+
+/// 16ビット 4:1 マルチプレクサ
+///
+/// 2ビットの選択信号で4つのワードから1つを選ぶ。mux16 を2段に並べた構成で、
+/// 1段目で sel[0] により (a, b) と (c, d) を絞り込み、2段目で sel[1] により選ぶ
+///
+/// **selはLSB→MSB順**（sel[0]が下位ビット）なので、配列の見た目と2進数の並びが逆になる点に注意
+///
+/// | sel            | 出力 |
+/// |----------------|------|
+/// | [false, false] | a    |
+/// | [true, false]  | b    |
+/// | [false, true]  | c    |
+/// | [true, true]   | d    |
+pub fn mux4way16(
+    sel: &[bool; 2],
+    a: &[bool; 16],
+    b: &[bool; 16],
+    c: &[bool; 16],
+    d: &[bool; 16],
+) -> [bool; 16] {
+    let ab = mux16(sel[0], a, b);
+    let cd = mux16(sel[0], c, d);
+    mux16(sel[1], &ab, &cd)
+}
+
+/// 16ビット 8:1 マルチプレクサ
+///
+/// 3ビットの選択信号で8つのワードから inputs[sel] を選ぶ
+/// 下位2ビット sel[0..2] で inputs[0..4] と inputs[4..8] をそれぞれ mux4way16 で絞り込み、
+/// 最上位ビット sel[2] で mux16 により選ぶ
+///
+/// selはLSB→MSB順（mux4way16 を参照）。例えば [true, false, true] は 5 を表す
+pub fn mux8way16(sel: &[bool; 3], inputs: &[[bool; 16]; 8]) -> [bool; 16] {
+    let low = [sel[0], sel[1]];
+    let lower = mux4way16(&low, &inputs[0], &inputs[1], &inputs[2], &inputs[3]);
+    let upper = mux4way16(&low, &inputs[4], &inputs[5], &inputs[6], &inputs[7]);
+    mux16(sel[2], &lower, &upper)
+}
+
+/// Nウェイ・Wビット マルチプレクサ
+///
+/// selの値（LSB→MSB順の2進数）が示す番号の入力バスを出力する
+///
+/// selの下位ビットから順に、隣り合う入力のペアを mux_bus で1段ずつ絞り込む
+/// （2^k 入力のmux木と同じ構造）
+///
+/// * `sel` - kビットの選択信号（LSB→MSB順）
+/// * `inputs` - 2^k 本のWビット入力（すべて同じ幅であること）
+///
+/// 入力の本数が 2^k でない場合や、幅がそろっていない場合はエラーを返す
+/// （0埋めによる暗黙の幅合わせはしない）
+/// 2^k が usize に収まらないほど sel が広い場合も SelectTooWide を返す
+#[cfg(feature = "alloc")]
+pub fn mux_nway_bus(sel: &[bool], inputs: &[&[bool]]) -> Result<Vec<bool>, MuxError> {
+    let expected = u32::try_from(sel.len())
+        .ok()
+        .and_then(|k| 1usize.checked_shl(k))
+        .ok_or(MuxError::SelectTooWide { bits: sel.len() })?;
+    if inputs.len() != expected {
+        return Err(MuxError::InputCount { expected, actual: inputs.len() });
+    }
+
+    let width = inputs[0].len();
+    for (index, input) in inputs.iter().enumerate() {
+        if input.len() != width {
+            return Err(MuxError::WidthMismatch { index, expected: width, actual: input.len() });
+        }
+    }
+
+    let mut layer: Vec<Vec<bool>> = inputs.iter().map(|input| input.to_vec()).collect();
+    for &s in sel {
+        layer = layer
+            .chunks(2)
+            .map(|pair| mux_bus(s, &pair[0], &pair[1]))
+            .collect();
+    }
+
+    Ok(layer.remove(0))
+}
+
+/// Nウェイ マルチプレクサ（幅・本数が不ぞろいの入力を許す版）
+///
+/// selの値（LSB→MSB順の2進数）が示す番号の入力を出力する
+///
+/// 最上位の選択ビットで入力を前半・後半に分け、それぞれを再帰的に絞り込んでから
+/// mux_bus で選ぶ（mux木と同じ構造）
+///
+/// * `sel` - kビットの選択信号（LSB→MSB順）
+/// * `inputs` - 最大 2^k 本の入力（LSB→MSB順）
+///
+/// - 入力の幅が異なる場合は、最も広い入力の幅に0で埋めてそろえる
+/// - 2^k 本に満たない分の入力は、すべて0のワードとして扱う
+/// - 2^k 本を超える分の入力は選ばれない
+/// - selが空なら inputs[0]（入力がなければ空のVec）を返す
+#[cfg(feature = "alloc")]
+pub fn mux_nway(sel: &[bool], inputs: &[Vec<bool>]) -> Vec<bool> {
+    let width = inputs.iter().map(|input| input.len()).max().unwrap_or(0);
+    mux_nway_tree(sel, inputs, width)
+}
+
+#[cfg(feature = "alloc")]
+fn mux_nway_tree(sel: &[bool], inputs: &[Vec<bool>], width: usize) -> Vec<bool> {
+    match sel.split_last() {
+        None => {
+            let mut out = inputs.first().cloned().unwrap_or_default();
+            out.resize(width, false);
+            out
+        }
+        Some((&top, rest)) => {
+            let half = (1usize << rest.len()).min(inputs.len());
+            let lower = mux_nway_tree(rest, &inputs[..half], width);
+            let upper = mux_nway_tree(rest, &inputs[half..], width);
+            mux_bus(top, &lower, &upper)
+        }
+    }
+}
+
+/// 1:4 デマルチプレクサ
+///
+/// 入力 d を、sel（LSB→MSB順の2ビット）が示す出力へ送る。それ以外の出力は常に0
+/// 出力の並びは mux4way16 の入力 (a, b, c, d) と同じ
+///
+/// | sel            | 出力                |
+/// |----------------|---------------------|
+/// | [false, false] | (d, 0, 0, 0)        |
+/// | [true, false]  | (0, d, 0, 0)        |
+/// | [false, true]  | (0, 0, d, 0)        |
+/// | [true, true]   | (0, 0, 0, d)        |
+///
+/// 1段目で sel[1] により上下に分け、2段目で sel[0] により分配する
+pub fn demux4way(sel: &[bool; 2], d: bool) -> (bool, bool, bool, bool) {
+    let (lower, upper) = demux(sel[1], d);
+    let (o0, o1) = demux(sel[0], lower);
+    let (o2, o3) = demux(sel[0], upper);
+    (o0, o1, o2, o3)
+}
+
+/// 1:8 デマルチプレクサ
+///
+/// 入力 d を、sel（LSB→MSB順の3ビット）が示す番号の出力へ送る。それ以外の出力は常に0
+///
+/// 1段目で最上位ビット sel[2] により上下に分け、2段目で demux4way により分配する
+pub fn demux8way(sel: &[bool; 3], d: bool) -> [bool; 8] {
+    let low = [sel[0], sel[1]];
+    let (lower, upper) = demux(sel[2], d);
+    let (o0, o1, o2, o3) = demux4way(&low, lower);
+    let (o4, o5, o6, o7) = demux4way(&low, upper);
+    [o0, o1, o2, o3, o4, o5, o6, o7]
+}
+
+/// nビット 1:2 デマルチプレクサ
+///
+/// 各ビットに `demux` を適用し、入力バス d を sel=0→(d,0), sel=1→(0,d) へ分配
+///
+/// * `sel` - 選択信号
+/// * `d` - nビット入力（LSB→MSB順）
+///
+/// 選択されなかった側は、入力と同じ幅のすべて0のバスになる
+#[cfg(feature = "alloc")]
+pub fn demux_bus(sel: bool, d: &[bool]) -> (Vec<bool>, Vec<bool>) {
+    let mut o0 = Vec::with_capacity(d.len());
+    let mut o1 = Vec::with_capacity(d.len());
+    for &bit in d {
+        let (b0, b1) = demux(sel, bit);
+        o0.push(b0);
+        o1.push(b1);
+    }
+    (o0, o1)
+}
+
+/// Nウェイ・Wビット デマルチプレクサ
+///
+/// 入力バス d を、selの値（LSB→MSB順の2進数）が示す番号の出力へ分配する
+///
+/// selの上位ビットから順に demux_bus で1段ずつ分岐させる（demux木と同じ構造）
+///
+/// * `sel` - kビットの選択信号（LSB→MSB順）
+/// * `d` - Wビット入力（LSB→MSB順）
+///
+/// 戻り値は 2^k 本のWビット出力。選択されなかった出力はすべて0のバスになる
+#[cfg(feature = "alloc")]
+pub fn demux_nway_bus(sel: &[bool], d: &[bool]) -> Vec<Vec<bool>> {
+    let mut layer = vec![d.to_vec()];
+    for &s in sel.iter().rev() {
+        let mut next = Vec::with_capacity(layer.len() * 2);
+        for input in &layer {
+            let (o0, o1) = demux_bus(s, input);
+            next.push(o0);
+            next.push(o1);
+        }
+        layer = next;
+    }
+    layer
+}
+
+/// Nウェイ 1ビット デマルチプレクサ
+///
+/// 入力 d を、selの値（LSB→MSB順の2進数）が示す番号の出力へ送る
+///
+/// 最上位の選択ビットで demux により前半・後半に分け、それぞれを再帰的に分配する
+///
+/// 戻り値は 2^k 本の出力。選ばれた1本だけが d で、それ以外は常に0
+#[cfg(feature = "alloc")]
+pub fn demux_nway(sel: &[bool], d: bool) -> Vec<bool> {
+    match sel.split_last() {
+        None => vec![d],
+        Some((&top, rest)) => {
+            let (lower, upper) = demux(top, d);
+            let mut out = demux_nway(rest, lower);
+            out.extend(demux_nway(rest, upper));
+            out
+        }
+    }
+}
+
+/// OR リダクション
+///
+/// nビット入力のすべてのビットを `or` ゲートで畳み込む
+///
+/// いずれかのビットが1ならtrue。空のスライスはfalse
+pub fn or_reduce(a: &[bool]) -> bool {
+    or_reduce_with(&NandBackend, a)
+}
+
+/// OR リダクション（バックエンド指定版）
+pub fn or_reduce_with<G: GateBackend>(g: &G, a: &[bool]) -> bool {
+    let mut acc = false;
+    for &bit in a {
+        acc = g.or(acc, bit);
+    }
+    acc
+}
+
+/// 8入力 OR
+///
+/// or_reduce の8ビット固定幅版
+pub fn or8way(a: &[bool; 8]) -> bool {
+    or_reduce(a)
+}
+
+/// AND リダクション
+///
+/// nビット入力のすべてのビットを `and` ゲートで畳み込む
+///
+/// すべてのビットが1ならtrue。空のスライスはtrue
+pub fn and_reduce(a: &[bool]) -> bool {
+    let mut acc = true;
+    for &bit in a {
+        acc = and(acc, bit);
+    }
+    acc
+}
+
+/// 16入力 AND
+///
+/// and_reduce の16ビット固定幅版
+pub fn and16way(a: &[bool; 16]) -> bool {
+    and_reduce(a)
+}
+
+/// XOR リダクション
+///
+/// nビット入力のすべてのビットを `xor` ゲートで畳み込む（ワードのパリティ）
+///
+/// 1のビットが奇数個ならtrue。空のスライスはfalse
+pub fn xor_reduce(a: &[bool]) -> bool {
+    let mut acc = false;
+    for &bit in a {
+        acc = xor(acc, bit);
+    }
+    acc
+}
+
+/// 16入力 XOR
+///
+/// xor_reduce の16ビット固定幅版（16ビットワードのパリティ）
+pub fn xor16way(a: &[bool; 16]) -> bool {
+    xor_reduce(a)
+}
+
+/// 共有バスの1本の信号線の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    /// 0を出力している
+    Low,
+    /// 1を出力している
+    High,
+    /// 出力していない（ハイインピーダンス）
+    Z,
+}
+
+/// トライステートバッファ
+///
+/// enable が1なら d を出力し、0なら出力を切り離す（Z）
+pub fn tri_state(enable: bool, d: bool) -> Line {
+    match (enable, d) {
+        (false, _) => Line::Z,
+        (true, false) => Line::Low,
+        (true, true) => Line::High,
+    }
+}
+
+/// nビット トライステートバッファ
+///
+/// 各ビットに tri_state を適用する
+#[cfg(feature = "alloc")]
+pub fn tri_state_n(enable: bool, d: &[bool]) -> Vec<Line> {
+    d.iter().map(|&bit| tri_state(enable, bit)).collect()
+}
+
+/// バスの衝突（2つのドライバが異なる値を出力している）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusContention {
+    /// 衝突したビット位置（1本の信号線の場合は0）
+    pub bit: usize,
+    /// 衝突した2つのドライバの番号
+    pub drivers: (usize, usize),
+}
+
+impl fmt::Display for BusContention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bus contention on bit {}: drivers {} and {} disagree",
+            self.bit, self.drivers.0, self.drivers.1
+        )
+    }
+}
+
+impl core::error::Error for BusContention {}
+
+/// 複数のドライバがつながった1本の信号線の値を決める
+///
+/// - Z 以外のドライバがすべて同じ値なら、その値
+/// - すべてのドライバが Z（またはドライバがない）なら、プルダウンにより 0（false）
+/// - 0 と 1 を出力するドライバが混在する場合は、BusContention を返す
+pub fn resolve(drivers: &[Line]) -> Result<bool, BusContention> {
+    resolve_line(drivers.iter().copied(), 0)
+}
+
+fn resolve_line(drivers: impl Iterator<Item = Line>, bit: usize) -> Result<bool, BusContention> {
+    let mut driven: Option<(usize, bool)> = None;
+    for (index, line) in drivers.enumerate() {
+        let value = match line {
+            Line::Z => continue,
+            Line::Low => false,
+            Line::High => true,
+        };
+        match driven {
+            None => driven = Some((index, value)),
+            Some((first, v)) if v != value => {
+                return Err(BusContention { bit, drivers: (first, index) });
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(driven.is_some_and(|(_, v)| v))
+}
+
+/// 複数のドライバがつながったnビットバスの値を決める
+///
+/// 各ビットを resolve と同じ規則で決める。ドライバごとに幅が異なる場合は、
+/// 足りないビットをそのドライバの Z として扱い、最も広いドライバの幅で出力する
+///
+/// 衝突がある場合は、最も下位の衝突ビットを BusContention として返す
+#[cfg(feature = "alloc")]
+pub fn resolve_n(drivers: &[&[Line]]) -> Result<Vec<bool>, BusContention> {
+    let width = drivers.iter().map(|d| d.len()).max().unwrap_or(0);
+    (0..width)
+        .map(|bit| resolve_line(drivers.iter().map(|d| d.get(bit).copied().unwrap_or(Line::Z)), bit))
+        .collect()
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u16, bits_from_u64_n};
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_not_n() {
+        assert_eq!(not_n(&[true]), vec![false]);
+        assert_eq!(not_n(&[false]), vec![true]);
+        assert_eq!(not_n(&bits_from_u64_n(0b1010_0110, 8)), bits_from_u64_n(0b0101_1001, 8));
+        assert_eq!(not_n(&bits_from_u64_n(0x0F0F, 16)), bits_from_u64_n(0xF0F0, 16));
+        assert_eq!(not_n(&[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_not16() {
+        assert_eq!(not16(&[false; 16]), [true; 16]);
+        for v in [0x0000u16, 0xFFFF, 0x1234, 0x8001] {
+            assert_eq!(not16(&bits_from_u16(v)), bits_from_u16(!v));
+        }
+    }
+
+    #[test]
+    fn test_cond_invert() {
+        for (v, n) in [(0b1u64, 1usize), (0b1010_0110, 8), (0x0F0F, 16), (0x1_2345, 20)] {
+            let a = bits_from_u64_n(v, n);
+            assert_eq!(cond_invert(false, &a), a, "n = {}", n);
+            assert_eq!(cond_invert(true, &a), not_n(&a), "n = {}", n);
+        }
+        assert_eq!(cond_invert(false, &[]), Vec::<bool>::new());
+        assert_eq!(cond_invert(true, &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_cond_invert16() {
+        for v in [0x0000u16, 0xFFFF, 0x1234] {
+            assert_eq!(cond_invert16(false, &bits_from_u16(v)), bits_from_u16(v));
+            assert_eq!(cond_invert16(true, &bits_from_u16(v)), bits_from_u16(!v));
+        }
+    }
+
+    #[test]
+    fn test_and_n() {
+        assert_eq!(
+            and_n(&[true, true, false, false], &[true, false, true, false]),
+            vec![true, false, false, false]
+        );
+
+        // 長さが異なる場合は短い方を0で埋める
+        assert_eq!(and_n(&[true, true, true], &[true]), vec![true, false, false]);
+        assert_eq!(and_n(&[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_and16() {
+        let values = [0x0000u16, 0xFFFF, 0x1234, 0xF0F0, 0x8001, 0xA5A5];
+        for &a in &values {
+            for &b in &values {
+                assert_eq!(and16(&bits_from_u16(a), &bits_from_u16(b)), bits_from_u16(a & b), "{:#06x} & {:#06x}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_or_n() {
+        assert_eq!(
+            or_n(&[true, true, false, false], &[true, false, true, false]),
+            vec![true, true, true, false]
+        );
+
+        // 長さが異なる場合は短い方を0で埋める
+        assert_eq!(or_n(&[false, true, false], &[true]), vec![true, true, false]);
+        assert_eq!(or_n(&[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_or16_random() {
+        let mut rng = XorShift::new(0x0816);
+        for _ in 0..200 {
+            let a = rng.next_bits(16) as u16;
+            let b = rng.next_bits(16) as u16;
+            assert_eq!(or16(&bits_from_u16(a), &bits_from_u16(b)), bits_from_u16(a | b), "{:#06x} | {:#06x}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_nor_n() {
+        assert_eq!(
+            nor_n(&[true, true, false, false], &[true, false, true, false]),
+            vec![false, false, false, true]
+        );
+
+        // 長さが異なる場合は短い方を0で埋める（0同士のNORは1）
+        assert_eq!(nor_n(&[false, false], &[false]), vec![true, true]);
+        assert_eq!(nor_n(&[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_xor_n() {
+        assert_eq!(
+            xor_n(&[true, true, false, false], &[true, false, true, false]),
+            vec![false, true, true, false]
+        );
+
+        // 長さが異なる場合は短い方を0で埋める
+        assert_eq!(xor_n(&[true], &[true, true]), vec![false, true]);
+        assert_eq!(xor_n(&[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_xor16_random() {
+        let mut rng = XorShift::new(0x1816);
+        for _ in 0..500 {
+            let a = rng.next_bits(16) as u16;
+            let b = rng.next_bits(16) as u16;
+            assert_eq!(xor16(&bits_from_u16(a), &bits_from_u16(b)), bits_from_u16(a ^ b), "{:#06x} ^ {:#06x}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_xnor_n() {
+        assert_eq!(
+            xnor_n(&[true, true, false, false], &[true, false, true, false]),
+            vec![true, false, false, true]
+        );
+
+        // 16ビットの一致: すべて1になるのは同じワードどうしのときだけ
+        let all_equal = |a: u16, b: u16| xnor_n(&bits_from_u16(a), &bits_from_u16(b)).iter().all(|&bit| bit);
+        assert!(all_equal(0x1234, 0x1234));
+        assert!(all_equal(0xFFFF, 0xFFFF));
+        assert!(!all_equal(0x1234, 0x1235));
+        assert!(!all_equal(0x8000, 0x0000));
+        assert_eq!(xnor_n(&bits_from_u16(0x00FF), &bits_from_u16(0x0F0F)), bits_from_u64_n(0xF00F, 16));
+    }
+
+    #[test]
+    fn test_three_input_bus_gates() {
+        let (a, b, c) = (bits_from_u64_n(0b1111_0000, 8), bits_from_u64_n(0b1100_1100, 8), bits_from_u64_n(0b1010_1010, 8));
+        assert_eq!(and3_n(&a, &b, &c), bits_from_u64_n(0b1000_0000, 8));
+        assert_eq!(nand3_n(&a, &b, &c), bits_from_u64_n(0b0111_1111, 8));
+        assert_eq!(or3_n(&a, &b, &c), bits_from_u64_n(0b1111_1110, 8));
+        assert_eq!(xor3_n(&a, &b, &c), bits_from_u64_n(0b1001_0110, 8));
+    }
+
+    #[test]
+    fn test_three_input_bus_gates_width_mismatch() {
+        // 短い入力は0で埋め、最も長い入力の幅で出力する
+        let a = [true, true, true];
+        let b = [true, true];
+        let c = [true];
+        assert_eq!(and3_n(&a, &b, &c), vec![true, false, false]);
+        assert_eq!(nand3_n(&a, &b, &c), vec![false, true, true]);
+        assert_eq!(or3_n(&a, &b, &c), vec![true, true, true]);
+        assert_eq!(xor3_n(&a, &b, &c), vec![true, false, true]);
+        assert_eq!(or3_n(&[], &[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_mux_bus() {
+        let a = [true, false, true, false];
+        let b = [false, true, true, true];
+        assert_eq!(mux_bus(false, &a, &b), a.to_vec());
+        assert_eq!(mux_bus(true, &a, &b), b.to_vec());
+
+        // 長さが異なる場合は短い方を0で埋め、長い方の幅で出力する
+        let short = [true, true];
+        assert_eq!(mux_bus(false, &short, &b), vec![true, true, false, false]);
+        assert_eq!(mux_bus(true, &a, &short), vec![true, true, false, false]);
+        assert_eq!(mux_bus(true, &short, &b), b.to_vec());
+    }
+
+    #[test]
+    fn test_mux16() {
+        for (a, b) in [(0x0000u16, 0xFFFF), (0x1234, 0xABCD), (0x8001, 0x7FFE)] {
+            assert_eq!(mux16(false, &bits_from_u16(a), &bits_from_u16(b)), bits_from_u16(a));
+            assert_eq!(mux16(true, &bits_from_u16(a), &bits_from_u16(b)), bits_from_u16(b));
+        }
+    }
+
+    #[test]
+    fn test_mux4way16() {
+        let (a, b, c, d) = (bits_from_u16(0x1111), bits_from_u16(0x2222), bits_from_u16(0x4444), bits_from_u16(0x8888));
+        assert_eq!(mux4way16(&[false, false], &a, &b, &c, &d), a);
+        assert_eq!(mux4way16(&[true, false], &a, &b, &c, &d), b);
+        assert_eq!(mux4way16(&[false, true], &a, &b, &c, &d), c);
+        assert_eq!(mux4way16(&[true, true], &a, &b, &c, &d), d);
+    }
+
+    #[test]
+    fn test_mux8way16() {
+        let mut inputs = [[false; 16]; 8];
+        for (i, word) in inputs.iter_mut().enumerate() {
+            *word = bits_from_u16(i as u16);
+        }
+        for i in 0..8u64 {
+            let sel = [i & 1 != 0, i & 2 != 0, i & 4 != 0];
+            assert_eq!(mux8way16(&sel, &inputs), bits_from_u16(i as u16), "sel = {}", i);
+        }
+    }
+
+    #[test]
+    fn test_mux8way16_repeated_inputs() {
+        // 同じワードの間に区別できるワードを置き、選択位置が1つずれたら検出できるようにする
+        let same = bits_from_u16(0xAAAA);
+        let inputs = [same, same, same, bits_from_u16(0x0003), same, same, bits_from_u16(0x0006), same];
+        assert_eq!(mux8way16(&[true, true, false], &inputs), bits_from_u16(0x0003));
+        assert_eq!(mux8way16(&[false, true, true], &inputs), bits_from_u16(0x0006));
+        assert_eq!(mux8way16(&[false, false, true], &inputs), same);
+        assert_eq!(mux8way16(&[true, true, true], &inputs), same);
+    }
+
+    #[test]
+    fn test_mux_nway() {
+        for k in [2usize, 3, 5] {
+            let inputs: Vec<Vec<bool>> = (0..1u64 << k).map(|i| bits_from_u64_n(i * 3 + 1, 8)).collect();
+            for i in 0..1u64 << k {
+                assert_eq!(mux_nway(&bits_from_u64_n(i, k), &inputs), inputs[i as usize], "k = {}, sel = {}", k, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mux_nway_missing_and_ragged_inputs() {
+        // 3本しかない入力を2ビットで選ぶ: 4本目はすべて0のワード
+        // 幅は最も広い入力（4ビット）にそろえる
+        let inputs = vec![vec![true], vec![false, true, true, true], vec![true, true]];
+        assert_eq!(mux_nway(&bits_from_u64_n(0, 2), &inputs), vec![true, false, false, false]);
+        assert_eq!(mux_nway(&bits_from_u64_n(1, 2), &inputs), vec![false, true, true, true]);
+        assert_eq!(mux_nway(&bits_from_u64_n(2, 2), &inputs), vec![true, true, false, false]);
+        assert_eq!(mux_nway(&bits_from_u64_n(3, 2), &inputs), vec![false; 4]);
+    }
+
+    #[test]
+    fn test_mux_nway_empty_select() {
+        assert_eq!(mux_nway(&[], &[vec![true, false], vec![false, true]]), vec![true, false]);
+        assert_eq!(mux_nway(&[], &[]), Vec::<bool>::new());
+        assert_eq!(mux_nway(&[true], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_mux_nway_bus_4x5() {
+        let words: Vec<Vec<bool>> = (0..4).map(|i| bits_from_u64_n(i * 7 + 1, 5)).collect();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+        for (i, expected) in words.iter().enumerate() {
+            let sel = bits_from_u64_n(i as u64, 2);
+            assert_eq!(mux_nway_bus(&sel, &inputs).as_ref(), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_mux_nway_bus_8x16() {
+        let words: Vec<Vec<bool>> = (0..8).map(|i| bits_from_u64_n(0x1111 * i + 3, 16)).collect();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+        let fixed: [[bool; 16]; 8] = core::array::from_fn(|i| words[i].clone().try_into().unwrap());
+        for (i, expected) in words.iter().enumerate() {
+            let sel = bits_from_u64_n(i as u64, 3);
+            assert_eq!(mux_nway_bus(&sel, &inputs).as_ref(), Ok(expected));
+
+            // 固定幅の mux8way16 と同じ結果になる
+            let sel3 = [sel[0], sel[1], sel[2]];
+            assert_eq!(mux_nway_bus(&sel, &inputs).unwrap(), mux8way16(&sel3, &fixed).to_vec());
+        }
+    }
+
+    #[test]
+    fn test_mux_nway_bus_errors() {
+        let a = [true, false];
+        let b = [false, true];
+        let short = [true];
+
+        assert_eq!(
+            mux_nway_bus(&[false, false], &[&a, &b]),
+            Err(MuxError::InputCount { expected: 4, actual: 2 })
+        );
+        assert_eq!(
+            mux_nway_bus(&[false], &[&a, &short]),
+            Err(MuxError::WidthMismatch { index: 1, expected: 2, actual: 1 })
+        );
+
+        // 2^sel.len() が usize に収まらない
+        let wide = [false; usize::BITS as usize];
+        assert_eq!(
+            mux_nway_bus(&wide, &[&a, &b]),
+            Err(MuxError::SelectTooWide { bits: usize::BITS as usize })
+        );
+    }
+
+    #[test]
+    fn test_demux4way() {
+        for index in 0..4 {
+            let sel = [index & 1 != 0, index & 2 != 0];
+            for d in [false, true] {
+                let (o0, o1, o2, o3) = demux4way(&sel, d);
+                let outputs = [o0, o1, o2, o3];
+                for (k, &o) in outputs.iter().enumerate() {
+                    assert_eq!(o, d && k == index, "sel = {}, d = {}, output {}", index, d, k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_demux8way() {
+        for index in 0..8 {
+            let sel = [index & 1 != 0, index & 2 != 0, index & 4 != 0];
+            for d in [false, true] {
+                let mut expected = [false; 8];
+                expected[index] = d;
+                assert_eq!(demux8way(&sel, d), expected, "sel = {}, d = {}", index, d);
+            }
+        }
+    }
+
+    #[test]
+    fn test_demux8way_one_hot() {
+        // d=1 のとき、出力はちょうど1本だけが1になる
+        for index in 0..8 {
+            let sel = [index & 1 != 0, index & 2 != 0, index & 4 != 0];
+            let set = demux8way(&sel, true).iter().filter(|&&o| o).count();
+            assert_eq!(set, 1, "sel = {}", index);
+        }
+    }
+
+    #[test]
+    fn test_demux_nway() {
+        for k in 0..=5usize {
+            for i in 0..1u64 << k {
+                let out = demux_nway(&bits_from_u64_n(i, k), true);
+                assert_eq!(out.len(), 1 << k);
+                let set: Vec<usize> = (0..out.len()).filter(|&j| out[j]).collect();
+                assert_eq!(set, vec![i as usize], "k = {}, sel = {}", k, i);
+                assert_eq!(demux_nway(&bits_from_u64_n(i, k), false), vec![false; 1 << k]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_demux_bus() {
+        let d = [true, false, true];
+        assert_eq!(demux_bus(false, &d), (d.to_vec(), vec![false; 3]));
+        assert_eq!(demux_bus(true, &d), (vec![false; 3], d.to_vec()));
+    }
+
+    #[test]
+    fn test_demux_nway_bus_8x16() {
+        let d = bits_from_u64_n(0xBEEF, 16);
+        for i in 0..8 {
+            let outputs = demux_nway_bus(&bits_from_u64_n(i as u64, 3), &d);
+            assert_eq!(outputs.len(), 8);
+            for (j, output) in outputs.iter().enumerate() {
+                if i == j {
+                    assert_eq!(output, &d);
+                } else {
+                    assert_eq!(output, &vec![false; 16], "sel = {}, output {}", i, j);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_demux_nway_bus_empty_width() {
+        // 幅0の入力は、幅0の出力を 2^k 本返す
+        let outputs = demux_nway_bus(&[true, false], &[]);
+        assert_eq!(outputs, vec![Vec::<bool>::new(); 4]);
+    }
+
+    #[test]
+    fn test_or_reduce() {
+        assert_eq!(or_reduce(&[]), false);
+        assert_eq!(or_reduce(&[false; 8]), false);
+        assert_eq!(or_reduce(&[false, false, true, false]), true);
+    }
+
+    #[test]
+    fn test_or8way() {
+        assert_eq!(or8way(&[false; 8]), false);
+        for i in 0..8 {
+            let mut a = [false; 8];
+            a[i] = true;
+            assert_eq!(or8way(&a), true, "bit {}", i);
+        }
+    }
+
+    #[test]
+    fn test_and_reduce() {
+        assert_eq!(and_reduce(&[]), true);
+        assert_eq!(and_reduce(&[true; 8]), true);
+        assert_eq!(and_reduce(&[true, true, false, true]), false);
+        assert_eq!(and16way(&[true; 16]), true);
+        for i in 0..16 {
+            let mut a = [true; 16];
+            a[i] = false;
+            assert_eq!(and16way(&a), false, "bit {}", i);
+        }
+    }
+
+    #[test]
+    fn test_xor16way_random() {
+        let mut rng = XorShift::new(0x9A41);
+        for _ in 0..500 {
+            let v = rng.next_bits(16) as u16;
+            assert_eq!(xor16way(&bits_from_u16(v)), v.count_ones() % 2 == 1, "v = {:#06x}", v);
+        }
+    }
+
+    #[test]
+    fn test_xor_reduce() {
+        assert_eq!(xor_reduce(&[]), false);
+        assert_eq!(xor_reduce(&[true, false, true]), false);
+        assert_eq!(xor_reduce(&[true, true, true]), true);
+    }
+
+    #[test]
+    fn test_tri_state() {
+        assert_eq!(tri_state(false, false), Line::Z);
+        assert_eq!(tri_state(false, true), Line::Z);
+        assert_eq!(tri_state(true, false), Line::Low);
+        assert_eq!(tri_state(true, true), Line::High);
+    }
+
+    #[test]
+    fn test_resolve() {
+        // 1つのドライバ
+        assert_eq!(resolve(&[Line::High]), Ok(true));
+        assert_eq!(resolve(&[Line::Z, Line::Low, Line::Z]), Ok(false));
+
+        // 同じ値のドライバが複数
+        assert_eq!(resolve(&[Line::High, Line::Z, Line::High]), Ok(true));
+
+        // 衝突
+        assert_eq!(
+            resolve(&[Line::Z, Line::High, Line::High, Line::Low]),
+            Err(BusContention { bit: 0, drivers: (1, 3) })
+        );
+
+        // すべてZならプルダウンで0
+        assert_eq!(resolve(&[Line::Z, Line::Z]), Ok(false));
+        assert_eq!(resolve(&[]), Ok(false));
+    }
+
+    #[test]
+    fn test_resolve_n_shared_bus() {
+        // レジスタとRAMが同じデータバスにつながり、片方だけが出力を有効にする
+        let register = bits_from_u16(0x1234);
+        let ram = bits_from_u16(0xBEEF);
+        let bus = |reg_out: bool, ram_out: bool| {
+            let a = tri_state_n(reg_out, &register);
+            let b = tri_state_n(ram_out, &ram);
+            resolve_n(&[&a, &b])
+        };
+        assert_eq!(bus(true, false), Ok(bits_from_u16(0x1234).to_vec()));
+        assert_eq!(bus(false, true), Ok(bits_from_u16(0xBEEF).to_vec()));
+        assert_eq!(bus(false, false), Ok(vec![false; 16]));
+
+        // 両方が出力すると、値が食い違う最下位のビット（0x1234 ^ 0xBEEF の bit 0）で衝突する
+        assert_eq!(bus(true, true), Err(BusContention { bit: 0, drivers: (0, 1) }));
+    }
+
+    #[test]
+    fn test_resolve_n_ragged_drivers() {
+        let narrow = [Line::High];
+        let wide = [Line::Z, Line::Low, Line::High];
+        assert_eq!(resolve_n(&[&narrow, &wide]), Ok(vec![true, false, true]));
+        assert_eq!(resolve_n(&[]), Ok(vec![]));
+    }
+
+    fn check_and_bus_matches<const N: usize>(rng: &mut XorShift) {
+        for _ in 0..16 {
+            let a: [bool; N] = core::array::from_fn(|_| rng.next_bits(1) == 1);
+            let b: [bool; N] = core::array::from_fn(|_| rng.next_bits(1) == 1);
+            assert_eq!(and_bus(&a, &b).to_vec(), and_n(&a, &b), "N = {}", N);
+        }
+    }
+
+    #[test]
+    fn test_and_bus_matches_slice() {
+        let mut rng = XorShift::new(0xB05);
+        check_and_bus_matches::<1>(&mut rng);
+        check_and_bus_matches::<8>(&mut rng);
+        check_and_bus_matches::<16>(&mut rng);
+        check_and_bus_matches::<32>(&mut rng);
+    }
+}
@@ -0,0 +1,258 @@
+//! 直接マップ方式のキャッシュシミュレータ
+//!
+//! RAMの前に置き、アクセスごとにヒット・ミス・追い出しを記録する
+//! 書き込みはライトスルー（ライトバックは未対応）
+
+use alloc::{vec, vec::Vec};
+
+use crate::chip::MemoryChip;
+use crate::ecc::Word;
+
+/// キャッシュアクセスの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheEvent {
+    /// ラインにデータがあった
+    Hit,
+    /// 空のラインにメモリから読み込んだ（書き込みでは、ラインにデータがなかった）
+    Miss,
+    /// 別のタグのラインを追い出して読み込んだ（追い出したタグ）
+    Eviction { evicted_tag: usize },
+}
+
+/// セットごとのアクセス回数
+///
+/// misses は追い出しを伴うミスも含む（evictions はそのうち追い出しがあった回数）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// キャッシュライン
+struct Line {
+    tag: Option<usize>,
+    data: Vec<Word>,
+}
+
+/// 直接マップ方式・ライトスルーのキャッシュ
+///
+/// アドレスを words_per_line ワードのブロックに分け、ブロック番号を lines で割った余りを
+/// セット番号、商をタグとする
+pub struct Cache<M> {
+    memory: M,
+    words_per_line: usize,
+    lines: Vec<Line>,
+    stats: Vec<SetStats>,
+}
+
+impl<M: MemoryChip> Cache<M> {
+    /// `lines` 本のライン（1ラインあたり `words_per_line` ワード）を持つキャッシュを memory の前に置く
+    ///
+    /// lines と words_per_line は1以上、メモリのワード数は words_per_line の倍数であること
+    pub fn new(lines: usize, words_per_line: usize, memory: M) -> Self {
+        assert!(lines > 0 && words_per_line > 0, "a cache needs at least one line of one word");
+        assert!(
+            memory.size().is_multiple_of(words_per_line),
+            "memory size {} is not a multiple of the line size {}",
+            memory.size(),
+            words_per_line
+        );
+        Cache {
+            memory,
+            words_per_line,
+            lines: (0..lines).map(|_| Line { tag: None, data: vec![[false; 16]; words_per_line] }).collect(),
+            stats: vec![SetStats::default(); lines],
+        }
+    }
+
+    /// アドレスを (セット番号, タグ, ライン内の位置) に分ける
+    fn split(&self, addr: u16) -> (usize, usize, usize) {
+        let block = addr as usize / self.words_per_line;
+        (block % self.lines.len(), block / self.lines.len(), addr as usize % self.words_per_line)
+    }
+
+    fn record(&mut self, set: usize, event: CacheEvent) {
+        let stats = &mut self.stats[set];
+        match event {
+            CacheEvent::Hit => stats.hits += 1,
+            CacheEvent::Miss => stats.misses += 1,
+            CacheEvent::Eviction { .. } => {
+                stats.misses += 1;
+                stats.evictions += 1;
+            }
+        }
+    }
+
+    /// ワードを読み出す
+    ///
+    /// ミスしたときはライン全体をメモリから読み込む
+    ///
+    /// 戻り値は (word, event) のタプル
+    pub fn read(&mut self, addr: u16) -> (Word, CacheEvent) {
+        let (set, tag, offset) = self.split(addr);
+        let event = match self.lines[set].tag {
+            Some(t) if t == tag => CacheEvent::Hit,
+            previous => {
+                let base = addr as usize - offset;
+                let line = &mut self.lines[set];
+                for (i, word) in line.data.iter_mut().enumerate() {
+                    *word = self.memory.load((base + i) as u16);
+                }
+                line.tag = Some(tag);
+                match previous {
+                    Some(evicted_tag) => CacheEvent::Eviction { evicted_tag },
+                    None => CacheEvent::Miss,
+                }
+            }
+        };
+        self.record(set, event);
+        (self.lines[set].data[offset], event)
+    }
+
+    /// ワードを書き込む（ライトスルー）
+    ///
+    /// メモリには常に書き込み、ラインにデータがあればラインも更新する
+    /// ミスしてもラインには読み込まない（ライトアロケートなし）ので、結果は Hit か Miss
+    pub fn write(&mut self, addr: u16, word: &Word) -> CacheEvent {
+        let (set, tag, offset) = self.split(addr);
+        self.memory.store(addr, word);
+        let line = &mut self.lines[set];
+        let event = if line.tag == Some(tag) {
+            line.data[offset] = *word;
+            CacheEvent::Hit
+        } else {
+            CacheEvent::Miss
+        };
+        self.record(set, event);
+        event
+    }
+
+    /// ヒット率（読み書きすべてのアクセスに対するヒットの割合、アクセスがなければ0）
+    pub fn hit_rate(&self) -> f64 {
+        let hits: u64 = self.stats.iter().map(|s| s.hits).sum();
+        let total: u64 = self.stats.iter().map(|s| s.hits + s.misses).sum();
+        if total == 0 { 0.0 } else { hits as f64 / total as f64 }
+    }
+
+    /// セット `set` のアクセス回数
+    pub fn set_stats(&self, set: usize) -> SetStats {
+        self.stats[set]
+    }
+
+    /// ライン（セット）の数
+    pub fn lines(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// 下にあるメモリ
+    pub fn memory(&self) -> &M {
+        &self.memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::FastParityRam;
+    use crate::ecc::{EccRam, ParityRam};
+    use crate::convert::bits_from_u16;
+    use crate::testutil::XorShift;
+
+    /// アドレスと同じ値を入れたRAM
+    fn numbered_ram(size: usize) -> ParityRam {
+        let mut ram = ParityRam::new(size);
+        for addr in 0..size as u16 {
+            ram.write(addr, &bits_from_u16(addr));
+        }
+        ram
+    }
+
+    #[test]
+    fn test_cache_stride_pattern() {
+        // 4ライン×4ワード: 連続アクセスはラインの先頭だけがミスする
+        let mut cache = Cache::new(4, 4, numbered_ram(64));
+        for addr in 0..16u16 {
+            let expected = if addr % 4 == 0 { CacheEvent::Miss } else { CacheEvent::Hit };
+            assert_eq!(cache.read(addr), (bits_from_u16(addr), expected), "addr = {}", addr);
+        }
+        assert_eq!(cache.hit_rate(), 0.75);
+
+        // 同じ範囲をもう一度読むとすべてヒット
+        for addr in 0..16u16 {
+            assert_eq!(cache.read(addr).1, CacheEvent::Hit);
+        }
+        assert_eq!(cache.hit_rate(), 28.0 / 32.0);
+
+        // ストライド2で次の16ワードを読むと、各ラインの先頭で前のブロックを追い出す
+        for addr in (16..32u16).step_by(2) {
+            let expected = if addr % 4 == 0 {
+                CacheEvent::Eviction { evicted_tag: 0 }
+            } else {
+                CacheEvent::Hit
+            };
+            assert_eq!(cache.read(addr), (bits_from_u16(addr), expected), "addr = {}", addr);
+        }
+        for set in 0..4 {
+            assert_eq!(cache.set_stats(set), SetStats { hits: 8, misses: 2, evictions: 1 }, "set = {}", set);
+        }
+    }
+
+    #[test]
+    fn test_cache_conflict_thrashing() {
+        // 0 と 16 は同じセット0に入るので、交互に読むと毎回追い出し合う
+        let mut cache = Cache::new(4, 4, numbered_ram(64));
+        assert_eq!(cache.read(0), (bits_from_u16(0), CacheEvent::Miss));
+        for i in 0..10 {
+            assert_eq!(cache.read(16), (bits_from_u16(16), CacheEvent::Eviction { evicted_tag: 0 }), "i = {}", i);
+            assert_eq!(cache.read(0), (bits_from_u16(0), CacheEvent::Eviction { evicted_tag: 1 }), "i = {}", i);
+        }
+        assert_eq!(cache.set_stats(0), SetStats { hits: 0, misses: 21, evictions: 20 });
+        for set in 1..4 {
+            assert_eq!(cache.set_stats(set), SetStats::default());
+        }
+        assert_eq!(cache.hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_cache_write_through() {
+        let mut cache = Cache::new(2, 2, ParityRam::new(8));
+
+        // ライトアロケートなし: 書き込みミスではラインに読み込まない
+        assert_eq!(cache.write(5, &bits_from_u16(0x1234)), CacheEvent::Miss);
+        assert_eq!(cache.memory().read(5), (bits_from_u16(0x1234), true));
+        assert_eq!(cache.read(5), (bits_from_u16(0x1234), CacheEvent::Miss));
+
+        // 書き込みヒットはラインとメモリの両方を更新する
+        assert_eq!(cache.write(5, &bits_from_u16(0xBEEF)), CacheEvent::Hit);
+        assert_eq!(cache.read(5), (bits_from_u16(0xBEEF), CacheEvent::Hit));
+        assert_eq!(cache.memory().read(5), (bits_from_u16(0xBEEF), true));
+    }
+
+    #[test]
+    fn test_cache_matches_uncached_memory_on_random_trace() {
+        let mut rng = XorShift::new(0xCAC4E);
+        let mut uncached = EccRam::new(256);
+        let mut cache = Cache::new(8, 4, EccRam::new(256));
+        // 下のメモリは MemoryChip なら何でもよい（整数で保存する速いRAMの前にも置ける）
+        let mut fast = Cache::new(4, 8, FastParityRam::new(256));
+
+        for step in 0..5000 {
+            let addr = rng.next_bits(8) as u16;
+            if rng.next_bits(2) == 0 {
+                let w = bits_from_u16(rng.next_bits(16) as u16);
+                uncached.write(addr, &w);
+                cache.write(addr, &w);
+                fast.write(addr, &w);
+            } else {
+                assert_eq!(cache.read(addr).0, uncached.read(addr).0, "step {}: addr = {}", step, addr);
+                assert_eq!(fast.read(addr).0, uncached.read(addr).0, "step {}: addr = {}", step, addr);
+            }
+        }
+        // ライトスルーなので、メモリの内容もキャッシュなしと一致する
+        for addr in 0..256u16 {
+            assert_eq!(cache.memory().read(addr), uncached.read(addr), "addr = {}", addr);
+        }
+        assert!(cache.hit_rate() > 0.0 && cache.hit_rate() < 1.0);
+    }
+}
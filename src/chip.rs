@@ -0,0 +1,923 @@
+//! 部品の共通トレイト
+//!
+//! ゲートレベルの部品と、同じふるまいを整数演算で計算する速い部品に同じトレイトを実装しておくと、
+//! シミュレーションの中で部品ごとにどちらを使うかを選べる
+//! assert_chip_equivalence で2つの実装が同じ入力列に同じ出力を返すことを確かめられる
+
+use alloc::{vec, vec::Vec};
+
+use crate::alu::{alu16_ctl, fast_alu16, AluControl};
+use crate::convert::{bits_from_u16, bits_from_u64_n, u16_from_bits_slice, u64_from_bits_slice};
+use crate::display::SevenSegDriver;
+use crate::ecc::{Codeword, DATA_POSITIONS, EccRam, EccStatus, ParityRam, Word};
+use crate::gcd::{GcdPhase, GcdUnit};
+use crate::sequential::{SerialAdder, SerialSubtractor};
+
+/// ピン単位で評価する組み合わせ回路
+///
+/// 入力ピンと出力ピンはどちらもビット列（LSB→MSB順）で、幅は部品ごとに決まっている
+pub trait CombinationalChip {
+    /// 入力ピンの本数
+    fn input_width(&self) -> usize;
+
+    /// 出力ピンの本数
+    fn output_width(&self) -> usize;
+
+    /// 入力ピンの値から出力ピンの値を計算する
+    ///
+    /// inputs の長さは input_width と同じであること
+    fn eval(&self, inputs: &[bool]) -> Vec<bool>;
+}
+
+/// クロックで状態が進む順序回路
+///
+/// tick は1クロック分の入力ピンを与えて状態を進め、そのクロックの出力ピンを返す
+/// 組み合わせ回路は状態を持たない順序回路として扱える（eval をそのまま tick に使う）
+pub trait ClockedChip {
+    /// 入力ピンの本数
+    fn input_width(&self) -> usize;
+
+    /// 出力ピンの本数
+    fn output_width(&self) -> usize;
+
+    /// 1クロック進める
+    ///
+    /// inputs の長さは input_width と同じであること
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool>;
+}
+
+impl<C: CombinationalChip> ClockedChip for C {
+    fn input_width(&self) -> usize {
+        CombinationalChip::input_width(self)
+    }
+
+    fn output_width(&self) -> usize {
+        CombinationalChip::output_width(self)
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        self.eval(inputs)
+    }
+}
+
+/// アドレスで読み書きできる16ビットワードのメモリ
+pub trait MemoryChip {
+    /// ワード数
+    fn size(&self) -> usize;
+
+    /// ワードを読み出す
+    fn load(&self, addr: u16) -> Word;
+
+    /// ワードを書き込む
+    fn store(&mut self, addr: u16, word: &Word);
+
+    /// 読み出しと一緒に出る検査結果ピン（パリティやECCの状態）の本数
+    fn status_width(&self) -> usize {
+        0
+    }
+
+    /// addr を読み出したときの検査結果ピン
+    fn status(&self, _addr: u16) -> Vec<bool> {
+        Vec::new()
+    }
+}
+
+/// メモリを1ポートの順序回路として見るためのアダプタ
+///
+/// 入力ピンは data[0..16], address[16..32], load の順（33本）
+/// load が1ならクロックで data を address に書き込む
+/// 出力ピンはクロック後の address の内容 out[0..16] と、検査結果ピンの順
+pub struct MemoryPort<M>(pub M);
+
+impl<M: MemoryChip> ClockedChip for MemoryPort<M> {
+    fn input_width(&self) -> usize {
+        33
+    }
+
+    fn output_width(&self) -> usize {
+        16 + self.0.status_width()
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), 33, "a memory port has 33 input pins");
+        let addr = u16_from_bits_slice(&inputs[16..32]);
+        if inputs[32] {
+            let mut word = [false; 16];
+            word.copy_from_slice(&inputs[0..16]);
+            self.0.store(addr, &word);
+        }
+        let mut pins = self.0.load(addr).to_vec();
+        pins.extend(self.0.status(addr));
+        pins
+    }
+}
+
+/// 16ビットALUのピン配置から (x, y, ctl) を取り出す
+///
+/// 入力ピンは x[0..16], y[16..32], zx, nx, zy, ny, f, no の順（38本）
+fn alu16_pins(inputs: &[bool]) -> (u16, u16, AluControl) {
+    assert_eq!(inputs.len(), 38, "ALU16 has 38 input pins");
+    let x = u16_from_bits_slice(&inputs[0..16]);
+    let y = u16_from_bits_slice(&inputs[16..32]);
+    let c = &inputs[32..38];
+    (x, y, AluControl::new(c[0], c[1], c[2], c[3], c[4], c[5]))
+}
+
+/// 16ビットALUの出力ピン（out[0..16], zr, ng の18本）を組み立てる
+fn alu16_outputs(out: &Word, zr: bool, ng: bool) -> Vec<bool> {
+    let mut pins = out.to_vec();
+    pins.push(zr);
+    pins.push(ng);
+    pins
+}
+
+/// ゲートレベルの16ビットALU（alu16_ctl）
+///
+/// 入力ピンは x[0..16], y[16..32], zx, nx, zy, ny, f, no の順（38本）
+/// 出力ピンは out[0..16], zr, ng の順（18本）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Alu16;
+
+impl CombinationalChip for Alu16 {
+    fn input_width(&self) -> usize {
+        38
+    }
+
+    fn output_width(&self) -> usize {
+        18
+    }
+
+    fn eval(&self, inputs: &[bool]) -> Vec<bool> {
+        let (x, y, ctl) = alu16_pins(inputs);
+        let (out, zr, ng) = alu16_ctl(&bits_from_u16(x), &bits_from_u16(y), ctl);
+        alu16_outputs(&out, zr, ng)
+    }
+}
+
+/// 整数演算の16ビットALU（fast_alu16）
+///
+/// ピン配置は Alu16 と同じ。ゲートを1つずつ評価しないので、長いシミュレーションで使う
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FastAlu16;
+
+impl CombinationalChip for FastAlu16 {
+    fn input_width(&self) -> usize {
+        38
+    }
+
+    fn output_width(&self) -> usize {
+        18
+    }
+
+    fn eval(&self, inputs: &[bool]) -> Vec<bool> {
+        let (x, y, ctl) = alu16_pins(inputs);
+        let (out, zr, ng) = fast_alu16(x, y, ctl);
+        alu16_outputs(&bits_from_u16(out), zr, ng)
+    }
+}
+
+impl MemoryChip for ParityRam {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn load(&self, addr: u16) -> Word {
+        self.read(addr).0
+    }
+
+    fn store(&mut self, addr: u16, word: &Word) {
+        self.write(addr, word);
+    }
+
+    /// parity_ok の1本
+    fn status_width(&self) -> usize {
+        1
+    }
+
+    fn status(&self, addr: u16) -> Vec<bool> {
+        vec![self.read(addr).1]
+    }
+}
+
+/// 整数で保存するパリティ付きRAM（ParityRam の速い実装）
+///
+/// ワードを u16 で保存し、パリティは count_ones で計算する
+pub struct FastParityRam {
+    words: Vec<u16>,
+    parity: Vec<bool>,
+}
+
+impl FastParityRam {
+    /// `size` ワードのRAMを作る（すべて0で初期化）
+    pub fn new(size: usize) -> Self {
+        FastParityRam { words: vec![0; size], parity: vec![false; size] }
+    }
+
+    /// 保存されているビットを1つ反転させる（ParityRam::inject_error と同じビット番号）
+    pub fn inject_error(&mut self, addr: u16, bit: usize) {
+        let addr = addr as usize;
+        if bit < 16 {
+            self.words[addr] ^= 1 << bit;
+        } else {
+            assert!(bit == 16, "bit index {} out of range for a parity-protected word", bit);
+            self.parity[addr] = !self.parity[addr];
+        }
+    }
+}
+
+impl MemoryChip for FastParityRam {
+    fn size(&self) -> usize {
+        self.words.len()
+    }
+
+    fn load(&self, addr: u16) -> Word {
+        bits_from_u16(self.words[addr as usize])
+    }
+
+    fn store(&mut self, addr: u16, word: &Word) {
+        let v = u16_from_bits_slice(word);
+        self.words[addr as usize] = v;
+        self.parity[addr as usize] = v.count_ones() % 2 == 1;
+    }
+
+    fn status_width(&self) -> usize {
+        1
+    }
+
+    fn status(&self, addr: u16) -> Vec<bool> {
+        let addr = addr as usize;
+        vec![(self.words[addr].count_ones() % 2 == 1) == self.parity[addr]]
+    }
+}
+
+/// EccStatus を検査結果ピン（corrected, double_error, 訂正した位置[5]の7本）にする
+fn ecc_status_pins(status: EccStatus) -> Vec<bool> {
+    let (corrected, double_error, position) = match status {
+        EccStatus::Clean => (false, false, 0),
+        EccStatus::Corrected(p) => (true, false, p),
+        EccStatus::DoubleError => (false, true, 0),
+    };
+    let mut pins = vec![corrected, double_error];
+    pins.extend((0..5).map(|k| (position >> k) & 1 == 1));
+    pins
+}
+
+impl MemoryChip for EccRam {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn load(&self, addr: u16) -> Word {
+        self.read(addr).0
+    }
+
+    fn store(&mut self, addr: u16, word: &Word) {
+        self.write(addr, word);
+    }
+
+    fn status_width(&self) -> usize {
+        7
+    }
+
+    fn status(&self, addr: u16) -> Vec<bool> {
+        ecc_status_pins(self.read(addr).1)
+    }
+}
+
+/// 整数で保存するSECDED ECC付きRAM（EccRam の速い実装）
+///
+/// 22ビットの符号語を u32 で保存する。符号語のビット配置は Codeword と同じで、
+/// シンドロームは1になっているビットの位置番号の XOR として計算する
+pub struct FastEccRam {
+    codes: Vec<u32>,
+}
+
+/// 1になっているビットの位置番号（1〜21）の XOR
+fn fast_syndrome(code: u32) -> usize {
+    (1..22).filter(|p| (code >> p) & 1 == 1).fold(0, |acc, p| acc ^ p)
+}
+
+fn fast_encode(data: u16) -> u32 {
+    let mut code = 0u32;
+    for (i, &p) in DATA_POSITIONS.iter().enumerate() {
+        code |= (((data >> i) & 1) as u32) << p;
+    }
+    // 検査ビットの位置（2のべき）はまだ0なので、シンドロームの各ビットをそのまま置けばよい
+    let s = fast_syndrome(code);
+    for k in 0..5 {
+        code |= (((s >> k) & 1) as u32) << (1 << k);
+    }
+    code | (code.count_ones() % 2)
+}
+
+fn fast_decode(code: u32) -> (u16, EccStatus) {
+    let position = fast_syndrome(code);
+    let parity_error = code.count_ones() % 2 == 1;
+    let status = if !parity_error && position == 0 {
+        EccStatus::Clean
+    } else if parity_error && position < 22 {
+        EccStatus::Corrected(position)
+    } else {
+        EccStatus::DoubleError
+    };
+    let code = match status {
+        EccStatus::Corrected(p) => code ^ (1 << p),
+        _ => code,
+    };
+    let data = DATA_POSITIONS.iter().enumerate().fold(0u16, |acc, (i, &p)| acc | ((((code >> p) & 1) as u16) << i));
+    (data, status)
+}
+
+impl FastEccRam {
+    /// `size` ワードのRAMを作る（すべて0で初期化）
+    pub fn new(size: usize) -> Self {
+        FastEccRam { codes: vec![fast_encode(0); size] }
+    }
+
+    /// 保存されている符号語のビットを1つ反転させる（EccRam::inject_error と同じビット番号）
+    pub fn inject_error(&mut self, addr: u16, bit: usize) {
+        assert!(bit < 22, "bit index {} out of range for a codeword", bit);
+        self.codes[addr as usize] ^= 1 << bit;
+    }
+
+    /// 保存されている符号語
+    pub fn codeword(&self, addr: u16) -> Codeword {
+        let code = self.codes[addr as usize];
+        let mut c = [false; 22];
+        for (p, bit) in c.iter_mut().enumerate() {
+            *bit = (code >> p) & 1 == 1;
+        }
+        c
+    }
+}
+
+impl MemoryChip for FastEccRam {
+    fn size(&self) -> usize {
+        self.codes.len()
+    }
+
+    fn load(&self, addr: u16) -> Word {
+        bits_from_u16(fast_decode(self.codes[addr as usize]).0)
+    }
+
+    fn store(&mut self, addr: u16, word: &Word) {
+        self.codes[addr as usize] = fast_encode(u16_from_bits_slice(word));
+    }
+
+    fn status_width(&self) -> usize {
+        7
+    }
+
+    fn status(&self, addr: u16) -> Vec<bool> {
+        ecc_status_pins(fast_decode(self.codes[addr as usize]).1)
+    }
+}
+
+/// ビットシリアル加算器のピン配置
+///
+/// 入力ピンは a, b, clear の順（3本）。clear が1なら、そのクロックの加算の前に桁上げを0に戻す
+/// 出力ピンは sum, carry（クロック後の桁上げ）の順（2本）
+impl ClockedChip for SerialAdder {
+    fn input_width(&self) -> usize {
+        3
+    }
+
+    fn output_width(&self) -> usize {
+        2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), 3, "a serial adder has 3 input pins");
+        if inputs[2] {
+            self.reset_carry();
+        }
+        let sum = SerialAdder::tick(self, inputs[0], inputs[1]);
+        vec![sum, self.carry()]
+    }
+}
+
+/// 整数で計算するビットシリアル加算器（SerialAdder の速い実装、ピン配置も同じ）
+#[derive(Debug, Clone, Default)]
+pub struct FastSerialAdder {
+    carry: bool,
+}
+
+impl FastSerialAdder {
+    pub fn new() -> Self {
+        FastSerialAdder { carry: false }
+    }
+}
+
+impl ClockedChip for FastSerialAdder {
+    fn input_width(&self) -> usize {
+        3
+    }
+
+    fn output_width(&self) -> usize {
+        2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        let carry = self.carry && !inputs[2];
+        let total = inputs[0] as u8 + inputs[1] as u8 + carry as u8;
+        self.carry = total > 1;
+        vec![total & 1 == 1, self.carry]
+    }
+}
+
+/// ビットシリアル減算器のピン配置
+///
+/// 入力ピンは a, b, clear の順（3本）。clear が1なら、そのクロックの減算の前に借りを0に戻す
+/// 出力ピンは difference, borrow（クロック後の借り）の順（2本）
+impl ClockedChip for SerialSubtractor {
+    fn input_width(&self) -> usize {
+        3
+    }
+
+    fn output_width(&self) -> usize {
+        2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), 3, "a serial subtractor has 3 input pins");
+        if inputs[2] {
+            self.reset_borrow();
+        }
+        let difference = SerialSubtractor::tick(self, inputs[0], inputs[1]);
+        vec![difference, self.borrow()]
+    }
+}
+
+/// 整数で計算するビットシリアル減算器（SerialSubtractor の速い実装、ピン配置も同じ）
+#[derive(Debug, Clone, Default)]
+pub struct FastSerialSubtractor {
+    borrow: bool,
+}
+
+impl FastSerialSubtractor {
+    pub fn new() -> Self {
+        FastSerialSubtractor { borrow: false }
+    }
+}
+
+impl ClockedChip for FastSerialSubtractor {
+    fn input_width(&self) -> usize {
+        3
+    }
+
+    fn output_width(&self) -> usize {
+        2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        let borrow = self.borrow && !inputs[2];
+        let difference = inputs[0] as i8 - inputs[1] as i8 - borrow as i8;
+        self.borrow = difference < 0;
+        vec![difference & 1 == 1, self.borrow]
+    }
+}
+
+/// GCD 回路の出力ピン（restore, done, result の順）を組み立てる
+fn gcd_outputs(phase: GcdPhase, result: &[bool]) -> Vec<bool> {
+    let mut pins = vec![phase == GcdPhase::Restore, phase == GcdPhase::Done];
+    pins.extend_from_slice(result);
+    pins
+}
+
+/// 2進GCD回路のピン配置（n は GcdUnit::width）
+///
+/// 入力ピンは load, a[n], b[n] の順（2n + 1本）。load が1なら a と b を読み込み直し、
+/// 0ならクロックを1つ進める（step）
+/// 出力ピンはクロック後の restore, done, result[n] の順（n + 2本）
+/// - restore, done: 状態（Reduce では両方0）
+/// - result: Done のときは結果、それ以外は0
+impl ClockedChip for GcdUnit {
+    fn input_width(&self) -> usize {
+        2 * self.width() + 1
+    }
+
+    fn output_width(&self) -> usize {
+        self.width() + 2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        let n = self.width();
+        assert_eq!(inputs.len(), 2 * n + 1, "a {}-bit GCD unit has {} input pins", n, 2 * n + 1);
+        if inputs[0] {
+            *self = GcdUnit::new(&inputs[1..n + 1], &inputs[n + 1..]);
+        } else {
+            self.step();
+        }
+        match self.result() {
+            Some(result) => gcd_outputs(GcdPhase::Done, result),
+            None => gcd_outputs(self.phase(), &vec![false; n]),
+        }
+    }
+}
+
+/// 整数で計算する2進GCD回路（GcdUnit の速い実装、ステップとピン配置も同じ）
+///
+/// レジスタを u64 で持つので、幅は64ビットまで
+#[derive(Debug, Clone)]
+pub struct FastGcdUnit {
+    a: u64,
+    b: u64,
+    shift: u32,
+    phase: GcdPhase,
+    width: usize,
+}
+
+impl FastGcdUnit {
+    /// a と b を読み込んだ状態で作る（幅が異なる場合は短い方を0で埋める）
+    pub fn new(a: &[bool], b: &[bool]) -> Self {
+        let width = a.len().max(b.len());
+        assert!(width <= 64, "FastGcdUnit holds at most 64 bits, got {}", width);
+        FastGcdUnit {
+            a: u64_from_bits_slice(a),
+            b: u64_from_bits_slice(b),
+            shift: 0,
+            phase: GcdPhase::Reduce,
+            width,
+        }
+    }
+
+    /// クロックを1つ進める（GcdUnit::step と同じ手順）
+    pub fn step(&mut self) {
+        match self.phase {
+            GcdPhase::Reduce => {
+                if self.a == 0 {
+                    self.a = self.b;
+                    self.phase = GcdPhase::Restore;
+                } else if self.b == 0 {
+                    self.phase = GcdPhase::Restore;
+                } else {
+                    match (self.a & 1 == 0, self.b & 1 == 0) {
+                        (true, true) => {
+                            self.a >>= 1;
+                            self.b >>= 1;
+                            self.shift += 1;
+                        }
+                        (true, false) => self.a >>= 1,
+                        (false, true) => self.b >>= 1,
+                        (false, false) if self.a >= self.b => self.a -= self.b,
+                        (false, false) => self.b -= self.a,
+                    }
+                }
+            }
+            GcdPhase::Restore => {
+                if self.shift > 0 {
+                    self.a = (self.a << 1) & u64::MAX.checked_shr(64 - self.width as u32).unwrap_or(0);
+                    self.shift -= 1;
+                } else {
+                    self.phase = GcdPhase::Done;
+                }
+            }
+            GcdPhase::Done => {}
+        }
+    }
+}
+
+impl ClockedChip for FastGcdUnit {
+    fn input_width(&self) -> usize {
+        2 * self.width + 1
+    }
+
+    fn output_width(&self) -> usize {
+        self.width + 2
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        let n = self.width;
+        if inputs[0] {
+            *self = FastGcdUnit::new(&inputs[1..n + 1], &inputs[n + 1..]);
+        } else {
+            self.step();
+        }
+        let result = if self.phase == GcdPhase::Done { self.a } else { 0 };
+        gcd_outputs(self.phase, &bits_from_u64_n(result, n))
+    }
+}
+
+/// 7セグメント表示ドライバのピン配置（n は桁数）
+///
+/// 入力ピンは表示する値の16本（LSB→MSB順、1ワード）。クロックごとにラッチし直す（tick_with）
+/// 出力ピンは segments[7], digit_select[n] の順（n + 7本）
+impl ClockedChip for SevenSegDriver {
+    fn input_width(&self) -> usize {
+        16
+    }
+
+    fn output_width(&self) -> usize {
+        self.digit_select().len() + 7
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), 16, "a seven-segment driver has 16 input pins");
+        let (segments, select) = self.tick_with(inputs);
+        let mut pins = segments.to_vec();
+        pins.extend(select);
+        pins
+    }
+}
+
+/// 数字0〜9のセグメントパターン（[a, b, c, d, e, f, g]、seven_segment と同じ）
+const SEGMENT_PATTERNS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],
+    [false, true, true, false, false, false, false],
+    [true, true, false, true, true, false, true],
+    [true, true, true, true, false, false, true],
+    [false, true, true, false, false, true, true],
+    [true, false, true, true, false, true, true],
+    [true, false, true, true, true, true, true],
+    [true, true, true, false, false, false, false],
+    [true, true, true, true, true, true, true],
+    [true, true, true, true, false, true, true],
+];
+
+/// 整数で計算する7セグメント表示ドライバ（SevenSegDriver の速い実装、ピン配置も同じ）
+///
+/// 桁は10で割って取り出し、選択中の桁は添字で持つ
+#[derive(Debug, Clone)]
+pub struct FastSevenSegDriver {
+    digits: usize,
+    current: usize,
+    blank_leading_zeros: bool,
+}
+
+impl FastSevenSegDriver {
+    /// num_digits 桁のドライバを作る（桁0を選択した状態、num_digits は1以上）
+    pub fn new(num_digits: usize) -> Self {
+        assert!(num_digits > 0, "a seven-segment driver needs at least one digit");
+        FastSevenSegDriver { digits: num_digits, current: 0, blank_leading_zeros: false }
+    }
+
+    /// 上位の桁の0を消灯するかどうかを設定する（SevenSegDriver::set_leading_zero_blanking と同じ）
+    pub fn set_leading_zero_blanking(&mut self, enable: bool) {
+        self.blank_leading_zeros = enable;
+    }
+}
+
+impl ClockedChip for FastSevenSegDriver {
+    fn input_width(&self) -> usize {
+        16
+    }
+
+    fn output_width(&self) -> usize {
+        self.digits + 7
+    }
+
+    fn tick(&mut self, inputs: &[bool]) -> Vec<bool> {
+        // 表示する下位 digits 桁のうち、選択中の桁とそれより上の部分
+        let mut upper = u16_from_bits_slice(inputs) as u64;
+        for _ in 0..self.current {
+            upper /= 10;
+        }
+        let upper = 10u64.checked_pow((self.digits - self.current) as u32).map_or(upper, |m| upper % m);
+
+        let blank = self.blank_leading_zeros && self.current > 0 && upper == 0;
+        let mut pins = if blank { vec![false; 7] } else { SEGMENT_PATTERNS[(upper % 10) as usize].to_vec() };
+        pins.extend((0..self.digits).map(|k| k == self.current));
+        self.current = (self.current + 1) % self.digits;
+        pins
+    }
+}
+
+/// 2つの部品が同じ入力列に対して同じ出力を返すことを確かめる
+///
+/// 組み合わせ回路は ClockedChip としても渡せる。メモリは MemoryPort で包んで渡す
+/// ピンの本数が異なる場合や、stimulus のどれかの入力で出力が食い違った場合は、
+/// そのステップの入力を示して panic する（テストから使う）
+///
+/// * `a` - 基準にする部品（ゲートレベルの実装など）
+/// * `b` - 比べる部品（速い実装など）
+/// * `stimulus` - クロックごとの入力ピンの値の列
+pub fn assert_chip_equivalence(a: &mut impl ClockedChip, b: &mut impl ClockedChip, stimulus: &[Vec<bool>]) {
+    assert_eq!(a.input_width(), b.input_width(), "input pin counts differ");
+    assert_eq!(a.output_width(), b.output_width(), "output pin counts differ");
+    for (step, inputs) in stimulus.iter().enumerate() {
+        assert_eq!(a.tick(inputs), b.tick(inputs), "step {}: inputs {:?}", step, inputs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alu::AluOp;
+    use crate::ecc::secded_encode;
+    use crate::testutil::XorShift;
+
+    fn random_trace(seed: u64, steps: usize) -> Vec<Vec<bool>> {
+        let mut rng = XorShift::new(seed);
+        (0..steps).map(|_| bits_from_u64_n(rng.next_bits(38), 38)).collect()
+    }
+
+    /// メモリポートへのランダムな入力列（アドレスは下位 addr_bits ビットだけ使う）
+    fn memory_trace(rng: &mut XorShift, steps: usize, addr_bits: usize, load: bool) -> Vec<Vec<bool>> {
+        (0..steps)
+            .map(|_| {
+                let mut inputs = bits_from_u64_n(rng.next_bits(16), 16);
+                inputs.extend(bits_from_u64_n(rng.next_bits(addr_bits), 16));
+                inputs.push(load && rng.next_bits(1) == 1);
+                inputs
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fast_alu16_matches_gate_level_on_random_trace() {
+        assert_chip_equivalence(&mut Alu16, &mut FastAlu16, &random_trace(0xC41F, 2000));
+    }
+
+    #[test]
+    fn test_alu16_pins() {
+        // 7 + 5 = 12
+        let mut inputs = bits_from_u64_n(7, 16);
+        inputs.extend(bits_from_u64_n(5, 16));
+        let AluControl { zx, nx, zy, ny, f, no } = AluOp::XPlusY.control_bits();
+        inputs.extend([zx, nx, zy, ny, f, no]);
+
+        let mut expected = bits_from_u64_n(12, 16);
+        expected.extend([false, false]);
+        assert_eq!(Alu16.eval(&inputs), expected);
+        assert_eq!(FastAlu16.eval(&inputs), expected);
+        assert_eq!(ClockedChip::output_width(&FastAlu16), 18);
+    }
+
+    #[test]
+    fn test_fast_parity_ram_matches_parity_ram() {
+        let mut rng = XorShift::new(0x9A41);
+        let mut gate = MemoryPort(ParityRam::new(64));
+        let mut fast = MemoryPort(FastParityRam::new(64));
+        assert_chip_equivalence(&mut gate, &mut fast, &memory_trace(&mut rng, 2000, 6, true));
+
+        // 同じ位置に誤りを入れると、どちらも同じように検出する（2ビット反転は見逃す）
+        for addr in 0..64u16 {
+            let count = rng.next_bits(2) as usize;
+            for _ in 0..count {
+                let bit = (rng.next_u64() % 17) as usize;
+                gate.0.inject_error(addr, bit);
+                fast.0.inject_error(addr, bit);
+            }
+        }
+        assert_chip_equivalence(&mut gate, &mut fast, &memory_trace(&mut rng, 500, 6, false));
+    }
+
+    #[test]
+    fn test_fast_ecc_ram_matches_ecc_ram() {
+        let mut rng = XorShift::new(0xECC2);
+        let mut gate = MemoryPort(EccRam::new(64));
+        let mut fast = MemoryPort(FastEccRam::new(64));
+        assert_chip_equivalence(&mut gate, &mut fast, &memory_trace(&mut rng, 2000, 6, true));
+
+        // 1ビット誤りの訂正位置、2ビット誤りの検出、3ビット以上の誤判定まで一致する
+        for addr in 0..64u16 {
+            let count = rng.next_bits(2) as usize;
+            for _ in 0..count {
+                let bit = (rng.next_u64() % 22) as usize;
+                gate.0.inject_error(addr, bit);
+                fast.0.inject_error(addr, bit);
+            }
+        }
+        assert_chip_equivalence(&mut gate, &mut fast, &memory_trace(&mut rng, 500, 6, false));
+    }
+
+    #[test]
+    fn test_fast_ecc_ram_codeword_layout() {
+        // 符号語のビット配置は secded_encode と同じ
+        let mut ram = FastEccRam::new(1);
+        for v in [0x0000u16, 0xFFFF, 0x1234, 0xC0DE] {
+            ram.store(0, &bits_from_u16(v));
+            assert_eq!(ram.codeword(0), secded_encode(&bits_from_u16(v)), "v = {:#06x}", v);
+        }
+    }
+
+    /// 加算器・減算器へのランダムな入力列（clear はおよそ8クロックに1回）
+    fn serial_trace(seed: u64, steps: usize) -> Vec<Vec<bool>> {
+        let mut rng = XorShift::new(seed);
+        (0..steps)
+            .map(|_| vec![rng.next_bits(1) == 1, rng.next_bits(1) == 1, rng.next_bits(3) == 0])
+            .collect()
+    }
+
+    #[test]
+    fn test_fast_serial_adder_matches_serial_adder() {
+        assert_chip_equivalence(&mut SerialAdder::new(), &mut FastSerialAdder::new(), &serial_trace(0x5E41, 2000));
+    }
+
+    #[test]
+    fn test_fast_serial_subtractor_matches_serial_subtractor() {
+        assert_chip_equivalence(&mut SerialSubtractor::new(), &mut FastSerialSubtractor::new(), &serial_trace(0x5B7, 2000));
+    }
+
+    #[test]
+    fn test_serial_adder_pins() {
+        // 3 + 1 = 4 を LSB から: (1,1) -> 0 桁上げ1, (1,0) -> 0 桁上げ1, (0,0) -> 1 桁上げ0
+        let mut adder = SerialAdder::new();
+        let ticks = [[true, true, true], [true, false, false], [false, false, false]];
+        let outputs: Vec<Vec<bool>> = ticks.iter().map(|t| ClockedChip::tick(&mut adder, t)).collect();
+        assert_eq!(outputs, [[false, true], [false, true], [true, false]]);
+    }
+
+    #[test]
+    fn test_fast_gcd_unit_matches_gcd_unit() {
+        // 8ビットの値を読み込んでは、Done を過ぎるまで（5n + 2 = 42 ステップより多く）進める
+        let mut rng = XorShift::new(0x6CD);
+        let mut stimulus = Vec::new();
+        for _ in 0..60 {
+            let mut load = vec![true];
+            load.extend(bits_from_u64_n(rng.next_bits(8), 8));
+            load.extend(bits_from_u64_n(rng.next_bits(8), 8));
+            stimulus.push(load);
+            for _ in 0..rng.next_bits(6) as usize {
+                stimulus.push(vec![false; 17]);
+            }
+        }
+        let mut gate = GcdUnit::new(&[false; 8], &[false; 8]);
+        let mut fast = FastGcdUnit::new(&[false; 8], &[false; 8]);
+        assert_chip_equivalence(&mut gate, &mut fast, &stimulus);
+    }
+
+    #[test]
+    fn test_gcd_unit_pins() {
+        // gcd(12, 18) = 6
+        let mut unit = GcdUnit::new(&[false; 8], &[false; 8]);
+        let mut load = vec![true];
+        load.extend(bits_from_u64_n(12, 8));
+        load.extend(bits_from_u64_n(18, 8));
+        let mut outputs = ClockedChip::tick(&mut unit, &load);
+        assert_eq!(outputs[..2], [false, false]);
+        while !outputs[1] {
+            outputs = ClockedChip::tick(&mut unit, &[false; 17]);
+        }
+        assert_eq!(outputs[2..], bits_from_u64_n(6, 8));
+        assert_eq!((ClockedChip::input_width(&unit), ClockedChip::output_width(&unit)), (17, 10));
+    }
+
+    #[test]
+    fn test_fast_seven_seg_driver_matches_seven_seg_driver() {
+        let mut rng = XorShift::new(0x7E6);
+        for digits in [1, 3, 4, 6] {
+            for blanking in [false, true] {
+                let mut gate = SevenSegDriver::new(digits);
+                let mut fast = FastSevenSegDriver::new(digits);
+                gate.set_leading_zero_blanking(blanking);
+                fast.set_leading_zero_blanking(blanking);
+                // 小さい値（上位の桁が0）と16ビット全体の値を混ぜ、同じ値を数クロック保つ
+                let stimulus: Vec<Vec<bool>> = (0..60)
+                    .flat_map(|_| {
+                        let value = if rng.next_bits(1) == 0 { rng.next_bits(7) } else { rng.next_bits(16) };
+                        vec![bits_from_u64_n(value, 16); rng.next_bits(3) as usize + 1]
+                    })
+                    .collect();
+                assert_chip_equivalence(&mut gate, &mut fast, &stimulus);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seven_seg_driver_pins() {
+        // 2桁で 7 を表示: 桁0は7、桁1は上位の0なので消灯
+        let mut driver = SevenSegDriver::new(2);
+        driver.set_leading_zero_blanking(true);
+        let seven = bits_from_u16(7);
+        let mut expected = SEGMENT_PATTERNS[7].to_vec();
+        expected.extend([true, false]);
+        assert_eq!(ClockedChip::tick(&mut driver, &seven), expected);
+        let mut expected = vec![false; 7];
+        expected.extend([false, true]);
+        assert_eq!(ClockedChip::tick(&mut driver, &seven), expected);
+        assert_eq!((ClockedChip::input_width(&driver), ClockedChip::output_width(&driver)), (16, 9));
+    }
+
+    #[test]
+    #[should_panic(expected = "step 1")]
+    fn test_assert_chip_equivalence_reports_mismatch() {
+        // x | y だけを x ^ y と取り違えた部品
+        struct BrokenAlu;
+        impl CombinationalChip for BrokenAlu {
+            fn input_width(&self) -> usize {
+                38
+            }
+            fn output_width(&self) -> usize {
+                18
+            }
+            fn eval(&self, inputs: &[bool]) -> Vec<bool> {
+                let (x, y, ctl) = alu16_pins(inputs);
+                if ctl == AluControl::X_OR_Y {
+                    let out = x ^ y;
+                    return alu16_outputs(&bits_from_u16(out), out == 0, out & 0x8000 != 0);
+                }
+                FastAlu16.eval(inputs)
+            }
+        }
+
+        let pins = |x: u64, y: u64, ctl: AluControl| {
+            let mut inputs = bits_from_u64_n(x, 16);
+            inputs.extend(bits_from_u64_n(y, 16));
+            inputs.extend([ctl.zx, ctl.nx, ctl.zy, ctl.ny, ctl.f, ctl.no]);
+            inputs
+        };
+        let stimulus = [pins(1, 2, AluControl::X_OR_Y), pins(1, 1, AluControl::X_OR_Y)];
+        assert_chip_equivalence(&mut FastAlu16, &mut BrokenAlu, &stimulus);
+    }
+}
@@ -0,0 +1,489 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{and, not, or, xnor};
+use crate::bus::{and_reduce, mux_bus, xnor_n};
+use crate::wiring::{sign_extend, zero_extend};
+
+/// 大小比較器の出力
+///
+/// lt, eq, gt のうち、ちょうど1つだけがtrueになる
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    /// a < b
+    pub lt: bool,
+    /// a == b
+    pub eq: bool,
+    /// a > b
+    pub gt: bool,
+}
+
+impl Comparison {
+    /// core::cmp::Ordering に変換する
+    pub fn ordering(&self) -> Ordering {
+        if self.lt {
+            Ordering::Less
+        } else if self.gt {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// 一致比較器（nビット）
+///
+/// ビットごとの XNOR をすべて AND リダクションに通し、すべてのビットが一致していればtrue
+///
+/// * `a`, `b` - 比較する入力（LSB→MSB順）
+///
+/// 長さが異なる場合は短い方を0で埋めてから比べる（n_bit_adder と同じ規則）
+/// そのため、値が同じなら幅が違っても等しい（例えば 0101 と 000_0101 は等しい）
+/// 空のスライスどうしは等しい
+pub fn eq_n(a: &[bool], b: &[bool]) -> bool {
+    and_reduce(&xnor_n(a, b))
+}
+
+/// 一致比較器（16ビット）
+///
+/// eq_n の16ビット固定幅版
+pub fn eq16(a: &[bool; 16], b: &[bool; 16]) -> bool {
+    eq_n(a, b)
+}
+
+/// 符号なしの大小比較器
+///
+/// MSB から順に、それより上のビットがすべて一致している間だけ、そのビットで大小を決める優先回路
+/// - gt: 上位がすべて一致し、そのビットで a = 1, b = 0 となる位置がある
+/// - lt: 上位がすべて一致し、そのビットで a = 0, b = 1 となる位置がある
+/// - eq: すべてのビットが一致する
+///
+/// * `a`, `b` - 比較する入力（LSB→MSB順）
+///
+/// 長さが異なる場合は短い方を0で埋める
+pub fn compare_unsigned(a: &[bool], b: &[bool]) -> Comparison {
+    let n = a.len().max(b.len());
+    let mut eq = true;
+    let mut lt = false;
+    let mut gt = false;
+    for i in (0..n).rev() {
+        let bit_a = i < a.len() && a[i];
+        let bit_b = i < b.len() && b[i];
+        gt = or(gt, and(eq, and(bit_a, not(bit_b))));
+        lt = or(lt, and(eq, and(not(bit_a), bit_b)));
+        eq = and(eq, xnor(bit_a, bit_b));
+    }
+    Comparison { lt, eq, gt }
+}
+
+/// カスケード入力付きの4ビット比較器（74x85 相当）
+///
+/// 4ビットの a と b を compare_unsigned で比べ、異なればその結果をそのまま出力する
+/// 等しい場合は、下位の比較器からのカスケード入力で決める（74x85 の真理値表と同じ）
+///
+/// | eq_in | gt_in | lt_in | (lt, eq, gt) |
+/// |-------|-------|-------|--------------|
+/// | 1     | x     | x     | (0, 1, 0)    |
+/// | 0     | 1     | 0     | (0, 0, 1)    |
+/// | 0     | 0     | 1     | (1, 0, 0)    |
+/// | 0     | 1     | 1     | (0, 0, 0)    |
+/// | 0     | 0     | 0     | (1, 0, 1)    |
+///
+/// 最下位の比較器には (lt_in, eq_in, gt_in) = (0, 1, 0) を与える
+///
+/// 戻り値は (lt, eq, gt) のタプル
+pub fn compare4_cascadable(a: &[bool; 4], b: &[bool; 4], lt_in: bool, eq_in: bool, gt_in: bool) -> (bool, bool, bool) {
+    let local = compare_unsigned(a, b);
+    let tie = local.eq;
+    let not_eq_in = not(eq_in);
+    let lt = or(local.lt, and(tie, and(not_eq_in, not(gt_in))));
+    let eq = and(tie, eq_in);
+    let gt = or(local.gt, and(tie, and(not_eq_in, not(lt_in))));
+    (lt, eq, gt)
+}
+
+/// カスケード接続した比較器（符号なし）
+///
+/// a と b を4ビットずつのスライスに分け、compare4_cascadable を下位から順に並べる
+/// 各段の出力を1つ上の段のカスケード入力につなぎ、最上位の段の出力を結果にする
+///
+/// 長さが異なる場合や、幅が4の倍数でない場合は、上位を0で埋める。結果は compare_unsigned と同じ
+pub fn compare_cascaded(a: &[bool], b: &[bool]) -> Comparison {
+    let n = a.len().max(b.len());
+    let nibble = |x: &[bool], i: usize| -> [bool; 4] {
+        core::array::from_fn(|j| x.get(i * 4 + j).copied().unwrap_or(false))
+    };
+
+    let (mut lt, mut eq, mut gt) = (false, true, false);
+    for i in 0..n.div_ceil(4) {
+        (lt, eq, gt) = compare4_cascadable(&nibble(a, i), &nibble(b, i), lt, eq, gt);
+    }
+    Comparison { lt, eq, gt }
+}
+
+/// 符号付き（2の補数）の大小比較器
+///
+/// 2の補数では符号ビットの重みが負なので、両方の符号ビットを NOT で反転してから
+/// compare_unsigned で比べる（反転すると -2^(n-1)〜2^(n-1)-1 が 0〜2^n-1 へ順序を保って移る）
+///
+/// * `a`, `b` - 比較する2の補数の入力（LSB→MSB順）
+///
+/// 長さが異なる場合は、短い方を符号拡張する（0で埋めると負の数の値が変わるため）
+pub fn compare_signed(a: &[bool], b: &[bool]) -> Comparison {
+    let n = a.len().max(b.len());
+    let flip_sign = |x: &[bool]| {
+        let mut extended = sign_extend(x, n);
+        if let Some(sign) = extended.last_mut() {
+            *sign = not(*sign);
+        }
+        extended
+    };
+    compare_unsigned(&flip_sign(a), &flip_sign(b))
+}
+
+/// 符号なしの最小値
+///
+/// compare_unsigned の gt を選択信号にした mux_bus で、a > b なら b、そうでなければ a を選ぶ
+/// 等しい場合は a を返す。出力は長い方の幅（短い方は0で埋める）
+pub fn min_unsigned(a: &[bool], b: &[bool]) -> Vec<bool> {
+    mux_bus(compare_unsigned(a, b).gt, a, b)
+}
+
+/// 符号なしの最大値
+///
+/// compare_unsigned の lt を選択信号にした mux_bus で、a < b なら b、そうでなければ a を選ぶ
+/// 等しい場合は a を返す。出力は長い方の幅（短い方は0で埋める）
+pub fn max_unsigned(a: &[bool], b: &[bool]) -> Vec<bool> {
+    mux_bus(compare_unsigned(a, b).lt, a, b)
+}
+
+/// 符号付きの最小値
+///
+/// compare_signed の gt で選ぶ。等しい場合は a を返す。出力は長い方の幅（短い方は符号拡張する）
+pub fn min_signed(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+    mux_bus(compare_signed(a, b).gt, &sign_extend(a, n), &sign_extend(b, n))
+}
+
+/// 符号付きの最大値
+///
+/// compare_signed の lt で選ぶ。等しい場合は a を返す。出力は長い方の幅（短い方は符号拡張する）
+pub fn max_signed(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let n = a.len().max(b.len());
+    mux_bus(compare_signed(a, b).lt, &sign_extend(a, n), &sign_extend(b, n))
+}
+
+/// ワードの一覧の最小値（符号なし）
+///
+/// 先頭のワードから順に min_unsigned を畳み込む（等しいワードの中では先に現れたものが残る）
+///
+/// 出力は最も長いワードの幅。空の一覧には幅0の空のVecを返す
+pub fn min_of(words: &[Vec<bool>]) -> Vec<bool> {
+    let Some((first, rest)) = words.split_first() else {
+        return Vec::new();
+    };
+    rest.iter().fold(first.clone(), |min, word| min_unsigned(&min, word))
+}
+
+/// 符号付きのクランプ
+///
+/// x を [lo, hi] の範囲に収める。比較器2つと mux_bus 2段の回路で、
+/// まず x > hi なら hi を選び（t = min(x, hi)）、次に t < lo なら lo を選ぶ
+///
+/// * `x` - 入力（2の補数、LSB→MSB順）
+/// * `lo`, `hi` - 範囲の下限と上限（2の補数、LSB→MSB順）
+///
+/// lo > hi の場合はパニックせず、常に lo を返す（t は hi 以下なので必ず lo より小さくなる）
+/// 出力は3つの入力のうち最も長い幅（短い入力は符号拡張する）
+pub fn clamp_signed(x: &[bool], lo: &[bool], hi: &[bool]) -> Vec<bool> {
+    let n = x.len().max(lo.len()).max(hi.len());
+    let (x, lo, hi) = (sign_extend(x, n), sign_extend(lo, n), sign_extend(hi, n));
+    let upper = mux_bus(compare_signed(&x, &hi).gt, &x, &hi);
+    mux_bus(compare_signed(&upper, &lo).lt, &upper, &lo)
+}
+
+/// 符号なしのクランプ
+///
+/// clamp_signed と同じ回路を compare_unsigned で作る。lo > hi の場合は常に lo を返す
+/// 出力は3つの入力のうち最も長い幅（短い入力は0で埋める）
+pub fn clamp_unsigned(x: &[bool], lo: &[bool], hi: &[bool]) -> Vec<bool> {
+    let n = x.len().max(lo.len()).max(hi.len());
+    let (x, lo, hi) = (zero_extend(x, n), zero_extend(lo, n), zero_extend(hi, n));
+    let upper = mux_bus(compare_unsigned(&x, &hi).gt, &x, &hi);
+    mux_bus(compare_unsigned(&upper, &lo).lt, &upper, &lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u16, bits_from_u64_n, from_signed, to_signed, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_eq_n_basic() {
+        assert!(eq_n(&bits_from_u64_n(0xA5, 8), &bits_from_u64_n(0xA5, 8)));
+        // 最上位ビットだけ、最下位ビットだけが異なる
+        assert!(!eq_n(&bits_from_u64_n(0x25, 8), &bits_from_u64_n(0xA5, 8)));
+        assert!(!eq_n(&bits_from_u64_n(0xA4, 8), &bits_from_u64_n(0xA5, 8)));
+        assert!(eq_n(&[], &[]));
+    }
+
+    #[test]
+    fn test_eq_n_mismatched_widths() {
+        // 0で埋めると等しい
+        assert!(eq_n(&bits_from_u64_n(0b0101, 4), &bits_from_u64_n(0b000_0101, 7)));
+        assert!(eq_n(&bits_from_u64_n(0, 3), &[]));
+        // 長い方の上位に1がある
+        assert!(!eq_n(&bits_from_u64_n(0b0101, 4), &bits_from_u64_n(0b100_0101, 7)));
+        assert!(!eq_n(&[], &[true]));
+    }
+
+    #[test]
+    fn test_eq16() {
+        for (x, y) in [(0u16, 0u16), (0xFFFF, 0xFFFF), (0x1234, 0x1234), (0x8000, 0x0000), (0x0001, 0x0000), (0x1234, 0x1235)] {
+            assert_eq!(eq16(&bits_from_u16(x), &bits_from_u16(y)), x == y, "{:#06x} == {:#06x}", x, y);
+        }
+    }
+
+    fn expected(ordering: Ordering) -> Comparison {
+        Comparison {
+            lt: ordering == Ordering::Less,
+            eq: ordering == Ordering::Equal,
+            gt: ordering == Ordering::Greater,
+        }
+    }
+
+    #[test]
+    fn test_compare_unsigned_exhaustive_4bit() {
+        for x in 0..16u8 {
+            for y in 0..16u8 {
+                let c = compare_unsigned(&bits_from_u64_n(x as u64, 4), &bits_from_u64_n(y as u64, 4));
+                assert_eq!(c, expected(x.cmp(&y)), "{} vs {}", x, y);
+                assert_eq!(c.ordering(), x.cmp(&y));
+                assert_eq!(c.lt as u8 + c.eq as u8 + c.gt as u8, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_unsigned_random_16bit() {
+        let mut rng = XorShift::new(0xC3A9);
+        for _ in 0..300 {
+            let (a, b) = (bits_from_u64_n(rng.next_bits(16), 16), bits_from_u64_n(rng.next_bits(16), 16));
+            assert_eq!(compare_unsigned(&a, &b), expected(u64_from_bits_slice(&a).cmp(&u64_from_bits_slice(&b))));
+        }
+        for (x, y) in [(0u64, 0xFFFF), (0xFFFF, 0), (0xFFFF, 0xFFFF), (0, 0)] {
+            assert_eq!(compare_unsigned(&bits_from_u64_n(x, 16), &bits_from_u64_n(y, 16)), expected(x.cmp(&y)));
+        }
+    }
+
+    #[test]
+    fn test_compare_unsigned_mismatched_widths() {
+        assert_eq!(compare_unsigned(&bits_from_u64_n(5, 3), &bits_from_u64_n(5, 8)), expected(Ordering::Equal));
+        assert_eq!(compare_unsigned(&bits_from_u64_n(7, 3), &bits_from_u64_n(8, 8)), expected(Ordering::Less));
+        assert_eq!(compare_unsigned(&bits_from_u64_n(200, 8), &bits_from_u64_n(7, 3)), expected(Ordering::Greater));
+        assert_eq!(compare_unsigned(&[], &[]), expected(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_compare_signed_exhaustive_4bit() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let (a, b) = (bits_from_u64_n(x, 4), bits_from_u64_n(y, 4));
+                let (sx, sy) = (to_signed(&a) as i8, to_signed(&b) as i8);
+                assert_eq!(compare_signed(&a, &b), expected(sx.cmp(&sy)), "{} vs {}", sx, sy);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_signed_boundaries() {
+        // 最小値 < 最大値（符号なしで比べると逆になる）
+        let (min, max) = (from_signed(i16::MIN as i64, 16), from_signed(i16::MAX as i64, 16));
+        assert_eq!(compare_signed(&min, &max), expected(Ordering::Less));
+        assert_eq!(compare_unsigned(&min, &max), expected(Ordering::Greater));
+
+        // -1 < 0
+        assert_eq!(compare_signed(&from_signed(-1, 16), &from_signed(0, 16)), expected(Ordering::Less));
+        assert_eq!(compare_signed(&from_signed(0, 16), &from_signed(-1, 16)), expected(Ordering::Greater));
+        assert_eq!(compare_signed(&[], &[]), expected(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_compare_signed_mismatched_widths() {
+        // 短い方は符号拡張する: 4ビットの -1 (1111) と 8ビットの -1 (1111_1111) は等しい
+        assert_eq!(compare_signed(&from_signed(-1, 4), &from_signed(-1, 8)), expected(Ordering::Equal));
+        assert_eq!(compare_signed(&from_signed(-3, 4), &from_signed(-100, 8)), expected(Ordering::Greater));
+        assert_eq!(compare_signed(&from_signed(-8, 4), &from_signed(-7, 8)), expected(Ordering::Less));
+        assert_eq!(compare_signed(&from_signed(-1, 4), &from_signed(15, 8)), expected(Ordering::Less));
+
+        let mut rng = XorShift::new(0x5163);
+        for _ in 0..200 {
+            let a = bits_from_u64_n(rng.next_bits(5), 5);
+            let b = bits_from_u64_n(rng.next_bits(12), 12);
+            let ordering = to_signed(&a).cmp(&to_signed(&b));
+            assert_eq!(compare_signed(&a, &b), expected(ordering));
+            assert_eq!(compare_signed(&b, &a), expected(ordering.reverse()));
+        }
+    }
+
+    #[test]
+    fn test_min_max_exhaustive_4bit() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let (a, b) = (bits_from_u64_n(x, 4), bits_from_u64_n(y, 4));
+                assert_eq!(u64_from_bits_slice(&min_unsigned(&a, &b)), x.min(y));
+                assert_eq!(u64_from_bits_slice(&max_unsigned(&a, &b)), x.max(y));
+
+                let (sx, sy) = (to_signed(&a), to_signed(&b));
+                assert_eq!(to_signed(&min_signed(&a, &b)), sx.min(sy), "min({}, {})", sx, sy);
+                assert_eq!(to_signed(&max_signed(&a, &b)), sx.max(sy), "max({}, {})", sx, sy);
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_max_tie_returns_a() {
+        // 値が等しく幅だけが違う場合、a の方が選ばれる（出力は長い方の幅）
+        let (a, b) = (bits_from_u64_n(5, 4), bits_from_u64_n(5, 8));
+        assert_eq!(min_unsigned(&a, &b), bits_from_u64_n(5, 8));
+        assert_eq!(max_unsigned(&b, &a), b);
+        assert_eq!(min_signed(&from_signed(-2, 4), &from_signed(-2, 8)), from_signed(-2, 8));
+    }
+
+    #[test]
+    fn test_min_max_signed_mixed_signs() {
+        let (negative, positive) = (from_signed(-100, 8), from_signed(3, 8));
+        assert_eq!(min_signed(&negative, &positive), negative);
+        assert_eq!(max_signed(&negative, &positive), positive);
+        // 符号なしでは逆になる
+        assert_eq!(min_unsigned(&negative, &positive), positive);
+        // 幅の異なる負の数は符号拡張して返す
+        assert_eq!(min_signed(&from_signed(-1, 4), &from_signed(20, 8)), from_signed(-1, 8));
+    }
+
+    #[test]
+    fn test_min_of() {
+        let words: Vec<Vec<bool>> = [9u64, 4, 12, 4, 7].iter().map(|&v| bits_from_u64_n(v, 4)).collect();
+        assert_eq!(u64_from_bits_slice(&min_of(&words)), 4);
+        assert_eq!(min_of(&words[..1]), bits_from_u64_n(9, 4));
+        assert_eq!(min_of(&[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_clamp_signed() {
+        let clamp = |x: i64, lo: i64, hi: i64| {
+            to_signed(&clamp_signed(&from_signed(x, 8), &from_signed(lo, 8), &from_signed(hi, 8)))
+        };
+        // 範囲の下、中、上、境界と等しい値
+        assert_eq!(clamp(-50, -10, 20), -10);
+        assert_eq!(clamp(5, -10, 20), 5);
+        assert_eq!(clamp(100, -10, 20), 20);
+        assert_eq!(clamp(-10, -10, 20), -10);
+        assert_eq!(clamp(20, -10, 20), 20);
+        // 負の範囲
+        assert_eq!(clamp(-1, -100, -50), -50);
+        assert_eq!(clamp(-128, -100, -50), -100);
+        assert_eq!(clamp(-70, -100, -50), -70);
+        // lo == hi
+        for x in [-128, -3, 0, 3, 127] {
+            assert_eq!(clamp(x, 7, 7), 7);
+        }
+        // lo > hi なら常に lo
+        for x in [-128, 0, 15, 127] {
+            assert_eq!(clamp(x, 10, -10), 10);
+        }
+    }
+
+    #[test]
+    fn test_clamp_exhaustive_4bit() {
+        for x in 0..16u64 {
+            for lo in 0..16u64 {
+                for hi in lo..16u64 {
+                    let (a, l, h) = (bits_from_u64_n(x, 4), bits_from_u64_n(lo, 4), bits_from_u64_n(hi, 4));
+                    assert_eq!(u64_from_bits_slice(&clamp_unsigned(&a, &l, &h)), x.clamp(lo, hi));
+                    let (sx, sl, sh) = (to_signed(&a), to_signed(&l), to_signed(&h));
+                    if sl <= sh {
+                        assert_eq!(to_signed(&clamp_signed(&a, &l, &h)), sx.clamp(sl, sh));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_clamp_unsigned() {
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(3, 8), &bits_from_u64_n(10, 8), &bits_from_u64_n(200, 8)), bits_from_u64_n(10, 8));
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(250, 8), &bits_from_u64_n(10, 8), &bits_from_u64_n(200, 8)), bits_from_u64_n(200, 8));
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(200, 8), &bits_from_u64_n(10, 8), &bits_from_u64_n(200, 8)), bits_from_u64_n(200, 8));
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(5, 8), &bits_from_u64_n(9, 8), &bits_from_u64_n(9, 8)), bits_from_u64_n(9, 8));
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(5, 8), &bits_from_u64_n(9, 8), &bits_from_u64_n(2, 8)), bits_from_u64_n(9, 8));
+        // 幅の異なる入力は0で埋め、最も長い幅で返す
+        assert_eq!(clamp_unsigned(&bits_from_u64_n(1, 2), &bits_from_u64_n(2, 4), &bits_from_u64_n(300, 12)), bits_from_u64_n(2, 12));
+    }
+
+    #[test]
+    fn test_compare4_cascadable_local_dominates() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                if x == y {
+                    continue;
+                }
+                let a: [bool; 4] = core::array::from_fn(|i| (x >> i) & 1 == 1);
+                let b: [bool; 4] = core::array::from_fn(|i| (y >> i) & 1 == 1);
+                for v in 0..8 {
+                    let (lt_in, eq_in, gt_in) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+                    let out = compare4_cascadable(&a, &b, lt_in, eq_in, gt_in);
+                    assert_eq!(out, (x < y, false, x > y), "{} vs {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare4_cascadable_tie_breaking() {
+        let nibble = [true, false, true, true];
+        // (lt_in, eq_in, gt_in) → (lt, eq, gt)
+        let table = [
+            ((false, false, false), (true, false, true)),
+            ((true, false, false), (true, false, false)),
+            ((false, false, true), (false, false, true)),
+            ((true, false, true), (false, false, false)),
+            ((false, true, false), (false, true, false)),
+            ((true, true, false), (false, true, false)),
+            ((false, true, true), (false, true, false)),
+            ((true, true, true), (false, true, false)),
+        ];
+        for ((lt_in, eq_in, gt_in), expected) in table {
+            assert_eq!(
+                compare4_cascadable(&nibble, &nibble, lt_in, eq_in, gt_in),
+                expected,
+                "cascade = ({}, {}, {})",
+                lt_in,
+                eq_in,
+                gt_in
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_cascaded_matches_compare_unsigned() {
+        let mut rng = XorShift::new(0x7485);
+        for _ in 0..300 {
+            let x = rng.next_bits(16);
+            // 上位だけ、下位だけが異なる組も作る
+            let y = match rng.next_bits(2) {
+                0 => x,
+                1 => x ^ 0x0001,
+                2 => x ^ 0x8000,
+                _ => rng.next_bits(16),
+            };
+            let (a, b) = (bits_from_u64_n(x, 16), bits_from_u64_n(y, 16));
+            assert_eq!(compare_cascaded(&a, &b), compare_unsigned(&a, &b), "{:#06x} vs {:#06x}", x, y);
+        }
+        // 4の倍数でない幅と、長さの異なる入力
+        assert_eq!(compare_cascaded(&bits_from_u64_n(5, 3), &bits_from_u64_n(5, 10)), compare_unsigned(&bits_from_u64_n(5, 3), &bits_from_u64_n(5, 10)));
+        assert_eq!(compare_cascaded(&bits_from_u64_n(600, 10), &bits_from_u64_n(7, 3)), compare_unsigned(&bits_from_u64_n(600, 10), &bits_from_u64_n(7, 3)));
+        assert_eq!(compare_cascaded(&[], &[]), expected(Ordering::Equal));
+    }
+}
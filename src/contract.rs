@@ -0,0 +1,190 @@
+use alloc::{format, string::String};
+use core::fmt;
+
+/// 真理値表（コントラクト）に違反した最初の行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractViolation {
+    /// 表の行番号（0始まり）
+    pub row: usize,
+    pub inputs: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {}: inputs {} expected {} but got {}",
+            self.row, self.inputs, self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for ContractViolation {}
+
+/// 2出力セレクタの真理値表の1行（(sel, d) → (o0, o1)）
+pub type DemuxRow = ((bool, bool), (bool, bool));
+
+fn check_rows<I: Copy + fmt::Debug, O: PartialEq + fmt::Debug>(
+    f: impl Fn(I) -> O,
+    table: &[(I, O)],
+) -> Result<(), ContractViolation> {
+    for (row, (inputs, expected)) in table.iter().enumerate() {
+        let actual = f(*inputs);
+        if actual != *expected {
+            return Err(ContractViolation {
+                row,
+                inputs: format!("{:?}", inputs),
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", actual),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 1入力ゲートを真理値表と照合する
+///
+/// 最初に食い違った行を ContractViolation として返す
+pub fn check_contract1(f: impl Fn(bool) -> bool, table: &[(bool, bool)]) -> Result<(), ContractViolation> {
+    check_rows(f, table)
+}
+
+/// 2入力ゲートを真理値表と照合する
+///
+/// 最初に食い違った行を ContractViolation として返す
+pub fn check_contract2(
+    f: impl Fn(bool, bool) -> bool,
+    table: &[((bool, bool), bool)],
+) -> Result<(), ContractViolation> {
+    check_rows(|(a, b)| f(a, b), table)
+}
+
+/// 3入力ゲート（mux の (sel, a, b) など）を真理値表と照合する
+///
+/// 最初に食い違った行を ContractViolation として返す
+pub fn check_contract3(
+    f: impl Fn(bool, bool, bool) -> bool,
+    table: &[((bool, bool, bool), bool)],
+) -> Result<(), ContractViolation> {
+    check_rows(|(a, b, c)| f(a, b, c), table)
+}
+
+/// 2出力のセレクタ（demux の (sel, d) → (o0, o1)）を真理値表と照合する
+///
+/// 最初に食い違った行を ContractViolation として返す
+pub fn check_contract_demux(
+    f: impl Fn(bool, bool) -> (bool, bool),
+    table: &[DemuxRow],
+) -> Result<(), ContractViolation> {
+    check_rows(|(sel, d)| f(sel, d), table)
+}
+
+/// NOT の真理値表
+pub const NOT_TABLE: [(bool, bool); 2] = [(false, true), (true, false)];
+
+/// NAND の真理値表
+pub const NAND_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), true),
+    ((false, true), true),
+    ((true, false), true),
+    ((true, true), false),
+];
+
+/// AND の真理値表
+pub const AND_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), false),
+    ((false, true), false),
+    ((true, false), false),
+    ((true, true), true),
+];
+
+/// OR の真理値表
+pub const OR_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), false),
+    ((false, true), true),
+    ((true, false), true),
+    ((true, true), true),
+];
+
+/// NOR の真理値表
+pub const NOR_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), true),
+    ((false, true), false),
+    ((true, false), false),
+    ((true, true), false),
+];
+
+/// XOR の真理値表
+pub const XOR_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), false),
+    ((false, true), true),
+    ((true, false), true),
+    ((true, true), false),
+];
+
+/// XNOR の真理値表
+pub const XNOR_TABLE: [((bool, bool), bool); 4] = [
+    ((false, false), true),
+    ((false, true), false),
+    ((true, false), false),
+    ((true, true), true),
+];
+
+/// 2:1 マルチプレクサの真理値表（入力は (sel, a, b)）
+pub const MUX_TABLE: [((bool, bool, bool), bool); 8] = [
+    ((false, false, false), false),
+    ((false, false, true), false),
+    ((false, true, false), true),
+    ((false, true, true), true),
+    ((true, false, false), false),
+    ((true, false, true), true),
+    ((true, true, false), false),
+    ((true, true, true), true),
+];
+
+/// 1:2 デマルチプレクサの真理値表（入力は (sel, d)、出力は (o0, o1)）
+pub const DEMUX_TABLE: [DemuxRow; 4] = [
+    ((false, false), (false, false)),
+    ((false, true), (true, false)),
+    ((true, false), (false, false)),
+    ((true, true), (false, true)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrong_closure_reports_first_row() {
+        // sel の意味を逆にした誤った mux
+        let wrong_mux = |sel: bool, a: bool, b: bool| if sel { a } else { b };
+        let violation = check_contract3(wrong_mux, &MUX_TABLE).unwrap_err();
+        assert_eq!(violation.row, 1);
+        assert_eq!(violation.inputs, "(false, false, true)");
+        assert_eq!(violation.expected, "false");
+        assert_eq!(violation.actual, "true");
+        assert_eq!(
+            violation.to_string(),
+            "row 1: inputs (false, false, true) expected false but got true"
+        );
+    }
+
+    #[test]
+    fn test_wrong_demux_reports_first_row() {
+        let swapped = |sel: bool, d: bool| (sel && d, !sel && d);
+        let violation = check_contract_demux(swapped, &DEMUX_TABLE).unwrap_err();
+        assert_eq!(violation.row, 1);
+        assert_eq!(violation.actual, "(false, true)");
+    }
+
+    #[test]
+    fn test_host_reference_satisfies_tables() {
+        assert_eq!(check_contract1(|a| !a, &NOT_TABLE), Ok(()));
+        assert_eq!(check_contract2(|a, b| !(a && b), &NAND_TABLE), Ok(()));
+        assert_eq!(check_contract2(|a, b| a && b, &AND_TABLE), Ok(()));
+        assert_eq!(check_contract2(|a, b| a || b, &OR_TABLE), Ok(()));
+        assert_eq!(check_contract2(|a, b| a != b, &XOR_TABLE), Ok(()));
+    }
+}
@@ -0,0 +1,366 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::{and, not, or};
+use crate::alu::{AluControl, AluOp};
+use crate::bus::mux16;
+
+/// Hack の jump フィールド（j1 j2 j3）
+///
+/// それぞれ「out < 0」「out = 0」「out > 0」のときにジャンプすることを表す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    /// 000: ジャンプしない
+    Null,
+    /// 001: out > 0
+    Jgt,
+    /// 010: out = 0
+    Jeq,
+    /// 011: out >= 0
+    Jge,
+    /// 100: out < 0
+    Jlt,
+    /// 101: out != 0
+    Jne,
+    /// 110: out <= 0
+    Jle,
+    /// 111: 無条件ジャンプ
+    Jmp,
+}
+
+impl Jump {
+    /// 3ビットから Jump を作る
+    ///
+    /// ビットの並びは Hack 仕様の表記順 [j1, j2, j3] = [JLT, JEQ, JGT]
+    /// （命令語のビット2, 1, 0 に対応する）
+    pub fn from_bits(bits: &[bool; 3]) -> Jump {
+        match bits {
+            [false, false, false] => Jump::Null,
+            [false, false, true] => Jump::Jgt,
+            [false, true, false] => Jump::Jeq,
+            [false, true, true] => Jump::Jge,
+            [true, false, false] => Jump::Jlt,
+            [true, false, true] => Jump::Jne,
+            [true, true, false] => Jump::Jle,
+            [true, true, true] => Jump::Jmp,
+        }
+    }
+
+    /// Jump を3ビット [j1, j2, j3] = [JLT, JEQ, JGT] に変換する
+    pub fn to_bits(self) -> [bool; 3] {
+        match self {
+            Jump::Null => [false, false, false],
+            Jump::Jgt => [false, false, true],
+            Jump::Jeq => [false, true, false],
+            Jump::Jge => [false, true, true],
+            Jump::Jlt => [true, false, false],
+            Jump::Jne => [true, false, true],
+            Jump::Jle => [true, true, false],
+            Jump::Jmp => [true, true, true],
+        }
+    }
+}
+
+/// 条件ジャンプの判定
+///
+/// ALUのフラグ zr, ng から「正」を作り、jump フィールドの各ビットとの AND を OR でまとめる
+///
+/// * `jump_bits` - [j1, j2, j3] = [JLT, JEQ, JGT]（Jump::from_bits を参照）
+/// * `zr` - ALUの出力が0
+/// * `ng` - ALUの出力が負
+///
+/// ジャンプするならtrue
+pub fn jump_decision(jump_bits: &[bool; 3], zr: bool, ng: bool) -> bool {
+    let positive = and(not(zr), not(ng));
+    let lt = and(jump_bits[0], ng);
+    let eq = and(jump_bits[1], zr);
+    let gt = and(jump_bits[2], positive);
+    or(or(lt, eq), gt)
+}
+
+/// ジャンプユニット
+///
+/// jump_decision の別名。j は [JLT, JEQ, JGT] の順（Jump::from_bits を参照）で、
+/// [false, false, false] はジャンプせず、[true, true, true]（JMP）は常にジャンプする
+pub fn jump_unit(j: &[bool; 3], zr: bool, ng: bool) -> bool {
+    jump_decision(j, zr, ng)
+}
+
+/// ジャンプの条件（Jump の別名。from_bits / to_bits で3ビットと変換する）
+pub type JumpCondition = Jump;
+
+/// dest フィールドから得られる書き込み信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DestEnables {
+    /// Aレジスタへ書き込む
+    pub load_a: bool,
+    /// Dレジスタへ書き込む
+    pub load_d: bool,
+    /// M（RAM[A]）へ書き込む
+    pub write_m: bool,
+}
+
+/// dest フィールドのデコード
+///
+/// * `dest_bits` - [d1, d2, d3] = [A, D, M]（Hack 仕様の表記順、命令語のビット5, 4, 3）
+///
+/// 各ビットがそのまま対応するレジスタの書き込み信号になる
+pub fn dest_routing(dest_bits: &[bool; 3]) -> DestEnables {
+    DestEnables {
+        load_a: dest_bits[0],
+        load_d: dest_bits[1],
+        write_m: dest_bits[2],
+    }
+}
+
+/// comp フィールドの記法の解析エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompError {
+    /// Hack の28個の comp の記法のどれでもない
+    UnknownMnemonic { mnemonic: String },
+}
+
+impl fmt::Display for CompError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompError::UnknownMnemonic { mnemonic } => {
+                write!(f, "unknown comp mnemonic {:?}", mnemonic)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CompError {}
+
+/// comp の記法と、a ビット、ALUの関数の対応（Hack 仕様の表の順）
+///
+/// ALUの x には D、y には a=0 なら A、a=1 なら M がつながる
+const COMP_TABLE: [(&str, bool, AluOp); 28] = [
+    ("0", false, AluOp::Zero),
+    ("1", false, AluOp::One),
+    ("-1", false, AluOp::NegOne),
+    ("D", false, AluOp::X),
+    ("A", false, AluOp::Y),
+    ("!D", false, AluOp::NotX),
+    ("!A", false, AluOp::NotY),
+    ("-D", false, AluOp::NegX),
+    ("-A", false, AluOp::NegY),
+    ("D+1", false, AluOp::XPlusOne),
+    ("A+1", false, AluOp::YPlusOne),
+    ("D-1", false, AluOp::XMinusOne),
+    ("A-1", false, AluOp::YMinusOne),
+    ("D+A", false, AluOp::XPlusY),
+    ("D-A", false, AluOp::XMinusY),
+    ("A-D", false, AluOp::YMinusX),
+    ("D&A", false, AluOp::XAndY),
+    ("D|A", false, AluOp::XOrY),
+    ("M", true, AluOp::Y),
+    ("!M", true, AluOp::NotY),
+    ("-M", true, AluOp::NegY),
+    ("M+1", true, AluOp::YPlusOne),
+    ("M-1", true, AluOp::YMinusOne),
+    ("D+M", true, AluOp::XPlusY),
+    ("D-M", true, AluOp::XMinusY),
+    ("M-D", true, AluOp::YMinusX),
+    ("D&M", true, AluOp::XAndY),
+    ("D|M", true, AluOp::XOrY),
+];
+
+/// comp フィールドの記法から a ビットとALUの制御ビットを得る
+///
+/// * `mnemonic` - Hack の comp の記法（"D+A", "M-1", "!D" など。空白は含めない）
+///
+/// 戻り値は (a, ctl) のタプル。a は y の入力に A（false）と M（true）のどちらをつなぐか
+pub fn comp_to_control(mnemonic: &str) -> Result<(bool, AluControl), CompError> {
+    COMP_TABLE
+        .iter()
+        .find(|(m, _, _)| *m == mnemonic)
+        .map(|&(_, a, op)| (a, op.control_bits()))
+        .ok_or_else(|| CompError::UnknownMnemonic { mnemonic: mnemonic.to_string() })
+}
+
+/// a ビットとALUの制御ビットから comp フィールドの記法を得る（逆アセンブラ用）
+///
+/// 28個の記法のどれにも当たらない組み合わせでは None を返す
+/// （a=1 で y を使わない関数、例えば "0" や "D" は a=0 の記法だけを持つ）
+pub fn control_to_comp(a: bool, ctl: AluControl) -> Option<&'static str> {
+    COMP_TABLE
+        .iter()
+        .find(|&&(_, row_a, op)| row_a == a && op.control_bits() == ctl)
+        .map(|&(m, _, _)| m)
+}
+
+/// Aレジスタの入力の選択
+///
+/// A命令なら命令語そのもの（定数）、C命令ならALUの出力を mux16 で選ぶ
+///
+/// * `is_a_instruction` - A命令かどうか（命令語のMSBが0）
+/// * `instruction` - 命令語（LSB→MSB順）
+/// * `alu_out` - ALUの出力（LSB→MSB順）
+pub fn a_input_select(is_a_instruction: bool, instruction: &[bool; 16], alu_out: &[bool; 16]) -> [bool; 16] {
+    mux16(is_a_instruction, alu_out, instruction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Jump; 8] = [
+        Jump::Null,
+        Jump::Jgt,
+        Jump::Jeq,
+        Jump::Jge,
+        Jump::Jlt,
+        Jump::Jne,
+        Jump::Jle,
+        Jump::Jmp,
+    ];
+
+    #[test]
+    fn test_dest_routing() {
+        for code in 0..8usize {
+            let bits = [code & 4 != 0, code & 2 != 0, code & 1 != 0];
+            let enables = dest_routing(&bits);
+            assert_eq!(
+                (enables.load_a, enables.load_d, enables.write_m),
+                (code & 4 != 0, code & 2 != 0, code & 1 != 0),
+                "dest = {:03b}",
+                code
+            );
+        }
+
+        // AMD=... はすべてに書き込む
+        assert_eq!(
+            dest_routing(&[true, true, true]),
+            DestEnables { load_a: true, load_d: true, write_m: true }
+        );
+    }
+
+    #[test]
+    fn test_a_input_select() {
+        // @5 の命令語と、適当なALU出力
+        let mut instruction = [false; 16];
+        instruction[0] = true;
+        instruction[2] = true;
+        let alu_out = [true; 16];
+
+        assert_eq!(a_input_select(true, &instruction, &alu_out), instruction);
+        assert_eq!(a_input_select(false, &instruction, &alu_out), alu_out);
+    }
+
+    #[test]
+    fn test_jump_bits_round_trip() {
+        for (code, jump) in ALL.iter().enumerate() {
+            let bits = [code & 4 != 0, code & 2 != 0, code & 1 != 0];
+            assert_eq!(Jump::from_bits(&bits), *jump);
+            assert_eq!(jump.to_bits(), bits);
+        }
+    }
+
+    // Hack 仕様のジャンプ表: (zr, ng) = 正, 0, 負 のそれぞれで、ジャンプするかどうか
+    const JUMP_SPEC: [(Jump, [bool; 3]); 8] = [
+        (Jump::Null, [false, false, false]),
+        (Jump::Jgt, [true, false, false]),
+        (Jump::Jeq, [false, true, false]),
+        (Jump::Jge, [true, true, false]),
+        (Jump::Jlt, [false, false, true]),
+        (Jump::Jne, [true, false, true]),
+        (Jump::Jle, [false, true, true]),
+        (Jump::Jmp, [true, true, true]),
+    ];
+
+    // 正、0、負 の (zr, ng)
+    const FLAG_STATES: [(bool, bool); 3] = [(false, false), (true, false), (false, true)];
+
+    #[test]
+    fn test_jump_decision_spec_table() {
+        for (jump, expected) in JUMP_SPEC {
+            for (i, &(zr, ng)) in FLAG_STATES.iter().enumerate() {
+                assert_eq!(
+                    jump_decision(&jump.to_bits(), zr, ng),
+                    expected[i],
+                    "{:?} with zr = {}, ng = {}",
+                    jump,
+                    zr,
+                    ng
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_comp_round_trip() {
+        for (mnemonic, a, _) in COMP_TABLE {
+            let (decoded_a, ctl) = comp_to_control(mnemonic).unwrap();
+            assert_eq!(decoded_a, a, "{}", mnemonic);
+            assert_eq!(control_to_comp(a, ctl), Some(mnemonic));
+        }
+        assert_eq!(comp_to_control("D+A"), Ok((false, AluControl::X_PLUS_Y)));
+        assert_eq!(comp_to_control("M-D"), Ok((true, AluControl::Y_MINUS_X)));
+        assert_eq!(control_to_comp(true, AluControl::ZERO), None);
+        assert_eq!(control_to_comp(false, AluControl::new(true, true, true, true, false, false)), None);
+    }
+
+    #[test]
+    fn test_comp_evaluates_through_alu() {
+        use crate::alu::alu16;
+        use crate::convert::{bits_from_i16, i16_from_bits};
+
+        for (d, a_reg, m) in [(7i16, 5i16, -3i16), (-100, 42, 1000), (0, -1, i16::MIN)] {
+            for (mnemonic, a, _) in COMP_TABLE {
+                let y = if a { m } else { a_reg };
+                let expected = match mnemonic.replace(['A', 'M'], "Y").as_str() {
+                    "0" => 0,
+                    "1" => 1,
+                    "-1" => -1,
+                    "D" => d,
+                    "Y" => y,
+                    "!D" => !d,
+                    "!Y" => !y,
+                    "-D" => d.wrapping_neg(),
+                    "-Y" => y.wrapping_neg(),
+                    "D+1" => d.wrapping_add(1),
+                    "Y+1" => y.wrapping_add(1),
+                    "D-1" => d.wrapping_sub(1),
+                    "Y-1" => y.wrapping_sub(1),
+                    "D+Y" => d.wrapping_add(y),
+                    "D-Y" => d.wrapping_sub(y),
+                    "Y-D" => y.wrapping_sub(d),
+                    "D&Y" => d & y,
+                    "D|Y" => d | y,
+                    other => panic!("unexpected mnemonic {}", other),
+                };
+                let (_, c) = comp_to_control(mnemonic).unwrap();
+                let (out, _, _) = alu16(&bits_from_i16(d), &bits_from_i16(y), c.zx, c.nx, c.zy, c.ny, c.f, c.no);
+                assert_eq!(i16_from_bits(&out), expected, "{}, D = {}, A = {}, M = {}", mnemonic, d, a_reg, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_comp_unknown_mnemonic() {
+        for s in ["", "A+D", "D + A", "X", "M+M"] {
+            assert_eq!(comp_to_control(s), Err(CompError::UnknownMnemonic { mnemonic: s.to_string() }));
+        }
+        assert_eq!(
+            CompError::UnknownMnemonic { mnemonic: "A+D".to_string() }.to_string(),
+            "unknown comp mnemonic \"A+D\""
+        );
+    }
+
+    #[test]
+    fn test_jump_unit_exhaustive() {
+        // 8つの条件すべてを、(zr, ng) の意味のある3状態（正、0、負）で仕様表と比べる
+        // 仕様表は jjj = 000〜111 の順に並んでいる（j[0] が j1）
+        for (code, (condition, expected)) in JUMP_SPEC.into_iter().enumerate() {
+            let j = [code & 4 != 0, code & 2 != 0, code & 1 != 0];
+            assert_eq!(condition.to_bits(), j, "{:?}", condition);
+            for (&(zr, ng), &taken) in FLAG_STATES.iter().zip(&expected) {
+                assert_eq!(jump_unit(&j, zr, ng), taken, "{:?} with zr = {}, ng = {}", condition, zr, ng);
+            }
+            assert_eq!(JumpCondition::from_bits(&j), condition);
+        }
+        assert!(!jump_unit(&JumpCondition::Null.to_bits(), true, false));
+        assert!(jump_unit(&JumpCondition::Jmp.to_bits(), false, false));
+    }
+}
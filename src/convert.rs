@@ -0,0 +1,608 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt;
+
+use crate::wiring::bit_reverse;
+
+/// u16 を16ビットのビット列（LSB→MSB順）に変換する
+///
+/// 戻り値の [0] が最下位ビット、[15] が最上位ビット
+pub fn bits_from_u16(v: u16) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (i, bit) in out.iter_mut().enumerate() {
+        *bit = (v >> i) & 1 == 1;
+    }
+    out
+}
+
+/// 16ビットのビット列（LSB→MSB順）を u16 に変換する
+pub fn u16_from_bits(bits: &[bool; 16]) -> u16 {
+    u16_from_bits_slice(bits)
+}
+
+/// u16 を nビットのビット列（LSB→MSB順）に変換する
+///
+/// n > 16 なら上位を0で埋め、n < 16 なら下位nビットだけを残す
+pub fn bits_from_u16_n(v: u16, n: usize) -> Vec<bool> {
+    (0..n).map(|i| i < 16 && (v >> i) & 1 == 1).collect()
+}
+
+/// u64 を nビットのビット列（LSB→MSB順）に変換する
+///
+/// bits_from_u16_n と同じく、n > 64 なら上位を0で埋め、n < 64 なら下位nビットだけを残す
+pub fn bits_from_u64_n(v: u64, n: usize) -> Vec<bool> {
+    (0..n).map(|i| i < 64 && (v >> i) & 1 == 1).collect()
+}
+
+/// 任意の長さのビット列（LSB→MSB順）を u16 に変換する
+///
+/// 17ビット目以降は切り捨て、16ビットに満たない分は0として扱う
+pub fn u16_from_bits_slice(bits: &[bool]) -> u16 {
+    bits.iter()
+        .take(16)
+        .enumerate()
+        .fold(0, |acc, (i, &bit)| acc | ((bit as u16) << i))
+}
+
+/// 任意の長さのビット列（LSB→MSB順）を u64 に変換する
+///
+/// u16_from_bits_slice と同じく、65ビット目以降は切り捨てる
+pub fn u64_from_bits_slice(bits: &[bool]) -> u64 {
+    bits.iter()
+        .take(64)
+        .enumerate()
+        .fold(0, |acc, (i, &bit)| acc | ((bit as u64) << i))
+}
+
+/// i16 を16ビットの2の補数のビット列（LSB→MSB順）に変換する
+pub fn bits_from_i16(v: i16) -> [bool; 16] {
+    bits_from_u16(v as u16)
+}
+
+/// 16ビットの2の補数のビット列（LSB→MSB順）を i16 に変換する
+///
+/// 最上位ビット（[15]）が1なら負数
+pub fn i16_from_bits(bits: &[bool; 16]) -> i16 {
+    u16_from_bits(bits) as i16
+}
+
+/// u64 を16ビットずつの4つのリム（下位ワードが先）に分ける
+///
+/// 戻り値の [0] がビット0〜15、[3] がビット48〜63。各リムはLSB→MSB順
+pub fn limbs_from_u64(v: u64) -> [[bool; 16]; 4] {
+    core::array::from_fn(|i| bits_from_u16((v >> (16 * i)) as u16))
+}
+
+/// 4つの16ビットのリム（下位ワードが先）を u64 にまとめる（limbs_from_u64 の逆）
+pub fn u64_from_limbs(limbs: &[[bool; 16]; 4]) -> u64 {
+    limbs
+        .iter()
+        .rev()
+        .fold(0, |acc, limb| (acc << 16) | u16_from_bits(limb) as u64)
+}
+
+/// 任意の幅の2の補数のビット列（LSB→MSB順）を i64 に変換する
+///
+/// 最後の要素を符号ビットとして扱う。空のスライスは0
+/// 64ビットを超える分は無視し、下位64ビットを i64 として解釈する
+pub fn to_signed(bits: &[bool]) -> i64 {
+    let n = bits.len().min(64);
+    let raw = bits[..n]
+        .iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, &bit)| acc | ((bit as u64) << i));
+    if n > 0 && n < 64 && bits[n - 1] {
+        (raw | (u64::MAX << n)) as i64
+    } else {
+        raw as i64
+    }
+}
+
+/// i64 を width ビットの2の補数のビット列（LSB→MSB順）に変換する
+///
+/// width が64未満なら下位 width ビットだけを残す（表せない値は2の補数として回り込む）
+/// width が64を超える場合は符号ビットで上位を埋める
+pub fn from_signed(v: i64, width: usize) -> Vec<bool> {
+    (0..width).map(|i| (v >> i.min(63)) & 1 == 1).collect()
+}
+
+/// ビット列の文字列表現の解析エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBitsError {
+    /// 接頭辞と区切りの `_` を除くと、数字が1つもない
+    Empty,
+    /// position文字目（0始まり、接頭辞を含む）に使えない文字がある
+    InvalidChar { position: usize, ch: char },
+}
+
+impl fmt::Display for ParseBitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBitsError::Empty => write!(f, "no digits to parse"),
+            ParseBitsError::InvalidChar { position, ch } => {
+                write!(f, "invalid character {:?} at position {}", ch, position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseBitsError {}
+
+/// 文字列を1桁ずつ解析する
+///
+/// 接頭辞 prefix（大文字・小文字を問わない）と区切りの `_` を読み飛ばし、
+/// 各桁を bits_per_digit ビットに展開する。文字列の左端が最上位の桁
+fn parse_digits(s: &str, prefix: &str, radix: u32, bits_per_digit: usize) -> Result<Vec<bool>, ParseBitsError> {
+    let skip = match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => prefix.len(),
+        _ => 0,
+    };
+
+    let mut digits = Vec::new();
+    for (position, ch) in s.chars().enumerate().skip(skip) {
+        if ch == '_' {
+            continue;
+        }
+        match ch.to_digit(radix) {
+            Some(d) => digits.push(d),
+            None => return Err(ParseBitsError::InvalidChar { position, ch }),
+        }
+    }
+    if digits.is_empty() {
+        return Err(ParseBitsError::Empty);
+    }
+
+    // 最下位の桁（文字列の右端）から順にLSB→MSB順のビット列にする
+    let mut out = Vec::with_capacity(digits.len() * bits_per_digit);
+    for &d in digits.iter().rev() {
+        for i in 0..bits_per_digit {
+            out.push((d >> i) & 1 == 1);
+        }
+    }
+    Ok(out)
+}
+
+/// 2進数の文字列をビット列（LSB→MSB順）に変換する
+///
+/// 人が数を書くときと同じく、文字列の左端が最上位ビット（"0110" は [false, true, true, false]）
+/// 接頭辞 `0b` と、桁の区切りの `_` を使える。出力の幅は数字の個数と同じ
+pub fn parse_bits(s: &str) -> Result<Vec<bool>, ParseBitsError> {
+    parse_digits(s, "0b", 2, 1)
+}
+
+/// 16進数の文字列をビット列（LSB→MSB順）に変換する
+///
+/// 文字列の左端が最上位の桁。接頭辞 `0x` と区切りの `_` を使え、大文字・小文字を問わない
+/// 出力の幅は桁数 × 4（"0x1F" は8ビット）
+pub fn parse_bits_hex(s: &str) -> Result<Vec<bool>, ParseBitsError> {
+    parse_digits(s, "0x", 16, 4)
+}
+
+/// ビット列（LSB→MSB順）を、最上位ビットを左にした2進数の文字列にする
+///
+/// 入力の幅と同じ桁数になるよう0を残す（[true, false, true] → "101"、空のスライス → ""）
+pub fn format_bits(bits: &[bool]) -> String {
+    bits.iter().rev().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+/// ビット列（LSB→MSB順）を、最上位の桁を左にした16進数の文字列（小文字、接頭辞なし）にする
+///
+/// 桁数は ceil(幅 / 4)。幅が4の倍数でなければ、最上位の桁の足りないビットを0とみなす
+pub fn format_bits_hex(bits: &[bool]) -> String {
+    bits.chunks(4)
+        .rev()
+        .map(|nibble| {
+            let d = nibble.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << i));
+            char::from_digit(d, 16).unwrap()
+        })
+        .collect()
+}
+
+/// ビット列（LSB→MSB順）を符号なし整数とみなして10進数の文字列にする
+///
+/// 64ビットを超える分は無視する
+pub fn format_bits_unsigned(bits: &[bool]) -> String {
+    let n = bits.len().min(64);
+    let v = bits[..n].iter().enumerate().fold(0u64, |acc, (i, &b)| acc | ((b as u64) << i));
+    v.to_string()
+}
+
+/// ビット列（LSB→MSB順）を2の補数の符号付き整数とみなして10進数の文字列にする（to_signed を参照）
+pub fn format_bits_signed(bits: &[bool]) -> String {
+    to_signed(bits).to_string()
+}
+
+/// ビット列を format_bits の形式で表示するラッパー
+///
+/// `println!("{}", DisplayBits(&out))` のように、そのまま書式指定に使える
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayBits<'a>(pub &'a [bool]);
+
+impl fmt::Display for DisplayBits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_bits(self.0))
+    }
+}
+
+/// 外部とやり取りするビット列の並び順
+///
+/// クレート内部のゲート・加算器・ALUは常に LsbFirst。MsbFirst は Nand2Tetris の .cmp ファイルや
+/// 教科書の表のように、最上位ビットを先頭に書く外部形式との変換にだけ使う
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// [0] が最下位ビット（クレート内部の並び）
+    LsbFirst,
+    /// [0] が最上位ビット
+    MsbFirst,
+}
+
+/// ビット列の並び順を from から to へ変える（同じ順なら何もしない）
+pub fn reorder(bits: &[bool], from: BitOrder, to: BitOrder) -> Vec<bool> {
+    if from == to { bits.to_vec() } else { bit_reverse(bits) }
+}
+
+fn to_array16(bits: Vec<bool>) -> [bool; 16] {
+    let mut out = [false; 16];
+    out.copy_from_slice(&bits);
+    out
+}
+
+/// bits_from_u16 の並び順指定版
+pub fn bits_from_u16_with_order(v: u16, order: BitOrder) -> [bool; 16] {
+    to_array16(reorder(&bits_from_u16(v), BitOrder::LsbFirst, order))
+}
+
+/// u16_from_bits の並び順指定版（bits は order の並び）
+pub fn u16_from_bits_with_order(bits: &[bool; 16], order: BitOrder) -> u16 {
+    u16_from_bits(&to_array16(reorder(bits, order, BitOrder::LsbFirst)))
+}
+
+/// bits_from_i16 の並び順指定版
+pub fn bits_from_i16_with_order(v: i16, order: BitOrder) -> [bool; 16] {
+    to_array16(reorder(&bits_from_i16(v), BitOrder::LsbFirst, order))
+}
+
+/// i16_from_bits の並び順指定版（bits は order の並び）
+pub fn i16_from_bits_with_order(bits: &[bool; 16], order: BitOrder) -> i16 {
+    i16_from_bits(&to_array16(reorder(bits, order, BitOrder::LsbFirst)))
+}
+
+/// parse_bits の並び順指定版（文字列の左端は常に最上位ビットで、出力を order の並びにする）
+pub fn parse_bits_with_order(s: &str, order: BitOrder) -> Result<Vec<bool>, ParseBitsError> {
+    parse_bits(s).map(|bits| reorder(&bits, BitOrder::LsbFirst, order))
+}
+
+/// format_bits の並び順指定版（bits は order の並びで、文字列の左端は常に最上位ビット）
+pub fn format_bits_with_order(bits: &[bool], order: BitOrder) -> String {
+    format_bits(&reorder(bits, order, BitOrder::LsbFirst))
+}
+
+/// ビット列から整数型への変換エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryFromBitsError {
+    /// width ビットのビット列の値が、変換先の型で表せない
+    Overflow { width: usize },
+}
+
+impl fmt::Display for TryFromBitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryFromBitsError::Overflow { width } => {
+                write!(f, "{}-bit value does not fit in the target integer type", width)
+            }
+        }
+    }
+}
+
+impl core::error::Error for TryFromBitsError {}
+
+/// 整数型へ TryFrom で変換するためのビット列（LSB→MSB順）の参照
+///
+/// 符号なしの型へは、変換先の幅を超える上位ビットがすべて0なら変換できる
+/// 符号付きの型へは、スライスの最後の要素を符号ビットとする2の補数として解釈し
+/// （4ビットの [false, true, true, true] は -2）、値が変換先の範囲に収まれば変換できる
+///
+/// `u8::try_from(Bits(&bits))` のように使う。空のスライスは0
+#[derive(Debug, Clone, Copy)]
+pub struct Bits<'a>(pub &'a [bool]);
+
+/// 整数から From で作ったビット列（LSB→MSB順）
+///
+/// 幅は元の型のビット数と同じ（u8 なら8ビット）。符号付きの型は2の補数で表す
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitVec(pub Vec<bool>);
+
+impl From<BitVec> for Vec<bool> {
+    fn from(bits: BitVec) -> Vec<bool> {
+        bits.0
+    }
+}
+
+macro_rules! impl_bits_conversions {
+    ($($t:ty => $signed:expr),* $(,)?) => {$(
+        impl TryFrom<Bits<'_>> for $t {
+            type Error = TryFromBitsError;
+
+            fn try_from(bits: Bits<'_>) -> Result<$t, TryFromBitsError> {
+                let bits = bits.0;
+                let target = <$t>::BITS as usize;
+                if bits.len() > target {
+                    // 符号なしなら上位ビットがすべて0、符号付きなら符号拡張になっていれば収まる
+                    let fill = $signed && bits[bits.len() - 1];
+                    let first = if $signed { target - 1 } else { target };
+                    if bits[first..].iter().any(|&b| b != fill) {
+                        return Err(TryFromBitsError::Overflow { width: bits.len() });
+                    }
+                }
+                let value = if $signed { to_signed(bits) as $t } else { value_u64(bits) as $t };
+                Ok(value)
+            }
+        }
+
+        impl From<$t> for BitVec {
+            fn from(v: $t) -> BitVec {
+                BitVec((0..<$t>::BITS).map(|i| (v >> i) & 1 == 1).collect())
+            }
+        }
+    )*};
+}
+
+/// ビット列の下位64ビットを符号なしの値にする
+fn value_u64(bits: &[bool]) -> u64 {
+    bits.iter().take(64).enumerate().fold(0, |acc, (i, &bit)| acc | ((bit as u64) << i))
+}
+
+impl_bits_conversions!(u8 => false, u16 => false, u32 => false, u64 => false, i16 => true, i32 => true);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWEEP: [u16; 8] = [0x0000, 0x0001, 0x0002, 0x00FF, 0x1234, 0x7FFF, 0x8000, 0xFFFF];
+
+    #[test]
+    fn test_u16_round_trip() {
+        for v in SWEEP {
+            assert_eq!(u16_from_bits(&bits_from_u16(v)), v);
+            assert_eq!(u16_from_bits_slice(&bits_from_u16_n(v, 16)), v);
+        }
+        for v in 0..=u16::MAX {
+            assert_eq!(u16_from_bits(&bits_from_u16(v)), v);
+        }
+    }
+
+    #[test]
+    fn test_lsb_first_order() {
+        let one = bits_from_u16(1);
+        assert_eq!(one[0], true);
+        assert_eq!(one[1..], [false; 15]);
+
+        let msb = bits_from_u16(0x8000);
+        assert_eq!(msb[15], true);
+        assert_eq!(msb[..15], [false; 15]);
+    }
+
+    #[test]
+    fn test_slice_extend_and_truncate() {
+        // 幅を広げると上位は0、狭めると下位だけが残る
+        assert_eq!(bits_from_u16_n(0xFFFF, 18)[16..], [false, false]);
+        assert_eq!(bits_from_u16_n(0x1234, 8), bits_from_u16(0x0034)[..8].to_vec());
+        assert_eq!(bits_from_u16_n(0x1234, 0), Vec::<bool>::new());
+
+        assert_eq!(bits_from_u64_n(u64::MAX, 66)[63..], [true, false, false]);
+        assert_eq!(bits_from_u64_n(0x1_0000_1234, 16), bits_from_u16(0x1234).to_vec());
+        for v in [0u64, 1, 0x8000_0000_0000_0000, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            assert_eq!(to_signed(&bits_from_u64_n(v, 64)) as u64, v);
+        }
+
+        assert_eq!(u16_from_bits_slice(&[true, false, true]), 5);
+        assert_eq!(u16_from_bits_slice(&[true; 20]), 0xFFFF);
+        assert_eq!(u16_from_bits_slice(&[]), 0);
+
+        assert_eq!(u64_from_bits_slice(&[true; 70]), u64::MAX);
+        for v in [0u64, 5, 0x8000_0000_0000_0000, 0x0123_4567_89AB_CDEF] {
+            assert_eq!(u64_from_bits_slice(&bits_from_u64_n(v, 64)), v);
+        }
+    }
+
+    #[test]
+    fn test_u64_limbs_round_trip() {
+        for v in [0u64, 1, 0xFFFF, 0x1_0000, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            assert_eq!(u64_from_limbs(&limbs_from_u64(v)), v);
+        }
+        let limbs = limbs_from_u64(0x0004_0003_0002_0001);
+        for (i, limb) in limbs.iter().enumerate() {
+            assert_eq!(u16_from_bits(limb), i as u16 + 1);
+        }
+    }
+
+    #[test]
+    fn test_i16_round_trip() {
+        for v in [i16::MIN, -1, 0, 1, i16::MAX] {
+            assert_eq!(i16_from_bits(&bits_from_i16(v)), v);
+            assert_eq!(to_signed(&bits_from_i16(v)), v as i64);
+            assert_eq!(from_signed(v as i64, 16), bits_from_i16(v).to_vec());
+        }
+        assert_eq!(bits_from_i16(-1), [true; 16]);
+        assert_eq!(bits_from_i16(i16::MIN), bits_from_u16(0x8000));
+    }
+
+    #[test]
+    fn test_signed_generic_width() {
+        // 5ビットの -7 は 0b11001
+        assert_eq!(from_signed(-7, 5), bits_from_u16_n(0b11001, 5));
+        assert_eq!(to_signed(&from_signed(-7, 5)), -7);
+        assert_eq!(to_signed(&bits_from_u16_n(0b01111, 5)), 15);
+
+        // 表せない値は下位ビットだけが残る（5ビットでは 20 → -12）
+        assert_eq!(to_signed(&from_signed(20, 5)), -12);
+
+        // 64ビットを超える幅は符号で埋める
+        assert_eq!(from_signed(-1, 70), vec![true; 70]);
+        assert_eq!(to_signed(&[]), 0);
+        assert_eq!(from_signed(5, 0), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_parse_bits() {
+        assert_eq!(parse_bits("0110"), Ok(vec![false, true, true, false]));
+        assert_eq!(parse_bits("1010101010101010"), Ok(bits_from_u16(0xAAAA).to_vec()));
+        assert_eq!(parse_bits("0b1111_0000"), Ok(bits_from_u16_n(0xF0, 8)));
+        assert_eq!(parse_bits("0B1"), Ok(vec![true]));
+    }
+
+    #[test]
+    fn test_parse_bits_hex() {
+        assert_eq!(parse_bits_hex("0x1F"), Ok(bits_from_u16_n(0x1F, 8)));
+        assert_eq!(parse_bits_hex("dead_BEEF").map(|b| b.len()), Ok(32));
+        assert_eq!(parse_bits_hex("BeEf"), Ok(bits_from_u16(0xBEEF).to_vec()));
+        assert_eq!(parse_bits_hex("0X0"), Ok(vec![false; 4]));
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for v in [0x0000u16, 0x0001, 0x8000, 0xFFFF, 0x1234] {
+            assert_eq!(parse_bits(&format!("{:016b}", v)), Ok(bits_from_u16(v).to_vec()));
+            assert_eq!(parse_bits_hex(&format!("{:#06x}", v)), Ok(bits_from_u16(v).to_vec()));
+        }
+    }
+
+    #[test]
+    fn test_parse_bits_errors() {
+        assert_eq!(parse_bits(""), Err(ParseBitsError::Empty));
+        assert_eq!(parse_bits("0b"), Err(ParseBitsError::Empty));
+        assert_eq!(parse_bits("__"), Err(ParseBitsError::Empty));
+        assert_eq!(parse_bits("10201"), Err(ParseBitsError::InvalidChar { position: 2, ch: '2' }));
+        assert_eq!(parse_bits("0b1x"), Err(ParseBitsError::InvalidChar { position: 3, ch: 'x' }));
+        assert_eq!(parse_bits_hex("0x"), Err(ParseBitsError::Empty));
+        assert_eq!(parse_bits_hex("0x1G"), Err(ParseBitsError::InvalidChar { position: 3, ch: 'G' }));
+        assert_eq!(parse_bits("é1"), Err(ParseBitsError::InvalidChar { position: 0, ch: 'é' }));
+    }
+
+    #[test]
+    fn test_format_bits() {
+        assert_eq!(format_bits(&bits_from_u16(5)), "0000000000000101");
+        assert_eq!(format_bits(&[true, false, false]), "001");
+        assert_eq!(format_bits(&[]), "");
+
+        assert_eq!(format_bits_hex(&bits_from_u16(0xBEEF)), "beef");
+        assert_eq!(format_bits_hex(&bits_from_u16_n(0x1F, 5)), "1f");
+        assert_eq!(format_bits_hex(&[]), "");
+
+        assert_eq!(format_bits_unsigned(&bits_from_i16(-2)), "65534");
+        assert_eq!(format_bits_signed(&bits_from_i16(-2)), "-2");
+        assert_eq!(format_bits_signed(&[]), "0");
+    }
+
+    #[test]
+    fn test_display_bits() {
+        let out = bits_from_u16_n(0b0110, 4);
+        assert_eq!(format!("{}", DisplayBits(&out)), "0110");
+        assert_eq!(DisplayBits(&out).to_string(), format_bits(&out));
+    }
+
+    #[test]
+    fn test_format_parse_round_trip() {
+        for n in [1usize, 4, 7, 16] {
+            for v in [0u16, 1, 0x55, 0xFFFF] {
+                let b = bits_from_u16_n(v, n);
+                assert_eq!(parse_bits(&format_bits(&b)), Ok(b.clone()));
+                if n % 4 == 0 {
+                    assert_eq!(parse_bits_hex(&format_bits_hex(&b)), Ok(b.clone()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_order_consistency() {
+        for v in [0x0000u16, 0x0001, 0x8000, 0x1234, 0xFFFF] {
+            let msb_first = bits_from_u16_with_order(v, BitOrder::MsbFirst);
+            let lsb_first = bits_from_u16_with_order(v, BitOrder::LsbFirst);
+            assert_eq!(lsb_first, bits_from_u16(v));
+
+            // MSB先頭の並びを反転すると、LSB先頭の並びと一致する
+            assert_eq!(reorder(&msb_first, BitOrder::MsbFirst, BitOrder::LsbFirst), lsb_first.to_vec());
+            assert_eq!(u16_from_bits_with_order(&msb_first, BitOrder::MsbFirst), v);
+
+            let s = format!("{:016b}", v);
+            assert_eq!(parse_bits_with_order(&s, BitOrder::MsbFirst), Ok(msb_first.to_vec()));
+            assert_eq!(format_bits_with_order(&msb_first, BitOrder::MsbFirst), s);
+        }
+
+        assert_eq!(bits_from_u16_with_order(1, BitOrder::MsbFirst)[15], true);
+        let minus_two = bits_from_i16_with_order(-2, BitOrder::MsbFirst);
+        assert_eq!(i16_from_bits_with_order(&minus_two, BitOrder::MsbFirst), -2);
+    }
+
+    #[test]
+    fn test_reorder_twice_is_identity() {
+        let bits = bits_from_u16_n(0b1_0110, 5);
+        for (from, to) in [(BitOrder::LsbFirst, BitOrder::MsbFirst), (BitOrder::MsbFirst, BitOrder::LsbFirst)] {
+            assert_eq!(reorder(&reorder(&bits, from, to), to, from), bits);
+        }
+        assert_eq!(reorder(&bits, BitOrder::MsbFirst, BitOrder::MsbFirst), bits);
+    }
+
+    #[test]
+    fn test_try_from_bits_width4_into_u8_and_i16() {
+        for v in 0..16u64 {
+            let b = bits_from_u64_n(v, 4);
+            assert_eq!(u8::try_from(Bits(&b)), Ok(v as u8));
+            let expected = if v >= 8 { v as i16 - 16 } else { v as i16 };
+            assert_eq!(i16::try_from(Bits(&b)), Ok(expected), "bits = {:04b}", v);
+        }
+        assert_eq!(i16::try_from(Bits(&[false, true, true, true])), Ok(-2));
+        assert_eq!(u8::try_from(Bits(&[])), Ok(0));
+    }
+
+    #[test]
+    fn test_try_from_bits_overflow() {
+        // 上位ビットが0なら、変換先より広いスライスでも変換できる
+        assert_eq!(u8::try_from(Bits(&bits_from_u64_n(0xFF, 12))), Ok(0xFF));
+        assert_eq!(u8::try_from(Bits(&bits_from_u64_n(0x100, 12))), Err(TryFromBitsError::Overflow { width: 12 }));
+
+        // 符号付きは符号拡張になっていれば収まる
+        assert_eq!(i16::try_from(Bits(&from_signed(-32768, 20))), Ok(i16::MIN));
+        assert_eq!(i16::try_from(Bits(&from_signed(32767, 20))), Ok(i16::MAX));
+        assert_eq!(i16::try_from(Bits(&from_signed(32768, 20))), Err(TryFromBitsError::Overflow { width: 20 }));
+        assert_eq!(i16::try_from(Bits(&from_signed(-32769, 17))), Err(TryFromBitsError::Overflow { width: 17 }));
+        assert_eq!(i32::try_from(Bits(&from_signed(i32::MIN as i64, 40))), Ok(i32::MIN));
+        assert_eq!(i32::try_from(Bits(&from_signed(i32::MAX as i64 + 1, 40))), Err(TryFromBitsError::Overflow { width: 40 }));
+
+        assert_eq!(
+            TryFromBitsError::Overflow { width: 12 }.to_string(),
+            "12-bit value does not fit in the target integer type"
+        );
+    }
+
+    #[test]
+    fn test_bits_conversions_64bit_boundaries() {
+        for v in [0u64, 1, u64::MAX >> 1, 1 << 63, u64::MAX] {
+            let b: Vec<bool> = BitVec::from(v).into();
+            assert_eq!(b.len(), 64);
+            assert_eq!(b, bits_from_u64_n(v, 64));
+            assert_eq!(u64::try_from(Bits(&b)), Ok(v));
+        }
+
+        let mut wide = bits_from_u64_n(u64::MAX, 64);
+        wide.push(false);
+        assert_eq!(u64::try_from(Bits(&wide)), Ok(u64::MAX));
+        wide[64] = true;
+        assert_eq!(u64::try_from(Bits(&wide)), Err(TryFromBitsError::Overflow { width: 65 }));
+    }
+
+    #[test]
+    fn test_bit_vec_from_integers() {
+        assert_eq!(BitVec::from(0xA5u8).0, bits_from_u64_n(0xA5, 8));
+        assert_eq!(BitVec::from(0x1234u16).0, bits_from_u16(0x1234).to_vec());
+        assert_eq!(BitVec::from(0xDEAD_BEEFu32).0, bits_from_u64_n(0xDEAD_BEEF, 32));
+        assert_eq!(BitVec::from(-2i16).0, bits_from_i16(-2).to_vec());
+        assert_eq!(BitVec::from(-1i32).0, vec![true; 32]);
+
+        // From で作ったビット列は、同じ型へ TryFrom で戻せる
+        for v in [i32::MIN, -1, 0, 1, i32::MAX] {
+            let b = BitVec::from(v);
+            assert_eq!(i32::try_from(Bits(&b.0)), Ok(v));
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::bus::{mux_bus, mux_nway, mux_nway_bus};
+
+/// クロスバスイッチの入力エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrossbarError {
+    /// 入力バスの幅がそろっていない（index番目の幅がinputs[0]と異なる）
+    WidthMismatch { index: usize, expected: usize, actual: usize },
+    /// output番目の出力が、存在しない入力番号を指定している
+    RoutingOutOfRange { output: usize, input: usize },
+    /// 排他モードで、2つの出力が同じ入力を選択している
+    Conflict { input: usize, outputs: (usize, usize) },
+}
+
+impl fmt::Display for CrossbarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrossbarError::WidthMismatch { index, expected, actual } => {
+                write!(f, "input {} has width {}, expected {}", index, actual, expected)
+            }
+            CrossbarError::RoutingOutOfRange { output, input } => {
+                write!(f, "output {} routes from input {}, which does not exist", output, input)
+            }
+            CrossbarError::Conflict { input, outputs } => {
+                write!(f, "outputs {} and {} both select input {}", outputs.0, outputs.1, input)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CrossbarError {}
+
+/// N×M クロスバスイッチ
+///
+/// 出力jには routing[j] が示す番号の入力バスを接続する（Noneならすべて0のバス）
+///
+/// 各出力ごとに mux_nway_bus を1つ置いた構造で、同じ入力を複数の出力へ
+/// 同時に流すこと（ブロードキャスト）もできる
+///
+/// * `inputs` - N本のWビット入力（すべて同じ幅であること）
+/// * `routing` - M個の出力それぞれが選択する入力番号
+///
+/// 戻り値は M本のWビット出力
+pub fn crossbar(inputs: &[&[bool]], routing: &[Option<usize>]) -> Result<Vec<Vec<bool>>, CrossbarError> {
+    let width = inputs.first().map_or(0, |input| input.len());
+    for (index, input) in inputs.iter().enumerate() {
+        if input.len() != width {
+            return Err(CrossbarError::WidthMismatch { index, expected: width, actual: input.len() });
+        }
+    }
+    for (output, route) in routing.iter().enumerate() {
+        if let Some(input) = *route
+            && input >= inputs.len()
+        {
+            return Err(CrossbarError::RoutingOutOfRange { output, input });
+        }
+    }
+
+    // 入力の本数を2のべき乗まで0のバスで埋め、選択信号の幅を決める
+    let mut sel_width = 0;
+    while (1usize << sel_width) < inputs.len() {
+        sel_width += 1;
+    }
+    let zero = vec![false; width];
+    let mut padded: Vec<&[bool]> = inputs.to_vec();
+    padded.resize(1 << sel_width, &zero);
+
+    let mut outputs = Vec::with_capacity(routing.len());
+    for route in routing {
+        let index = route.unwrap_or(0);
+        let sel: Vec<bool> = (0..sel_width).map(|i| (index >> i) & 1 == 1).collect();
+        let selected = mux_nway_bus(&sel, &padded).expect("padded inputs always match the select width");
+
+        // 未接続の出力は、選択結果の代わりに0のバスを出す
+        outputs.push(mux_bus(route.is_some(), &zero, &selected));
+    }
+
+    Ok(outputs)
+}
+
+/// 排他モードのクロスバスイッチ
+///
+/// crossbar と同じだが、2つ以上の出力が同じ入力を選択するとエラーにする
+/// （置換のみを許すスイッチのモデル）
+pub fn crossbar_exclusive(inputs: &[&[bool]], routing: &[Option<usize>]) -> Result<Vec<Vec<bool>>, CrossbarError> {
+    for (j, route) in routing.iter().enumerate() {
+        if let Some(input) = *route
+            && let Some(first) = routing[..j].iter().position(|r| *r == Some(input))
+        {
+            return Err(CrossbarError::Conflict { input, outputs: (first, j) });
+        }
+    }
+
+    crossbar(inputs, routing)
+}
+
+/// 選択バス駆動のクロスバスイッチ
+///
+/// 出力jには、select[j]（LSB→MSB順の2進数）が示す番号の入力ワードを接続する
+/// 出力ごとに mux_nway を1つ置いた構造で、幅や本数の扱いも mux_nway と同じ
+///
+/// - 入力の幅が異なる場合は、最も広い入力の幅に0で埋めてそろえる
+/// - 選択値が入力の本数以上の場合は、すべて0のワードになる
+///
+/// routing を Option で与える crossbar と違い、エラーは返さない
+pub fn crossbar_select(inputs: &[Vec<bool>], select: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    select.iter().map(|sel| mux_nway(sel, inputs)).collect()
+}
+
+/// 4×4・16ビットのクロスバスイッチ
+///
+/// crossbar_select の固定幅版。出力jは inputs[select[j]] になる
+pub fn crossbar4x16(inputs: &[[bool; 16]; 4], select: &[[bool; 2]; 4]) -> [[bool; 16]; 4] {
+    let inputs: Vec<Vec<bool>> = inputs.iter().map(|word| word.to_vec()).collect();
+    let select: Vec<Vec<bool>> = select.iter().map(|sel| sel.to_vec()).collect();
+
+    let mut out = [[false; 16]; 4];
+    for (o, word) in out.iter_mut().zip(crossbar_select(&inputs, &select)) {
+        o.copy_from_slice(&word);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u16, bits_from_u64_n};
+
+    fn words() -> Vec<Vec<bool>> {
+        (0..4).map(|i| bits_from_u64_n(0x1001 * (i + 1), 16)).collect()
+    }
+
+    #[test]
+    fn test_crossbar_permutation() {
+        let words = words();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+        let routing = [Some(2), Some(0), Some(3), Some(1)];
+
+        let outputs = crossbar_exclusive(&inputs, &routing).unwrap();
+        for (j, route) in routing.iter().enumerate() {
+            assert_eq!(outputs[j], words[route.unwrap()]);
+        }
+    }
+
+    #[test]
+    fn test_crossbar_broadcast() {
+        let words = words();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+
+        let outputs = crossbar(&inputs, &[Some(1), Some(1), None, Some(1)]).unwrap();
+        assert_eq!(outputs[0], words[1]);
+        assert_eq!(outputs[1], words[1]);
+        assert_eq!(outputs[2], vec![false; 16]);
+        assert_eq!(outputs[3], words[1]);
+    }
+
+    #[test]
+    fn test_crossbar_exclusive_conflict() {
+        let words = words();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+
+        assert_eq!(
+            crossbar_exclusive(&inputs, &[Some(0), Some(2), Some(2), None]),
+            Err(CrossbarError::Conflict { input: 2, outputs: (1, 2) })
+        );
+    }
+
+    #[test]
+    fn test_crossbar_out_of_range() {
+        let words = words();
+        let inputs: Vec<&[bool]> = words.iter().map(|w| w.as_slice()).collect();
+
+        assert_eq!(
+            crossbar(&inputs, &[Some(0), Some(4)]),
+            Err(CrossbarError::RoutingOutOfRange { output: 1, input: 4 })
+        );
+    }
+
+    #[test]
+    fn test_crossbar_width_mismatch() {
+        let a = [true, false];
+        let b = [true];
+        assert_eq!(
+            crossbar(&[&a, &b], &[Some(0)]),
+            Err(CrossbarError::WidthMismatch { index: 1, expected: 2, actual: 1 })
+        );
+    }
+
+    #[test]
+    fn test_crossbar4x16_all_permutations() {
+        let inputs = [bits_from_u16(0x1111), bits_from_u16(0x2222), bits_from_u16(0x4444), bits_from_u16(0x8888)];
+        let sel = |i: usize| [i & 1 != 0, i & 2 != 0];
+
+        // 4! = 24 通りの置換をすべて試す
+        let mut count = 0;
+        for p in 0..256usize {
+            let route = [p & 3, (p >> 2) & 3, (p >> 4) & 3, (p >> 6) & 3];
+            let mut used = [false; 4];
+            route.iter().for_each(|&r| used[r] = true);
+            if used.contains(&false) {
+                continue;
+            }
+            count += 1;
+
+            let select = [sel(route[0]), sel(route[1]), sel(route[2]), sel(route[3])];
+            let expected = [inputs[route[0]], inputs[route[1]], inputs[route[2]], inputs[route[3]]];
+            assert_eq!(crossbar4x16(&inputs, &select), expected, "route = {:?}", route);
+        }
+        assert_eq!(count, 24);
+    }
+
+    #[test]
+    fn test_crossbar4x16_broadcast() {
+        let inputs = [bits_from_u16(0xBEEF), bits_from_u16(0x0001), bits_from_u16(0x0002), bits_from_u16(0x0003)];
+        assert_eq!(crossbar4x16(&inputs, &[[false, false]; 4]), [bits_from_u16(0xBEEF); 4]);
+    }
+
+    #[test]
+    fn test_crossbar_select_out_of_range() {
+        // 3本の入力に対して選択値3は、すべて0のワード
+        let words: Vec<Vec<bool>> = words().into_iter().take(3).collect();
+        let outputs = crossbar_select(&words, &[bits_from_u64_n(3, 2), bits_from_u64_n(2, 2)]);
+        assert_eq!(outputs, vec![vec![false; 16], words[2].clone()]);
+    }
+}
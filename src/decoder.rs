@@ -0,0 +1,308 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{and, not, or};
+use crate::adder::popcount;
+use crate::bus::{and_reduce, mux_bus, or_reduce, xnor_n};
+
+/// 2-4 デコーダ（イネーブル付き）
+///
+/// enable が1のとき、sel（LSB→MSB順の2ビット）が示す番号の出力だけを1にする
+/// enable が0のときは、すべての出力が0
+///
+/// 各出力は選択ビットのリテラル（そのまま、または反転）と enable の AND
+///
+/// demux4way と回路は似ているが、こちらは「どの出力を有効にするか」を選ぶ回路で、
+/// 入力データを運ぶのではなく enable で全体を有効・無効にする
+///
+/// ```
+/// use logic_gates::bus::demux4way;
+/// use logic_gates::decoder::decoder2to4;
+///
+/// // sel = 2 の出力だけが1になる
+/// assert_eq!(decoder2to4(&[false, true], true), [false, false, true, false]);
+/// // enable=0 ならすべて0
+/// assert_eq!(decoder2to4(&[false, true], false), [false; 4]);
+/// // demux4way はデータ d を選ばれた出力へ運ぶ（d=1 なら同じ結果）
+/// assert_eq!(demux4way(&[false, true], true), (false, false, true, false));
+/// ```
+pub fn decoder2to4(sel: &[bool; 2], enable: bool) -> [bool; 4] {
+    let n0 = not(sel[0]);
+    let n1 = not(sel[1]);
+    [
+        and(enable, and(n0, n1)),
+        and(enable, and(sel[0], n1)),
+        and(enable, and(n0, sel[1])),
+        and(enable, and(sel[0], sel[1])),
+    ]
+}
+
+/// 3-8 デコーダ（イネーブル付き）
+///
+/// enable が1のとき、sel（LSB→MSB順の3ビット）が示す番号の出力だけを1にする
+/// enable が0のときは、すべての出力が0
+///
+/// 2つの decoder2to4 を並べ、最上位ビット sel[2] でどちらのイネーブルを立てるかを決める
+/// （下位側は enable·￢sel[2]、上位側は enable·sel[2]）
+pub fn decoder3to8(sel: &[bool; 3], enable: bool) -> [bool; 8] {
+    let low = [sel[0], sel[1]];
+    let lower = decoder2to4(&low, and(enable, not(sel[2])));
+    let upper = decoder2to4(&low, and(enable, sel[2]));
+
+    let mut out = [false; 8];
+    out[..4].copy_from_slice(&lower);
+    out[4..].copy_from_slice(&upper);
+    out
+}
+
+/// 8-3 エンコーダ
+///
+/// one-hot の8本の入力を、1になっている入力の番号（LSB→MSB順の3ビット）に変換する
+///
+/// 出力ビットkは、番号のkビット目が1である入力線の OR
+/// （out[0] = i1+i3+i5+i7, out[1] = i2+i3+i6+i7, out[2] = i4+i5+i6+i7）
+///
+/// - 複数の入力が1の場合は、それらの番号のビットごとの OR になる
+/// - すべて0の入力は [false, false, false]（番号0の入力と区別できない）
+pub fn encoder8to3(inputs: &[bool; 8]) -> [bool; 3] {
+    let mut out = [false; 3];
+    for (k, bit) in out.iter_mut().enumerate() {
+        for (i, &line) in inputs.iter().enumerate() {
+            if (i >> k) & 1 == 1 {
+                *bit = or(*bit, line);
+            }
+        }
+    }
+    out
+}
+
+/// 優先度付きエンコーダ
+///
+/// 1になっている入力のうち、番号が最も大きいものの番号（LSB→MSB順）を出力する
+///
+/// * `inputs` - n本の入力
+///
+/// 戻り値は (index, valid) のタプル
+/// - index: ceil(log2(n)) ビットの番号（validがfalseのときはすべて0）
+/// - valid: いずれかの入力が1ならtrue
+///
+/// 番号の小さい入力から順に mux_bus を連ね、入力iが1なら定数 i に置き換える
+/// （後段ほど優先度が高い）
+pub fn priority_encoder(inputs: &[bool]) -> (Vec<bool>, bool) {
+    let width = index_width(inputs.len());
+
+    let mut index = vec![false; width];
+    for (i, &line) in inputs.iter().enumerate() {
+        let constant: Vec<bool> = (0..width).map(|k| (i >> k) & 1 == 1).collect();
+        index = mux_bus(line, &index, &constant);
+    }
+
+    (index, or_reduce(inputs))
+}
+
+/// 8入力の優先度付きエンコーダ
+///
+/// priority_encoder の8入力固定幅版
+pub fn priority_encoder8(inputs: &[bool; 8]) -> ([bool; 3], bool) {
+    let (index, valid) = priority_encoder(inputs);
+    ([index[0], index[1], index[2]], valid)
+}
+
+/// 番号の幅: n本の線を区別するのに必要なビット数 ceil(log2(n))
+fn index_width(n: usize) -> usize {
+    match n {
+        0 | 1 => 0,
+        n => (usize::BITS - (n - 1).leading_zeros()) as usize,
+    }
+}
+
+/// one-hot の判定
+///
+/// ちょうど1ビットだけが1のときにtrue（空のスライスやすべて0はfalse）
+///
+/// 1の個数を popcount（全加算器の連鎖）で数え、定数1と xnor_n で一致を調べる
+pub fn is_one_hot(a: &[bool]) -> bool {
+    let count = popcount(a);
+    let one: Vec<bool> = (0..count.len()).map(|k| k == 0).collect();
+    and_reduce(&xnor_n(&count, &one))
+}
+
+/// one-hot から2進数への変換
+///
+/// 1になっている線の番号（LSB→MSB順、ceil(log2(n)) ビット）を返す
+/// encoder8to3 と同じく、番号のkビット目が1である線の OR で組み立てる
+///
+/// 複数の線が1の場合は番号のビットごとの OR、すべて0なら0になる
+pub fn one_hot_to_binary(a: &[bool]) -> Vec<bool> {
+    let mut out = vec![false; index_width(a.len())];
+    for (k, bit) in out.iter_mut().enumerate() {
+        for (i, &line) in a.iter().enumerate() {
+            if (i >> k) & 1 == 1 {
+                *bit = or(*bit, line);
+            }
+        }
+    }
+    out
+}
+
+/// 2進数から one-hot への変換（汎用デコーダ）
+///
+/// a（LSB→MSB順）の値が示す番号の線だけを1にした、width 本の one-hot を返す
+///
+/// 各出力 i は、a のビット j を「i のビット j が1ならそのまま、0なら反転」したリテラルの AND
+/// （decoder2to4 と同じ構成を width 本ぶん直接作る。2^a.len() 本をすべて作ってから切り詰めない）
+/// a の値が width 以上の場合は、すべて0になる。a で表せない番号（i >= 2^a.len()）の線は常に0
+pub fn binary_to_one_hot(a: &[bool], width: usize) -> Vec<bool> {
+    (0..width)
+        .map(|i| {
+            let reachable = a.len() >= usize::BITS as usize || i >> a.len() == 0;
+            let literals: Vec<bool> = a
+                .iter()
+                .enumerate()
+                .map(|(j, &bit)| {
+                    let one = j < usize::BITS as usize && (i >> j) & 1 == 1;
+                    if one { bit } else { not(bit) }
+                })
+                .collect();
+            reachable && and_reduce(&literals)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder2to4_exhaustive() {
+        for index in 0..4 {
+            let sel = [index & 1 != 0, index & 2 != 0];
+            for enable in [false, true] {
+                let mut expected = [false; 4];
+                expected[index] = enable;
+                assert_eq!(decoder2to4(&sel, enable), expected, "sel = {}, enable = {}", index, enable);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoder3to8_exhaustive() {
+        for index in 0..8 {
+            let sel = [index & 1 != 0, index & 2 != 0, index & 4 != 0];
+            for enable in [false, true] {
+                let out = decoder3to8(&sel, enable);
+                let mut expected = [false; 8];
+                expected[index] = enable;
+                assert_eq!(out, expected, "sel = {}, enable = {}", index, enable);
+
+                // イネーブル時はちょうど1本だけが1
+                let set = out.iter().filter(|&&o| o).count();
+                assert_eq!(set, if enable { 1 } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoder8to3() {
+        for index in 0..8 {
+            let mut inputs = [false; 8];
+            inputs[index] = true;
+            let expected = [index & 1 != 0, index & 2 != 0, index & 4 != 0];
+            assert_eq!(encoder8to3(&inputs), expected, "input {}", index);
+        }
+
+        assert_eq!(encoder8to3(&[false; 8]), [false; 3]);
+
+        // 入力1と入力4が同時に1なら、1 | 4 = 5
+        let mut multi = [false; 8];
+        multi[1] = true;
+        multi[4] = true;
+        assert_eq!(encoder8to3(&multi), [true, false, true]);
+    }
+
+    #[test]
+    fn test_priority_encoder8() {
+        for index in 0..8 {
+            let mut inputs = [false; 8];
+            inputs[index] = true;
+            let expected = [index & 1 != 0, index & 2 != 0, index & 4 != 0];
+            assert_eq!(priority_encoder8(&inputs), (expected, true), "input {}", index);
+        }
+
+        // 複数の入力が1なら、番号の大きい方が優先される
+        let mut multi = [false; 8];
+        multi[1] = true;
+        multi[4] = true;
+        assert_eq!(priority_encoder8(&multi), ([false, false, true], true));
+
+        assert_eq!(priority_encoder8(&[false; 8]), ([false; 3], false));
+        assert_eq!(priority_encoder8(&[true; 8]), ([true; 3], true));
+    }
+
+    #[test]
+    fn test_priority_encoder_widths() {
+        // 5入力は3ビット、2入力は1ビット、1入力は0ビットの番号
+        assert_eq!(priority_encoder(&[true, false, true, true, false]), (vec![true, true, false], true));
+        assert_eq!(priority_encoder(&[false, false, false, false, true]), (vec![false, false, true], true));
+        assert_eq!(priority_encoder(&[true, false]), (vec![false], true));
+        assert_eq!(priority_encoder(&[true]), (vec![], true));
+        assert_eq!(priority_encoder(&[]), (vec![], false));
+        assert_eq!(priority_encoder(&[false; 5]), (vec![false; 3], false));
+    }
+
+    #[test]
+    fn test_is_one_hot() {
+        assert_eq!(is_one_hot(&[]), false);
+        assert_eq!(is_one_hot(&[false; 5]), false);
+        assert_eq!(is_one_hot(&[true; 5]), false);
+        assert_eq!(is_one_hot(&[true]), true);
+        assert_eq!(is_one_hot(&[true, true]), false);
+        for i in 0..6 {
+            let mut a = vec![false; 6];
+            a[i] = true;
+            assert_eq!(is_one_hot(&a), true, "bit {}", i);
+            a[(i + 3) % 6] = true;
+            assert_eq!(is_one_hot(&a), false, "bits {} and {}", i, (i + 3) % 6);
+        }
+    }
+
+    #[test]
+    fn test_one_hot_round_trip() {
+        // 2のべき乗でない幅（5, 6）も含める
+        for width in [1usize, 2, 5, 6, 8] {
+            for i in 0..width {
+                let index: Vec<bool> = (0..index_width(width)).map(|k| (i >> k) & 1 == 1).collect();
+                let one_hot = binary_to_one_hot(&index, width);
+                assert_eq!(one_hot.len(), width);
+                assert_eq!(is_one_hot(&one_hot), true, "width = {}, index = {}", width, i);
+                assert_eq!(one_hot[i], true);
+                assert_eq!(one_hot_to_binary(&one_hot), index, "width = {}, index = {}", width, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_hot_edge_cases() {
+        assert_eq!(one_hot_to_binary(&[]), Vec::<bool>::new());
+        assert_eq!(one_hot_to_binary(&[false; 5]), vec![false; 3]);
+        assert_eq!(one_hot_to_binary(&[true; 5]), vec![true; 3]);
+
+        assert_eq!(binary_to_one_hot(&[], 1), vec![true]);
+        assert_eq!(binary_to_one_hot(&[], 0), Vec::<bool>::new());
+        // 範囲外の値（6 >= 5）はすべて0
+        assert_eq!(binary_to_one_hot(&[false, true, true], 5), vec![false; 5]);
+        // 1ビットでは表せない線（2, 3）は常に0
+        assert_eq!(binary_to_one_hot(&[true], 4), vec![false, true, false, false]);
+        assert_eq!(binary_to_one_hot(&[false], 4), vec![true, false, false, false]);
+    }
+
+    #[test]
+    fn test_binary_to_one_hot_matches_demux() {
+        // 幅が 2^k のときは d=1 の demux_nway と同じ出力になる
+        for k in 0..5usize {
+            for v in 0..(1usize << k) {
+                let a: Vec<bool> = (0..k).map(|j| (v >> j) & 1 == 1).collect();
+                assert_eq!(binary_to_one_hot(&a, 1 << k), crate::bus::demux_nway(&a, true), "k = {}, v = {}", k, v);
+            }
+        }
+    }
+}
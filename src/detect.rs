@@ -0,0 +1,60 @@
+use crate::{and, not};
+use crate::adder::decrementer;
+use crate::bus::{and_n, or_reduce};
+
+/// 1ビットだけ、または1ビットも立っていないかの検出
+///
+/// a AND (a - 1) が0になるかどうかで判定する（最下位の1を消す定番の手法）
+///
+/// * `a` - nビット入力（LSB→MSB順）
+///
+/// 立っているビットが高々1つならtrue（0もtrue）
+pub fn has_single_bit_or_zero(a: &[bool]) -> bool {
+    let (a_minus_one, _underflow) = decrementer(a);
+    let masked = and_n(a, &a_minus_one);
+    not(or_reduce(&masked))
+}
+
+/// 2のべき乗の検出
+///
+/// ちょうど1ビットだけ立っている場合にtrueを返す
+///
+/// * `a` - nビット入力（LSB→MSB順）
+///
+/// 0はfalseになる
+pub fn is_power_of_two(a: &[bool]) -> bool {
+    and(or_reduce(a), has_single_bit_or_zero(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::bits_from_u64_n;
+
+    #[test]
+    fn test_is_power_of_two_exhaustive() {
+        // 10ビットの全パターンをu16::is_power_of_twoと比較
+        for v in 0..(1u16 << 10) {
+            let a = bits_from_u64_n(v as u64, 10);
+            assert_eq!(is_power_of_two(&a), v.is_power_of_two(), "v = {}", v);
+            assert_eq!(has_single_bit_or_zero(&a), v.count_ones() <= 1, "v = {}", v);
+        }
+    }
+
+    #[test]
+    fn test_zero() {
+        // 0は2のべき乗ではないが、「高々1ビット」には該当する
+        assert_eq!(is_power_of_two(&[false; 16]), false);
+        assert_eq!(has_single_bit_or_zero(&[false; 16]), true);
+        assert_eq!(is_power_of_two(&[]), false);
+    }
+
+    #[test]
+    fn test_all_ones() {
+        assert_eq!(is_power_of_two(&[true; 16]), false);
+        assert_eq!(has_single_bit_or_zero(&[true; 16]), false);
+
+        // 1ビット幅の1は2のべき乗（= 1）
+        assert_eq!(is_power_of_two(&[true]), true);
+    }
+}
@@ -0,0 +1,269 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{and, mux, not, or};
+use crate::adder::n_bit_adder;
+use crate::bus::or_reduce;
+use crate::sequential::Dff;
+
+/// 4ビットの値が lit の各値のいずれかに一致するかを、最小項の積和で求める
+///
+/// 最小項はそれぞれ4つのリテラルの AND、それらを OR でまとめる
+fn sum_of_minterms(digit: &[bool; 4], lit: &[u8]) -> bool {
+    let mut out = false;
+    for &m in lit {
+        let mut term = true;
+        for (i, &bit) in digit.iter().enumerate() {
+            let literal = if (m >> i) & 1 == 1 { bit } else { not(bit) };
+            term = and(term, literal);
+        }
+        out = or(out, term);
+    }
+    out
+}
+
+/// 7セグメントデコーダ
+///
+/// 4ビットの2進数（LSB→MSB順）0〜9を、セグメント a〜g の点灯パターンに変換する
+///
+/// ```text
+///  aaa
+/// f   b
+///  ggg
+/// e   c
+///  ddd
+/// ```
+///
+/// - 出力は [a, b, c, d, e, f, g] の順で、trueが点灯（アクティブハイ）
+/// - 10〜15 は16進の A〜F ではなく、表示を消す（すべてのセグメントが消灯）
+/// - 各セグメントは、そのセグメントが点灯する数字の最小項の積和で組み立てている
+pub fn seven_segment(digit: &[bool; 4]) -> [bool; 7] {
+    [
+        sum_of_minterms(digit, &[0, 2, 3, 5, 6, 7, 8, 9]),    // a
+        sum_of_minterms(digit, &[0, 1, 2, 3, 4, 7, 8, 9]),    // b
+        sum_of_minterms(digit, &[0, 1, 3, 4, 5, 6, 7, 8, 9]), // c
+        sum_of_minterms(digit, &[0, 2, 3, 5, 6, 8, 9]),       // d
+        sum_of_minterms(digit, &[0, 2, 6, 8]),                // e
+        sum_of_minterms(digit, &[0, 4, 5, 6, 8, 9]),          // f
+        sum_of_minterms(digit, &[2, 3, 4, 5, 6, 8, 9]),       // g
+    ]
+}
+
+/// 2進数からBCDへの変換（ダブルダブル法）
+///
+/// MSBから1ビットずつBCDの桁列へ左シフトで取り込み、シフトの前に
+/// 5以上の桁へ3を加える（加算は n_bit_adder、条件分岐は mux）
+///
+/// * `bin` - 2進数の入力（LSB→MSB順）
+/// * `digits` - 出力するBCDの桁数
+///
+/// 戻り値は下位の桁から順に並べた各桁（各桁はLSB→MSB順の4ビット）
+/// 入力の値が digits 桁に収まらない場合は、下位 digits 桁だけが残る
+pub fn binary_to_bcd(bin: &[bool], digits: usize) -> Vec<[bool; 4]> {
+    let mut bcd = vec![[false; 4]; digits];
+
+    for &incoming in bin.iter().rev() {
+        // 5以上の桁に3を加える: d >= 5 ⇔ d3 + d2·(d1 + d0)
+        for d in bcd.iter_mut() {
+            let ge5 = or(d[3], and(d[2], or(d[1], d[0])));
+            let (plus3, _) = n_bit_adder(d, &[true, true, false, false]);
+            for i in 0..4 {
+                d[i] = mux(ge5, d[i], plus3[i]);
+            }
+        }
+
+        // 全桁をまとめて1ビット左シフトし、空いたLSBに入力ビットを入れる
+        let mut carry = incoming;
+        for d in bcd.iter_mut() {
+            let out = d[3];
+            d[3] = d[2];
+            d[2] = d[1];
+            d[1] = d[0];
+            d[0] = carry;
+            carry = out;
+        }
+    }
+
+    bcd
+}
+
+/// 多桁の7セグメント表示ドライバ（ダイナミック点灯）
+///
+/// ラッチした2進数をBCDに変換し、リングカウンタで1クロックに1桁ずつ選択して
+/// その桁のセグメントパターンを出力する（実際のダイナミック点灯表示と同じ方式）
+///
+/// 桁の番号は0が最下位の桁。リングカウンタは桁0から始まり、tickごとに1つ上の桁へ進む
+pub struct SevenSegDriver {
+    value: Vec<bool>,
+    ring: Vec<Dff>,
+    blank_leading_zeros: bool,
+}
+
+impl SevenSegDriver {
+    /// num_digits 桁のドライバを作る（値は0、桁0を選択した状態）
+    pub fn new(num_digits: usize) -> Self {
+        let mut ring = vec![Dff::new(); num_digits];
+        if let Some(first) = ring.first_mut() {
+            first.tick(true);
+        }
+        SevenSegDriver { value: Vec::new(), ring, blank_leading_zeros: false }
+    }
+
+    /// 上位の桁の0を消灯するかどうかを設定する（最下位の桁は常に表示する）
+    pub fn set_leading_zero_blanking(&mut self, enable: bool) {
+        self.blank_leading_zeros = enable;
+    }
+
+    /// 表示する値（2進数、LSB→MSB順）をラッチする
+    pub fn set_value(&mut self, value: &[bool]) {
+        self.value = value.to_vec();
+    }
+
+    /// 現在選択されている桁（リングカウンタの出力、one-hot）
+    pub fn digit_select(&self) -> Vec<bool> {
+        self.ring.iter().map(|dff| dff.out()).collect()
+    }
+
+    /// 1クロック分の出力を返し、リングカウンタを次の桁へ進める
+    ///
+    /// 戻り値は (segment_lines, digit_select) のタプル
+    /// - segment_lines: 選択中の桁のセグメントパターン（seven_segment を参照）
+    /// - digit_select: 選択中の桁だけがtrueの one-hot 信号
+    pub fn tick(&mut self) -> ([bool; 7], Vec<bool>) {
+        let select = self.digit_select();
+        let bcd = binary_to_bcd(&self.value, self.ring.len());
+
+        // 選択中の桁を AND-OR で取り出す
+        let mut digit = [false; 4];
+        for (i, bit) in digit.iter_mut().enumerate() {
+            let lanes: Vec<bool> = bcd.iter().zip(&select).map(|(d, &s)| and(s, d[i])).collect();
+            *bit = or_reduce(&lanes);
+        }
+
+        // 上位の0の消灯: 選択中の桁とそれより上の桁がすべて0で、最下位の桁でなければ消す
+        let mut blank = false;
+        for (k, &s) in select.iter().enumerate().skip(1) {
+            let upper: Vec<bool> = bcd[k..].iter().flatten().copied().collect();
+            blank = or(blank, and(s, not(or_reduce(&upper))));
+        }
+        let blank = and(self.blank_leading_zeros, blank);
+
+        let mut segments = seven_segment(&digit);
+        for segment in segments.iter_mut() {
+            *segment = and(*segment, not(blank));
+        }
+
+        // リングカウンタを1つ回す
+        let n = self.ring.len();
+        if n > 0 {
+            let mut carry = select[n - 1];
+            for dff in self.ring.iter_mut() {
+                carry = dff.tick(carry);
+            }
+        }
+
+        (segments, select)
+    }
+
+    /// 値をラッチしてから1クロック分の出力を返す（set_value の後に tick するのと同じ）
+    ///
+    /// クロックごとに入力が変わる回路から駆動するときに使う
+    pub fn tick_with(&mut self, value: &[bool]) -> ([bool; 7], Vec<bool>) {
+        self.set_value(value);
+        self.tick()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::bits_from_u64_n;
+
+    // [a, b, c, d, e, f, g]
+    const DIGITS: [[bool; 7]; 10] = [
+        [true, true, true, true, true, true, false],     // 0
+        [false, true, true, false, false, false, false], // 1
+        [true, true, false, true, true, false, true],    // 2
+        [true, true, true, true, false, false, true],    // 3
+        [false, true, true, false, false, true, true],   // 4
+        [true, false, true, true, false, true, true],    // 5
+        [true, false, true, true, true, true, true],     // 6
+        [true, true, true, false, false, false, false],  // 7
+        [true, true, true, true, true, true, true],      // 8
+        [true, true, true, true, false, true, true],     // 9
+    ];
+
+    fn nibble(v: u8) -> [bool; 4] {
+        [v & 1 != 0, v & 2 != 0, v & 4 != 0, v & 8 != 0]
+    }
+
+    #[test]
+    fn test_seven_segment_all_inputs() {
+        // 16通りすべての入力を固定の表と比較する（10〜15は消灯）
+        const BLANK: [bool; 7] = [false; 7];
+        let table: [[bool; 7]; 16] = [
+            DIGITS[0], DIGITS[1], DIGITS[2], DIGITS[3], DIGITS[4], DIGITS[5], DIGITS[6], DIGITS[7],
+            DIGITS[8], DIGITS[9], BLANK, BLANK, BLANK, BLANK, BLANK, BLANK,
+        ];
+        for (v, expected) in table.iter().enumerate() {
+            assert_eq!(seven_segment(&nibble(v as u8)), *expected, "value {}", v);
+        }
+    }
+
+    #[test]
+    fn test_binary_to_bcd() {
+        for v in [0u64, 7, 10, 99, 407, 9999] {
+            let bcd = binary_to_bcd(&bits_from_u64_n(v, 14), 4);
+            let mut expected = v;
+            for digit in bcd {
+                assert_eq!(digit, nibble((expected % 10) as u8), "v = {}", v);
+                expected /= 10;
+            }
+        }
+    }
+
+    #[test]
+    fn test_driver_multiplexes_407() {
+        let mut driver = SevenSegDriver::new(4);
+        driver.set_value(&bits_from_u64_n(407, 10));
+        driver.set_leading_zero_blanking(true);
+
+        let expected = [DIGITS[7], DIGITS[0], DIGITS[4], [false; 7]];
+        for (k, segments) in expected.iter().enumerate() {
+            let (lines, select) = driver.tick();
+            let mut one_hot = vec![false; 4];
+            one_hot[k] = true;
+            assert_eq!(select, one_hot);
+            assert_eq!(lines, *segments, "digit {}", k);
+        }
+
+        // 1周すると桁0に戻る。消灯しない設定では上位の0も表示する
+        driver.set_leading_zero_blanking(false);
+        let (lines, select) = driver.tick();
+        assert_eq!(select, vec![true, false, false, false]);
+        assert_eq!(lines, DIGITS[7]);
+        driver.tick();
+        driver.tick();
+        assert_eq!(driver.tick().0, DIGITS[0]);
+    }
+
+    #[test]
+    fn test_driver_zero_keeps_last_digit() {
+        // 値0でも最下位の桁は消さない
+        let mut driver = SevenSegDriver::new(2);
+        driver.set_leading_zero_blanking(true);
+        assert_eq!(driver.tick().0, DIGITS[0]);
+        assert_eq!(driver.tick().0, [false; 7]);
+    }
+
+    #[test]
+    fn test_driver_tick_with_latches_value() {
+        let mut driver = SevenSegDriver::new(3);
+        // 桁0は 5 を、桁1は 42 を、桁2は 900 をラッチしてから表示する
+        assert_eq!(driver.tick_with(&bits_from_u64_n(5, 10)), (DIGITS[5], vec![true, false, false]));
+        assert_eq!(driver.tick_with(&bits_from_u64_n(42, 10)), (DIGITS[4], vec![false, true, false]));
+        assert_eq!(driver.tick_with(&bits_from_u64_n(900, 10)), (DIGITS[9], vec![false, false, true]));
+
+        // ラッチした値は次の tick にもそのまま残る
+        assert_eq!(driver.tick().0, DIGITS[0]);
+    }
+}
@@ -0,0 +1,272 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{and, not, xor};
+use crate::adder::{n_bit_adder_with_carry, n_bit_subtractor};
+use crate::bus::{mux_bus, or_reduce};
+use crate::shifter::shift_left;
+use crate::wiring::zero_extend;
+
+/// 除算のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivisionError {
+    /// 除数が0（すべてのビットが0、または幅0）
+    DivideByZero,
+}
+
+impl fmt::Display for DivisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DivisionError::DivideByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl core::error::Error for DivisionError {}
+
+/// 除数が0かどうかを OR リダクションで調べる
+fn check_divisor(divisor: &[bool]) -> Result<(), DivisionError> {
+    if or_reduce(divisor) { Ok(()) } else { Err(DivisionError::DivideByZero) }
+}
+
+/// 符号なし除算器（引き戻し法、restoring division）
+///
+/// 被除数の上位ビットから1ビットずつ部分剰余に取り込み、除数を引く
+/// 引けた（借りが出ない）なら商のビットを1にして差を新しい部分剰余にし、
+/// 引けなければ商のビットを0にして元の部分剰余に戻す（mux で選ぶ）
+///
+/// * `dividend` - 被除数（LSB→MSB順）
+/// * `divisor` - 除数（LSB→MSB順）
+///
+/// 戻り値は (quotient, remainder) のタプル
+/// - quotient: 被除数と同じ幅の商
+/// - remainder: 除数と同じ幅の剰余
+///
+/// 除数が0なら DivisionError::DivideByZero を返す
+pub fn divide_unsigned(dividend: &[bool], divisor: &[bool]) -> Result<(Vec<bool>, Vec<bool>), DivisionError> {
+    check_divisor(divisor)?;
+
+    // 部分剰余は除数より1ビット広くとる（左にずらしたときにあふれないように）
+    let width = divisor.len() + 1;
+    let mut remainder = vec![false; width];
+    let mut quotient = vec![false; dividend.len()];
+    for i in (0..dividend.len()).rev() {
+        // 部分剰余を1ビット左にずらし、空いた最下位ビットに被除数のビットを入れる
+        remainder = shift_left(&remainder, 1).0;
+        remainder[0] = dividend[i];
+        let (difference, borrow) = n_bit_subtractor(&remainder, divisor);
+        quotient[i] = not(borrow);
+        remainder = mux_bus(quotient[i], &remainder, &difference);
+    }
+
+    remainder.truncate(divisor.len());
+    Ok((quotient, remainder))
+}
+
+/// 剰余（a mod m）
+///
+/// divide_unsigned の剰余の出力だけを使う回路
+///
+/// * `a` - 被除数（LSB→MSB順）
+/// * `m` - 法（LSB→MSB順）
+///
+/// 戻り値は m と同じ幅の剰余で、値は常に m 未満（m = 1 なら常に0）
+/// m が0なら DivisionError::DivideByZero を返す
+pub fn modulo(a: &[bool], m: &[bool]) -> Result<Vec<bool>, DivisionError> {
+    let (_quotient, remainder) = divide_unsigned(a, m)?;
+    Ok(remainder)
+}
+
+/// 16ビットの剰余
+///
+/// modulo の16ビット固定幅版
+pub fn mod16(a: &[bool; 16], m: &[bool; 16]) -> Result<[bool; 16], DivisionError> {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(modulo(a, m)?) {
+        *o = bit;
+    }
+    Ok(out)
+}
+
+/// 引き放し法の除算の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonRestoringResult {
+    /// 被除数と同じ幅の商
+    pub quotient: Vec<bool>,
+    /// 除数と同じ幅の剰余
+    pub remainder: Vec<bool>,
+    /// 各ステップの後の部分剰余（除数 + 2 ビットの2の補数、steps[k] が k 番目のステップ）
+    pub steps: Vec<Vec<bool>>,
+    /// 最後の部分剰余が負で、除数を加えて補正したか
+    pub corrected: bool,
+}
+
+/// 符号なし除算器（引き放し法、non-restoring division）
+///
+/// divide_unsigned と同じ (quotient, remainder) を返す。除数が0なら DivisionError::DivideByZero
+pub fn divide_nonrestoring(dividend: &[bool], divisor: &[bool]) -> Result<(Vec<bool>, Vec<bool>), DivisionError> {
+    let r = divide_nonrestoring_detailed(dividend, divisor)?;
+    Ok((r.quotient, r.remainder))
+}
+
+/// 符号なし除算器（引き放し法、各ステップの部分剰余を記録する版）
+///
+/// 引き戻し法と違い、引きすぎても元に戻さない。部分剰余を1ビット左にずらしたあと、
+/// 部分剰余が正なら除数を引き、負なら除数を加える（1つの加減算器で、XOR と桁上げ入力で切り替える）
+/// 商のビットは、その結果の部分剰余が負でなければ1
+///
+/// 最後の部分剰余が負なら、除数を1回加えて剰余を補正する
+pub fn divide_nonrestoring_detailed(dividend: &[bool], divisor: &[bool]) -> Result<NonRestoringResult, DivisionError> {
+    check_divisor(divisor)?;
+
+    // 部分剰余は -divisor 以上 divisor 未満なので、左にずらしても divisor + 2 ビットの2の補数に収まる
+    let width = divisor.len() + 2;
+    let extended = zero_extend(divisor, width);
+
+    let mut remainder = vec![false; width];
+    let mut quotient = vec![false; dividend.len()];
+    let mut steps = Vec::with_capacity(dividend.len());
+    for i in (0..dividend.len()).rev() {
+        let subtract = not(remainder[width - 1]);
+        // 部分剰余を1ビット左にずらし、空いた最下位ビットに被除数のビットを入れる
+        remainder = shift_left(&remainder, 1).0;
+        remainder[0] = dividend[i];
+        let operand: Vec<bool> = extended.iter().map(|&bit| xor(bit, subtract)).collect();
+        let (sum, _carry) = n_bit_adder_with_carry(&remainder, &operand, subtract);
+        remainder = sum;
+        quotient[i] = not(remainder[width - 1]);
+        steps.push(remainder.clone());
+    }
+
+    // 補正: 負なら除数を加える
+    let corrected = remainder[width - 1];
+    let correction: Vec<bool> = extended.iter().map(|&bit| and(bit, corrected)).collect();
+    let (mut remainder, _carry) = n_bit_adder_with_carry(&remainder, &correction, false);
+
+    remainder.truncate(divisor.len());
+    Ok(NonRestoringResult { quotient, remainder, steps, corrected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adder::n_bit_adder;
+    use crate::multiplier::multiply_unsigned;
+    use crate::convert::{bits_from_u16, bits_from_u64_n, to_signed, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_divide_unsigned_exhaustive_8_by_4() {
+        for x in 0..256u64 {
+            for y in 1..16u64 {
+                let (quotient, remainder) = divide_unsigned(&bits_from_u64_n(x, 8), &bits_from_u64_n(y, 4)).unwrap();
+                assert_eq!(quotient.len(), 8);
+                assert_eq!(remainder.len(), 4);
+                assert_eq!((u64_from_bits_slice(&quotient), u64_from_bits_slice(&remainder)), (x / y, x % y), "{} / {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_divide_unsigned_dividend_smaller_than_divisor() {
+        // 商は0で、剰余は被除数そのもの
+        for (x, y) in [(0u64, 1u64), (3, 4), (9, 15), (14, 15)] {
+            let (quotient, remainder) = divide_unsigned(&bits_from_u64_n(x, 8), &bits_from_u64_n(y, 4)).unwrap();
+            assert_eq!((u64_from_bits_slice(&quotient), u64_from_bits_slice(&remainder)), (0, x), "{} / {}", x, y);
+        }
+        // 被除数の方が狭い場合も同じ
+        let (quotient, remainder) = divide_unsigned(&bits_from_u64_n(5, 3), &bits_from_u64_n(200, 8)).unwrap();
+        assert_eq!((quotient, u64_from_bits_slice(&remainder)), (vec![false; 3], 5));
+    }
+
+    #[test]
+    fn test_divide_unsigned_by_zero() {
+        assert_eq!(divide_unsigned(&bits_from_u64_n(42, 8), &bits_from_u64_n(0, 4)), Err(DivisionError::DivideByZero));
+        assert_eq!(divide_unsigned(&bits_from_u64_n(42, 8), &[]), Err(DivisionError::DivideByZero));
+        assert_eq!(DivisionError::DivideByZero.to_string(), "division by zero");
+    }
+
+    #[test]
+    fn test_divide_nonrestoring_matches_restoring() {
+        for x in 0..256u64 {
+            for y in 0..16u64 {
+                let (a, b) = (bits_from_u64_n(x, 8), bits_from_u64_n(y, 4));
+                assert_eq!(divide_nonrestoring(&a, &b), divide_unsigned(&a, &b), "{} / {}", x, y);
+            }
+        }
+
+        let mut rng = XorShift::new(0xD1F0);
+        for _ in 0..300 {
+            let (a, b) = (bits_from_u64_n(rng.next_bits(16), 16), bits_from_u64_n(rng.next_bits(8) | 1, 8));
+            assert_eq!(divide_nonrestoring(&a, &b), divide_unsigned(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_divide_nonrestoring_steps() {
+        // 7 / 2 (0111 ÷ 10): 部分剰余は 0-2=-2, -2*2+1+2=-1, -1*2+1+2=1, 1*2+1-2=1
+        let r = divide_nonrestoring_detailed(&bits_from_u64_n(7, 4), &bits_from_u64_n(2, 2)).unwrap();
+        let steps: Vec<i64> = r.steps.iter().map(|step| to_signed(step)).collect();
+        assert_eq!(steps, vec![-2, -1, 1, 1]);
+        assert!(!r.corrected);
+        assert_eq!((u64_from_bits_slice(&r.quotient), u64_from_bits_slice(&r.remainder)), (3, 1));
+
+        // 8 / 3: 最後の部分剰余が負になり、補正で剰余 2 に戻す
+        let r = divide_nonrestoring_detailed(&bits_from_u64_n(8, 4), &bits_from_u64_n(3, 2)).unwrap();
+        assert!(r.corrected);
+        assert!(to_signed(r.steps.last().unwrap()) < 0);
+        assert_eq!((u64_from_bits_slice(&r.quotient), u64_from_bits_slice(&r.remainder)), (2, 2));
+    }
+
+    #[test]
+    fn test_divide_nonrestoring_by_zero() {
+        assert_eq!(divide_nonrestoring(&bits_from_u64_n(42, 8), &bits_from_u64_n(0, 4)), Err(DivisionError::DivideByZero));
+        assert_eq!(divide_nonrestoring_detailed(&bits_from_u64_n(42, 8), &[]), Err(DivisionError::DivideByZero));
+    }
+
+    #[test]
+    fn test_modulo_cases() {
+        // 2の累乗の法は下位ビットの取り出しと同じ
+        for k in 1..8 {
+            for x in [0u64, 1, 0x5A, 0xFF] {
+                assert_eq!(u64_from_bits_slice(&modulo(&bits_from_u64_n(x, 8), &bits_from_u64_n(1 << k, 8)).unwrap()), x & ((1 << k) - 1));
+            }
+        }
+        // 法が1なら常に0
+        for x in 0..256u64 {
+            assert_eq!(modulo(&bits_from_u64_n(x, 8), &bits_from_u64_n(1, 3)).unwrap(), vec![false; 3]);
+        }
+        // a > m と a < m
+        assert_eq!(u64_from_bits_slice(&modulo(&bits_from_u64_n(200, 8), &bits_from_u64_n(7, 4)).unwrap()), 200 % 7);
+        assert_eq!(u64_from_bits_slice(&modulo(&bits_from_u64_n(5, 8), &bits_from_u64_n(13, 4)).unwrap()), 5);
+        assert_eq!(modulo(&bits_from_u64_n(5, 8), &bits_from_u64_n(0, 4)), Err(DivisionError::DivideByZero));
+    }
+
+    #[test]
+    fn test_modulo_width_and_reconstruction() {
+        // 商 × 法 + 剰余 で被除数に戻る（乗算器と加算器で組み直す）
+        let mut rng = XorShift::new(0x30D0);
+        for _ in 0..200 {
+            let (wa, wm) = (rng.next_bits(3) as usize + 6, rng.next_bits(2) as usize + 2);
+            let a = bits_from_u64_n(rng.next_bits(wa), wa);
+            let m = bits_from_u64_n(rng.next_bits(wm) | 1, wm);
+
+            let remainder = modulo(&a, &m).unwrap();
+            assert_eq!(remainder.len(), m.len());
+            assert!(u64_from_bits_slice(&remainder) < u64_from_bits_slice(&m));
+
+            let (quotient, _) = divide_unsigned(&a, &m).unwrap();
+            let (reconstructed, carry) = n_bit_adder(&multiply_unsigned(&quotient, &m), &remainder);
+            assert!(!carry);
+            assert_eq!(u64_from_bits_slice(&reconstructed), u64_from_bits_slice(&a), "{} mod {}", u64_from_bits_slice(&a), u64_from_bits_slice(&m));
+        }
+    }
+
+    #[test]
+    fn test_mod16() {
+        for (x, y) in [(0u16, 1u16), (1000, 7), (0xFFFF, 0x100), (12, 0xFFFF), (0xFFFF, 0xFFFF)] {
+            assert_eq!(mod16(&bits_from_u16(x), &bits_from_u16(y)), Ok(bits_from_u16(x % y)), "{} mod {}", x, y);
+        }
+        assert_eq!(mod16(&bits_from_u16(1), &bits_from_u16(0)), Err(DivisionError::DivideByZero));
+    }
+}
@@ -0,0 +1,474 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::ops::Range;
+
+use crate::{and, not, xor};
+use crate::bus::{or_reduce, xor_reduce};
+
+/// 16ビットワード
+pub type Word = [bool; 16];
+
+/// ブロック操作（write_block, read_block, fill）のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemError {
+    /// アドレス範囲 start..end がRAMのワード数 size を超える
+    OutOfRange { start: usize, end: usize, size: usize },
+    /// 範囲の終わりが始まりより前にある
+    ReversedRange { start: u16, end: u16 },
+}
+
+impl fmt::Display for MemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemError::OutOfRange { start, end, size } => {
+                write!(f, "addresses {}..{} do not fit in a RAM of {} words", start, end, size)
+            }
+            MemError::ReversedRange { start, end } => {
+                write!(f, "range {}..{} ends before it starts", start, end)
+            }
+        }
+    }
+}
+
+impl core::error::Error for MemError {}
+
+/// start から len ワードの範囲が size ワードのRAMに収まるか確かめ、範囲を返す
+fn block_range(start: u16, len: usize, size: usize) -> Result<Range<usize>, MemError> {
+    let (start, end) = (start as usize, start as usize + len);
+    if end > size {
+        return Err(MemError::OutOfRange { start, end, size });
+    }
+    Ok(start..end)
+}
+
+/// fill の範囲を確かめ、ワード数を返す
+fn fill_len(range: &Range<u16>, size: usize) -> Result<usize, MemError> {
+    if range.end < range.start {
+        return Err(MemError::ReversedRange { start: range.start, end: range.end });
+    }
+    let len = (range.end - range.start) as usize;
+    block_range(range.start, len, size)?;
+    Ok(len)
+}
+
+/// パリティ付きRAM
+///
+/// 各ワードの書き込み時にパリティビット（偶数パリティ）を一緒に保存し、
+/// 読み出し時にパリティを計算し直して保存値と比較する
+///
+/// 1ビットの反転は検出できるが、2ビットの反転はパリティが一致してしまうため検出できない
+pub struct ParityRam {
+    words: Vec<Word>,
+    parity: Vec<bool>,
+}
+
+impl ParityRam {
+    /// `size` ワードのRAMを作る（すべて0で初期化）
+    pub fn new(size: usize) -> Self {
+        ParityRam {
+            words: vec![[false; 16]; size],
+            parity: vec![false; size],
+        }
+    }
+
+    /// ワード数
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// ワード数が0かどうか
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// ワードを書き込み、そのパリティビットを保存する
+    pub fn write(&mut self, addr: u16, word: &Word) {
+        let addr = addr as usize;
+        self.words[addr] = *word;
+        self.parity[addr] = xor_reduce(word);
+    }
+
+    /// ワードを読み出す
+    ///
+    /// 戻り値は (word, parity_ok) のタプル
+    /// - parity_ok: 保存されたパリティと、読み出したワードのパリティが一致すればtrue
+    pub fn read(&self, addr: u16) -> (Word, bool) {
+        let addr = addr as usize;
+        let word = self.words[addr];
+        let parity_ok = not(xor(xor_reduce(&word), self.parity[addr]));
+        (word, parity_ok)
+    }
+
+    /// start から連続するワードをまとめて書き込む
+    ///
+    /// 範囲がRAMに収まらない場合はエラーを返し、何も書き込まない
+    pub fn write_block(&mut self, start: u16, data: &[Word]) -> Result<(), MemError> {
+        let range = block_range(start, data.len(), self.len())?;
+        for (addr, word) in range.zip(data) {
+            self.write(addr as u16, word);
+        }
+        Ok(())
+    }
+
+    /// start から len ワードをまとめて読み出す（各ワードは read と同じ (word, parity_ok)）
+    pub fn read_block(&self, start: u16, len: usize) -> Result<Vec<(Word, bool)>, MemError> {
+        let range = block_range(start, len, self.len())?;
+        Ok(range.map(|addr| self.read(addr as u16)).collect())
+    }
+
+    /// range のすべてのワードに value を書き込む
+    ///
+    /// 範囲がRAMに収まらない場合はエラーを返し、何も書き込まない
+    pub fn fill(&mut self, range: Range<u16>, value: &Word) -> Result<(), MemError> {
+        let len = fill_len(&range, self.len())?;
+        self.write_block(range.start, &vec![*value; len])
+    }
+
+    /// 保存されているビットを1つ反転させる（エラー注入用）
+    ///
+    /// * `bit` - 0〜15はデータビット、16はパリティビット
+    pub fn inject_error(&mut self, addr: u16, bit: usize) {
+        let addr = addr as usize;
+        if bit < 16 {
+            self.words[addr][bit] = not(self.words[addr][bit]);
+        } else {
+            assert!(bit == 16, "bit index {} out of range for a parity-protected word", bit);
+            self.parity[addr] = not(self.parity[addr]);
+        }
+    }
+}
+
+/// SECDED符号語（16ビットのデータ + 5ビットのハミング検査ビット + 1ビットの全体パリティ）
+///
+/// - インデックス0: 全体パリティ
+/// - インデックス1〜21: ハミング符号の位置（1, 2, 4, 8, 16 が検査ビット、それ以外がデータ）
+pub type Codeword = [bool; 22];
+
+/// ハミング符号の位置のうち、データビットが置かれる位置（LSBから順に）
+pub(crate) const DATA_POSITIONS: [usize; 16] = [3, 5, 6, 7, 9, 10, 11, 12, 13, 14, 15, 17, 18, 19, 20, 21];
+
+/// 読み出し時の誤り訂正の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EccStatus {
+    /// 誤りなし
+    Clean,
+    /// 1ビットの誤りを訂正した（符号語のビット位置）
+    Corrected(usize),
+    /// 2ビットの誤りを検出した（訂正はしない）
+    DoubleError,
+}
+
+/// ハミング符号のシンドローム
+///
+/// 検査ビットkは、位置の2進表現のkビット目が1であるすべての位置の XOR
+/// 正しい符号語ではすべて0になり、1ビット誤りがあると誤った位置の番号になる
+fn syndrome(code: &Codeword) -> [bool; 5] {
+    let mut s = [false; 5];
+    for (k, bit) in s.iter_mut().enumerate() {
+        let covered: Vec<bool> = (1..22).filter(|p| (p >> k) & 1 == 1).map(|p| code[p]).collect();
+        *bit = xor_reduce(&covered);
+    }
+    s
+}
+
+/// 16ビットワードをSECDED符号語に符号化する
+pub fn secded_encode(data: &Word) -> Codeword {
+    let mut code = [false; 22];
+    for (i, &p) in DATA_POSITIONS.iter().enumerate() {
+        code[p] = data[i];
+    }
+
+    // 検査ビットの位置はまだ0なので、シンドロームがそのまま検査ビットの値になる
+    let s = syndrome(&code);
+    for (k, &bit) in s.iter().enumerate() {
+        code[1 << k] = bit;
+    }
+
+    code[0] = xor_reduce(&code[1..]);
+    code
+}
+
+/// SECDED符号語を復号する
+///
+/// シンドロームと全体パリティから誤りの種類を判定し、1ビット誤りなら
+/// シンドロームが示す位置のビットを反転して訂正する
+///
+/// 戻り値は (data, status) のタプル
+/// - 2ビット誤りの場合、dataは訂正していない符号語のデータビット
+pub fn secded_decode(code: &Codeword) -> (Word, EccStatus) {
+    let s = syndrome(code);
+    let parity_error = xor_reduce(code);
+    let syndrome_nonzero = or_reduce(&s);
+
+    let position = s.iter().rev().fold(0usize, |acc, &b| (acc << 1) | b as usize);
+    let status = if !parity_error && !syndrome_nonzero {
+        EccStatus::Clean
+    } else if parity_error && position < 22 {
+        // シンドローム0で全体パリティだけが合わない場合は、全体パリティビット自身の誤り
+        EccStatus::Corrected(position)
+    } else {
+        EccStatus::DoubleError
+    };
+
+    // 訂正: シンドロームと一致する位置のビットだけを、全体パリティ誤りのときに反転する
+    let mut corrected = *code;
+    for (p, bit) in corrected.iter_mut().enumerate() {
+        let mut matches = true;
+        for (k, &sk) in s.iter().enumerate() {
+            matches = and(matches, not(xor(sk, (p >> k) & 1 == 1)));
+        }
+        *bit = xor(*bit, and(parity_error, matches));
+    }
+
+    let mut data = [false; 16];
+    for (i, &p) in DATA_POSITIONS.iter().enumerate() {
+        data[i] = if status == EccStatus::DoubleError { code[p] } else { corrected[p] };
+    }
+    (data, status)
+}
+
+/// SECDED ECC付きRAM
+///
+/// 各ワードを22ビットの符号語として保存し、読み出し時に1ビット誤りを透過的に訂正する
+/// （訂正した値は保存し直さないので、誤りは次の書き込みまで残る）
+pub struct EccRam {
+    codes: Vec<Codeword>,
+}
+
+impl EccRam {
+    /// `size` ワードのRAMを作る（すべて0で初期化）
+    pub fn new(size: usize) -> Self {
+        EccRam { codes: vec![secded_encode(&[false; 16]); size] }
+    }
+
+    /// ワード数
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// ワード数が0かどうか
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// ワードを符号化して書き込む
+    pub fn write(&mut self, addr: u16, word: &Word) {
+        self.codes[addr as usize] = secded_encode(word);
+    }
+
+    /// ワードを読み出す
+    ///
+    /// 戻り値は (word, status) のタプル
+    pub fn read(&self, addr: u16) -> (Word, EccStatus) {
+        secded_decode(&self.codes[addr as usize])
+    }
+
+    /// start から連続するワードをまとめて符号化して書き込む
+    ///
+    /// 範囲がRAMに収まらない場合はエラーを返し、何も書き込まない
+    pub fn write_block(&mut self, start: u16, data: &[Word]) -> Result<(), MemError> {
+        let range = block_range(start, data.len(), self.len())?;
+        for (addr, word) in range.zip(data) {
+            self.write(addr as u16, word);
+        }
+        Ok(())
+    }
+
+    /// start から len ワードをまとめて読み出す（各ワードは read と同じ (word, status)）
+    pub fn read_block(&self, start: u16, len: usize) -> Result<Vec<(Word, EccStatus)>, MemError> {
+        let range = block_range(start, len, self.len())?;
+        Ok(range.map(|addr| self.read(addr as u16)).collect())
+    }
+
+    /// range のすべてのワードに value を書き込む
+    ///
+    /// 範囲がRAMに収まらない場合はエラーを返し、何も書き込まない
+    pub fn fill(&mut self, range: Range<u16>, value: &Word) -> Result<(), MemError> {
+        let len = fill_len(&range, self.len())?;
+        self.write_block(range.start, &vec![*value; len])
+    }
+
+    /// 保存されている符号語のビットを1つ反転させる（エラー注入用）
+    ///
+    /// * `bit` - 符号語のビット位置（0〜21、Codewordを参照）
+    pub fn inject_error(&mut self, addr: u16, bit: usize) {
+        let code = &mut self.codes[addr as usize];
+        code[bit] = not(code[bit]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::bits_from_u16;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_parity_ram_round_trip() {
+        let mut ram = ParityRam::new(8);
+        for (addr, v) in [0x0000u16, 0x0001, 0x8000, 0xFFFF, 0x1234].iter().enumerate() {
+            ram.write(addr as u16, &bits_from_u16(*v));
+            assert_eq!(ram.read(addr as u16), (bits_from_u16(*v), true));
+        }
+    }
+
+    #[test]
+    fn test_parity_ram_single_bit_errors() {
+        // パリティビットを含む17ビットのどれを反転しても検出できる
+        for bit in 0..17 {
+            let mut ram = ParityRam::new(1);
+            ram.write(0, &bits_from_u16(0xA5C3));
+            ram.inject_error(0, bit);
+            let (_, parity_ok) = ram.read(0);
+            assert_eq!(parity_ok, false, "bit = {}", bit);
+        }
+    }
+
+    #[test]
+    fn test_parity_ram_double_bit_error_missed() {
+        // 2ビットの反転はパリティでは検出できない
+        let mut ram = ParityRam::new(1);
+        ram.write(0, &bits_from_u16(0x00FF));
+        ram.inject_error(0, 3);
+        ram.inject_error(0, 12);
+        let (read, parity_ok) = ram.read(0);
+        assert_ne!(read, bits_from_u16(0x00FF));
+        assert_eq!(parity_ok, true);
+    }
+
+    #[test]
+    fn test_parity_ram_block_round_trip() {
+        let mut ram = ParityRam::new(16);
+        let data: Vec<Word> = [0x0001u16, 0x8000, 0xFFFF, 0x1234, 0xA5C3].iter().map(|&v| bits_from_u16(v)).collect();
+        ram.write_block(3, &data).unwrap();
+        let expected: Vec<(Word, bool)> = data.iter().map(|&w| (w, true)).collect();
+        assert_eq!(ram.read_block(3, data.len()).unwrap(), expected);
+        assert_eq!(ram.read(7), (bits_from_u16(0xA5C3), true));
+
+        // fill した範囲だけが書き換わる
+        ram.fill(10..16, &bits_from_u16(0xBEEF)).unwrap();
+        assert_eq!(ram.read_block(10, 6).unwrap(), vec![(bits_from_u16(0xBEEF), true); 6]);
+        assert_eq!(ram.read_block(8, 2).unwrap(), vec![(bits_from_u16(0), true); 2]);
+
+        // 保存されたパリティも書き込みと同じように検査される
+        ram.inject_error(4, 16);
+        assert_eq!(ram.read_block(4, 1).unwrap(), vec![(bits_from_u16(0x8000), false)]);
+    }
+
+    #[test]
+    fn test_block_ops_reject_out_of_range() {
+        let mut ram = ParityRam::new(8);
+        let data = [bits_from_u16(0x1111); 3];
+
+        // RAMの終わりをまたぐ書き込みは何も書き込まずにエラーになる
+        assert_eq!(ram.write_block(6, &data), Err(MemError::OutOfRange { start: 6, end: 9, size: 8 }));
+        assert_eq!(ram.read_block(0, 8).unwrap(), vec![(bits_from_u16(0), true); 8]);
+        assert_eq!(ram.read_block(7, 2), Err(MemError::OutOfRange { start: 7, end: 9, size: 8 }));
+        assert_eq!(ram.fill(4..9, &bits_from_u16(1)), Err(MemError::OutOfRange { start: 4, end: 9, size: 8 }));
+        // 逆向きの範囲（リテラルの 5..3 は clippy が空の範囲として警告する）
+        assert_eq!(ram.fill(Range { start: 5, end: 3 }, &bits_from_u16(1)), Err(MemError::ReversedRange { start: 5, end: 3 }));
+
+        // 終わりにちょうど収まる範囲と空の範囲は使える
+        assert_eq!(ram.write_block(5, &data), Ok(()));
+        assert_eq!(ram.write_block(8, &[]), Ok(()));
+        assert_eq!(ram.read_block(8, 0), Ok(Vec::new()));
+        assert_eq!(ram.fill(3..3, &bits_from_u16(1)), Ok(()));
+
+        let mut ecc = EccRam::new(4);
+        assert_eq!(ecc.write_block(2, &data), Err(MemError::OutOfRange { start: 2, end: 5, size: 4 }));
+        assert_eq!(ecc.fill(0..5, &bits_from_u16(1)), Err(MemError::OutOfRange { start: 0, end: 5, size: 4 }));
+        assert_eq!(ecc.read_block(0, 4).unwrap(), vec![(bits_from_u16(0), EccStatus::Clean); 4]);
+    }
+
+    #[test]
+    fn test_ecc_ram_fill_then_read_block() {
+        let mut ram = EccRam::new(32);
+        ram.fill(0..32, &bits_from_u16(0xC0DE)).unwrap();
+        ram.write_block(8, &[bits_from_u16(1), bits_from_u16(2)]).unwrap();
+        ram.inject_error(20, 5);
+
+        let block = ram.read_block(0, 32).unwrap();
+        for (addr, &(read, status)) in block.iter().enumerate() {
+            let expected = match addr {
+                8 => (bits_from_u16(1), EccStatus::Clean),
+                9 => (bits_from_u16(2), EccStatus::Clean),
+                20 => (bits_from_u16(0xC0DE), EccStatus::Corrected(5)),
+                _ => (bits_from_u16(0xC0DE), EccStatus::Clean),
+            };
+            assert_eq!((read, status), expected, "addr = {}", addr);
+        }
+    }
+
+    #[test]
+    fn test_ecc_ram_round_trip() {
+        let mut ram = EccRam::new(4);
+        for (addr, v) in [0x0000u16, 0xFFFF, 0x8001, 0x5A5A].iter().enumerate() {
+            ram.write(addr as u16, &bits_from_u16(*v));
+            assert_eq!(ram.read(addr as u16), (bits_from_u16(*v), EccStatus::Clean));
+        }
+    }
+
+    #[test]
+    fn test_ecc_ram_corrects_single_bit_errors() {
+        // データビット・検査ビット・全体パリティのどれを反転しても訂正できる
+        for v in [0x0000u16, 0xFFFF, 0x1234] {
+            for bit in 0..22 {
+                let mut ram = EccRam::new(1);
+                ram.write(0, &bits_from_u16(v));
+                ram.inject_error(0, bit);
+                assert_eq!(ram.read(0), (bits_from_u16(v), EccStatus::Corrected(bit)), "v = {:#06x}, bit = {}", v, bit);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ecc_ram_detects_double_bit_errors() {
+        for first in 0..22 {
+            for second in (first + 1)..22 {
+                let mut ram = EccRam::new(1);
+                ram.write(0, &bits_from_u16(0xC0DE));
+                ram.inject_error(0, first);
+                ram.inject_error(0, second);
+                let (_, status) = ram.read(0);
+                assert_eq!(status, EccStatus::DoubleError, "bits = {}, {}", first, second);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ecc_ram_soak() {
+        // ランダムな書き込み・読み出し・エラー注入を行い、モデルと状態を比較する
+        let mut rng = XorShift::new(0xECC);
+        let mut ram = EccRam::new(16);
+        let mut model: Vec<(u16, Vec<usize>)> = vec![(0, Vec::new()); 16];
+
+        for _ in 0..5000 {
+            let addr = rng.next_bits(4) as usize;
+            match rng.next_bits(2) {
+                0 => {
+                    let v = rng.next_bits(16) as u16;
+                    ram.write(addr as u16, &bits_from_u16(v));
+                    model[addr] = (v, Vec::new());
+                }
+                1 => {
+                    // 1ワードあたり高々2ビットまで誤りを注入する
+                    let bit = (rng.next_u64() % 22) as usize;
+                    let flipped = &mut model[addr].1;
+                    if flipped.len() < 2 && !flipped.contains(&bit) {
+                        ram.inject_error(addr as u16, bit);
+                        flipped.push(bit);
+                    }
+                }
+                _ => {
+                    let (v, flipped) = &model[addr];
+                    let (read, status) = ram.read(addr as u16);
+                    match flipped.len() {
+                        0 => assert_eq!((read, status), (bits_from_u16(*v), EccStatus::Clean)),
+                        1 => assert_eq!((read, status), (bits_from_u16(*v), EccStatus::Corrected(flipped[0]))),
+                        _ => assert_eq!(status, EccStatus::DoubleError),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{and, not};
+use crate::adder::{decrementer, incrementer, n_bit_subtractor};
+use crate::bus::{mux_bus, or_reduce};
+use crate::shifter::{shift_left, shift_right_logical};
+use crate::wiring::zero_extend;
+
+/// GCD 回路のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GcdError {
+    /// 上限のステップ数までに計算が終わらなかった
+    IterationLimit { steps: usize },
+}
+
+impl fmt::Display for GcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GcdError::IterationLimit { steps } => {
+                write!(f, "gcd did not finish within {} steps", steps)
+            }
+        }
+    }
+}
+
+impl core::error::Error for GcdError {}
+
+/// GCD 回路の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcdPhase {
+    /// a と b を2で割る・引き算するステップ
+    Reduce,
+    /// 取り除いた共通の2の累乗を、結果を左シフトして戻すステップ
+    Restore,
+    /// 計算が終わった
+    Done,
+}
+
+/// 2進GCD（Stein のアルゴリズム）の順序回路
+///
+/// 2つのレジスタ a, b と、共通の因数2の個数を数えるカウンタ shift を持ち、
+/// step ごとに次のどれか1つを行う
+///
+/// - Reduce: a か b が0なら、もう一方を結果にして Restore へ進む
+///   - 両方が偶数（LSB が0）なら両方を1ビット右シフトし、shift を incrementer で1増やす
+///   - 片方だけが偶数なら、その方を1ビット右シフトする
+///   - 両方が奇数なら、大きい方から小さい方を引く（大小は n_bit_subtractor の借りで判定する）
+/// - Restore: shift が0なら Done へ。そうでなければ結果を1ビット左シフトし、shift を decrementer で1減らす
+///
+/// 値の判定と更新はすべてゲートの回路で行い、整数としての演算は使わない
+#[derive(Debug, Clone)]
+pub struct GcdUnit {
+    a: Vec<bool>,
+    b: Vec<bool>,
+    shift: Vec<bool>,
+    phase: GcdPhase,
+}
+
+impl GcdUnit {
+    /// a と b を読み込んだ状態で作る
+    ///
+    /// 幅が異なる場合は短い方を0で埋める。結果は広い方と同じ幅になる
+    pub fn new(a: &[bool], b: &[bool]) -> Self {
+        let width = a.len().max(b.len());
+        // shift は width まで数えられる幅にする
+        let shift_width = (usize::BITS - width.leading_zeros()).max(1) as usize;
+        GcdUnit {
+            a: zero_extend(a, width),
+            b: zero_extend(b, width),
+            shift: vec![false; shift_width],
+            phase: GcdPhase::Reduce,
+        }
+    }
+
+    /// 現在の状態
+    pub fn phase(&self) -> GcdPhase {
+        self.phase
+    }
+
+    /// レジスタ a, b と結果の幅
+    pub fn width(&self) -> usize {
+        self.a.len()
+    }
+
+    /// 計算が終わっていれば結果（LSB→MSB順）
+    pub fn result(&self) -> Option<&[bool]> {
+        if self.phase == GcdPhase::Done { Some(&self.a) } else { None }
+    }
+
+    /// クロックを1つ進める
+    ///
+    /// Done の状態では何もしない
+    pub fn step(&mut self) {
+        match self.phase {
+            GcdPhase::Reduce => self.reduce(),
+            GcdPhase::Restore => {
+                if or_reduce(&self.shift) {
+                    self.a = shift_left(&self.a, 1).0;
+                    self.shift = decrementer(&self.shift).0;
+                } else {
+                    self.phase = GcdPhase::Done;
+                }
+            }
+            GcdPhase::Done => {}
+        }
+    }
+
+    fn reduce(&mut self) {
+        // 結果は a に置いて Restore へ進む
+        if !or_reduce(&self.a) {
+            self.a = self.b.clone();
+            self.phase = GcdPhase::Restore;
+            return;
+        }
+        if !or_reduce(&self.b) {
+            self.phase = GcdPhase::Restore;
+            return;
+        }
+
+        let a_even = not(self.a[0]);
+        let b_even = not(self.b[0]);
+        if and(a_even, b_even) {
+            self.a = shift_right_logical(&self.a, 1).0;
+            self.b = shift_right_logical(&self.b, 1).0;
+            self.shift = incrementer(&self.shift).0;
+        } else if a_even {
+            self.a = shift_right_logical(&self.a, 1).0;
+        } else if b_even {
+            self.b = shift_right_logical(&self.b, 1).0;
+        } else {
+            // 両方が奇数: a - b で借りが出なければ a >= b
+            let (a_minus_b, borrow) = n_bit_subtractor(&self.a, &self.b);
+            let (b_minus_a, _) = n_bit_subtractor(&self.b, &self.a);
+            self.a = mux_bus(borrow, &a_minus_b, &self.a);
+            self.b = mux_bus(borrow, &self.b, &b_minus_a);
+        }
+    }
+
+    /// Done になるまで step を繰り返し、結果を返す
+    ///
+    /// max_steps 回の step で終わらなければ GcdError::IterationLimit を返す
+    pub fn run_to_completion(&mut self, max_steps: usize) -> Result<Vec<bool>, GcdError> {
+        for _ in 0..max_steps {
+            if let Some(result) = self.result() {
+                return Ok(result.to_vec());
+            }
+            self.step();
+        }
+        self.result().map(|result| result.to_vec()).ok_or(GcdError::IterationLimit { steps: max_steps })
+    }
+}
+
+/// 最大公約数（2進GCD）
+///
+/// GcdUnit を Done になるまで動かす。Reduce の2ステップごとに a と b のビット長の合計が
+/// 少なくとも1減るので、nビットの入力は 5n + 2 ステップ以内に終わる
+///
+/// * `a`, `b` - 入力（LSB→MSB順、短い方は0で埋める）
+///
+/// 戻り値は広い方と同じ幅の gcd(a, b)。gcd(0, 0) は0
+pub fn gcd(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let width = a.len().max(b.len());
+    GcdUnit::new(a, b)
+        .run_to_completion(5 * width + 2)
+        .expect("binary gcd finishes within 5n + 2 steps")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u64_n, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    fn euclid(mut x: u64, mut y: u64) -> u64 {
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+        x
+    }
+
+    #[test]
+    fn test_gcd_with_zero() {
+        for x in [0u64, 1, 6, 255] {
+            assert_eq!(u64_from_bits_slice(&gcd(&bits_from_u64_n(0, 8), &bits_from_u64_n(x, 8))), x);
+            assert_eq!(u64_from_bits_slice(&gcd(&bits_from_u64_n(x, 8), &bits_from_u64_n(0, 8))), x);
+        }
+    }
+
+    #[test]
+    fn test_gcd_cases() {
+        // 等しい入力、互いに素な組、両方が偶数の組
+        for (x, y, expected) in [(42u64, 42u64, 42u64), (255, 255, 255), (17, 5, 1), (64, 81, 1), (48, 180, 12), (128, 96, 32)] {
+            let result = gcd(&bits_from_u64_n(x, 8), &bits_from_u64_n(y, 8));
+            assert_eq!(result.len(), 8);
+            assert_eq!(u64_from_bits_slice(&result), expected, "gcd({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_gcd_exhaustive_6bit() {
+        for x in 0..64u64 {
+            for y in 0..64u64 {
+                assert_eq!(u64_from_bits_slice(&gcd(&bits_from_u64_n(x, 6), &bits_from_u64_n(y, 6))), euclid(x, y), "gcd({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gcd_random_u32() {
+        let mut rng = XorShift::new(0x6CD0);
+        for _ in 0..100 {
+            let (x, y) = (rng.next_bits(32), rng.next_bits(32));
+            // 共通の2の累乗を持つ組も混ぜる
+            let k = rng.next_bits(3);
+            let (x, y) = ((x << k) & 0xFFFF_FFFF, (y << k) & 0xFFFF_FFFF);
+            assert_eq!(u64_from_bits_slice(&gcd(&bits_from_u64_n(x, 32), &bits_from_u64_n(y, 32))), euclid(x, y), "gcd({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_gcd_unit_steps() {
+        // gcd(12, 8): 両方偶数 ×2 で shift = 2、(3, 2) → (3, 1) → (2, 1) → (1, 1) → (0, 1) の4ステップ、
+        // a = 0 を見て b を結果にする1ステップ、左シフト ×2 と shift = 0 を見て終わる1ステップ
+        let mut unit = GcdUnit::new(&bits_from_u64_n(12, 4), &bits_from_u64_n(8, 4));
+        let mut steps = 0;
+        while unit.phase() != GcdPhase::Done {
+            assert_eq!(unit.result(), None);
+            unit.step();
+            steps += 1;
+        }
+        assert_eq!(u64_from_bits_slice(unit.result().unwrap()), 4);
+        assert_eq!(steps, 10);
+
+        // Done の後の step は何もしない
+        unit.step();
+        assert_eq!(u64_from_bits_slice(unit.result().unwrap()), 4);
+    }
+
+    #[test]
+    fn test_gcd_iteration_limit() {
+        let mut unit = GcdUnit::new(&bits_from_u64_n(12, 4), &bits_from_u64_n(8, 4));
+        assert_eq!(unit.run_to_completion(3), Err(GcdError::IterationLimit { steps: 3 }));
+        // 続きから実行できる
+        assert_eq!(unit.run_to_completion(100).map(|r| u64_from_bits_slice(&r)), Ok(4));
+        assert_eq!(GcdError::IterationLimit { steps: 3 }.to_string(), "gcd did not finish within 3 steps");
+    }
+}
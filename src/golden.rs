@@ -0,0 +1,394 @@
+use alloc::{format, string::{String, ToString}, vec::Vec};
+use core::fmt;
+
+use crate::convert::format_bits;
+
+/// ゴールデンベクタファイルのエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldenError {
+    /// line行目（1始まり）に "->" がない、または両辺のどちらかが空
+    MissingArrow { line: usize },
+    /// line行目のトークンが 0b/0x の形式になっていない
+    InvalidToken { line: usize, token: String },
+    /// line行目の入力または出力の幅が、1行目と異なる
+    InconsistentWidth { line: usize, expected: usize, actual: usize },
+    /// 関数の出力の幅がファイルの出力の幅と異なる
+    WidthMismatch { expected: usize, actual: usize },
+    /// 記録する値の幅（actual）が、トークンの幅の合計（expected）と一致しない
+    FieldWidth { expected: usize, actual: usize },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::MissingArrow { line } => write!(f, "line {}: expected \"inputs -> outputs\"", line),
+            GoldenError::InvalidToken { line, token } => {
+                write!(f, "line {}: invalid token {:?} (expected 0b... or 0x...)", line, token)
+            }
+            GoldenError::InconsistentWidth { line, expected, actual } => {
+                write!(f, "line {}: width {} differs from the first vector's width {}", line, actual, expected)
+            }
+            GoldenError::WidthMismatch { expected, actual } => {
+                write!(f, "function produced {} output bits, file expects {}", actual, expected)
+            }
+            GoldenError::FieldWidth { expected, actual } => {
+                write!(f, "value of {} bits does not match fields totaling {} bits", actual, expected)
+            }
+        }
+    }
+}
+
+impl core::error::Error for GoldenError {}
+
+/// ゴールデンベクタの1行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenVector {
+    /// ファイル上の行番号（1始まり）
+    pub line: usize,
+    pub inputs: Vec<bool>,
+    pub outputs: Vec<bool>,
+}
+
+/// 入出力の期待値の一覧（ゴールデンベクタ）
+///
+/// ファイル形式は1行に1ベクタで `入力 -> 出力` と書く
+/// - 各辺は空白区切りのトークンの並びで、トークンは `0b0101`（2進）または `0x1F`（16進）
+/// - トークンの中では左端がMSB。16進は1桁あたり4ビット
+/// - 1つの辺の中では、先に書いたトークンが下位ビットになるように連結する（LSB→MSB順）
+/// - `#` 以降はコメント、空行は無視する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenVectors {
+    pub vectors: Vec<GoldenVector>,
+}
+
+/// 食い違いのあった1行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    pub line: usize,
+    pub inputs: Vec<bool>,
+    pub expected: Vec<bool>,
+    pub actual: Vec<bool>,
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: inputs {} expected {} but got {}",
+            self.line,
+            render(&self.inputs),
+            render(&self.expected),
+            render(&self.actual)
+        )
+    }
+}
+
+/// ゴールデンベクタとの比較結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenReport {
+    /// 比較したベクタの数
+    pub total: usize,
+    pub mismatches: Vec<GoldenMismatch>,
+}
+
+impl GoldenReport {
+    /// すべてのベクタが一致したかどうか
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} of {} vectors mismatched", self.mismatches.len(), self.total)?;
+        for mismatch in &self.mismatches {
+            writeln!(f, "  {}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+/// ビット列（LSB→MSB順）をMSBが左の2進文字列にする
+fn render(bits: &[bool]) -> String {
+    format!("0b{}", format_bits(bits))
+}
+
+fn parse_token(line: usize, token: &str) -> Result<Vec<bool>, GoldenError> {
+    let invalid = || GoldenError::InvalidToken { line, token: token.to_string() };
+
+    let (digits, bits_per_digit, radix) = if let Some(d) = token.strip_prefix("0b") {
+        (d, 1, 2)
+    } else if let Some(d) = token.strip_prefix("0x") {
+        (d, 4, 16)
+    } else {
+        return Err(invalid());
+    };
+    if digits.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut bits = Vec::with_capacity(digits.len() * bits_per_digit);
+    for ch in digits.chars().rev() {
+        let v = ch.to_digit(radix).ok_or_else(invalid)?;
+        for i in 0..bits_per_digit {
+            bits.push((v >> i) & 1 == 1);
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_side(line: usize, side: &str) -> Result<Vec<bool>, GoldenError> {
+    let mut bits = Vec::new();
+    for token in side.split_whitespace() {
+        bits.extend(parse_token(line, token)?);
+    }
+    Ok(bits)
+}
+
+impl GoldenVectors {
+    /// ゴールデンベクタファイルを読み込む
+    ///
+    /// すべての行で入力と出力の幅がそろっていなければエラーにする
+    pub fn load(text: &str) -> Result<Self, GoldenError> {
+        let mut vectors: Vec<GoldenVector> = Vec::new();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let content = raw.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            let (lhs, rhs) = content.split_once("->").ok_or(GoldenError::MissingArrow { line })?;
+            if lhs.trim().is_empty() || rhs.trim().is_empty() {
+                return Err(GoldenError::MissingArrow { line });
+            }
+            let inputs = parse_side(line, lhs)?;
+            let outputs = parse_side(line, rhs)?;
+
+            if let Some(first) = vectors.first() {
+                if inputs.len() != first.inputs.len() {
+                    return Err(GoldenError::InconsistentWidth { line, expected: first.inputs.len(), actual: inputs.len() });
+                }
+                if outputs.len() != first.outputs.len() {
+                    return Err(GoldenError::InconsistentWidth { line, expected: first.outputs.len(), actual: outputs.len() });
+                }
+            }
+            vectors.push(GoldenVector { line, inputs, outputs });
+        }
+        Ok(GoldenVectors { vectors })
+    }
+
+    /// すべてのベクタの入力を関数に与え、出力を期待値と比較する
+    ///
+    /// すべてのベクタで幅を確かめる
+    /// - 入力または期待値の幅が最初のベクタと異なれば InconsistentWidth を返す
+    /// - 関数の出力幅が期待値の幅と異なれば WidthMismatch を返す
+    pub fn run_against(&self, f: impl Fn(&[bool]) -> Vec<bool>) -> Result<GoldenReport, GoldenError> {
+        let Some(first) = self.vectors.first() else {
+            return Ok(GoldenReport { total: 0, mismatches: Vec::new() });
+        };
+        let mut mismatches = Vec::new();
+        for vector in &self.vectors {
+            if vector.inputs.len() != first.inputs.len() {
+                return Err(GoldenError::InconsistentWidth {
+                    line: vector.line,
+                    expected: first.inputs.len(),
+                    actual: vector.inputs.len(),
+                });
+            }
+            if vector.outputs.len() != first.outputs.len() {
+                return Err(GoldenError::InconsistentWidth {
+                    line: vector.line,
+                    expected: first.outputs.len(),
+                    actual: vector.outputs.len(),
+                });
+            }
+            let actual = f(&vector.inputs);
+            if actual.len() != vector.outputs.len() {
+                return Err(GoldenError::WidthMismatch { expected: vector.outputs.len(), actual: actual.len() });
+            }
+            if actual != vector.outputs {
+                mismatches.push(GoldenMismatch {
+                    line: vector.line,
+                    inputs: vector.inputs.clone(),
+                    expected: vector.outputs.clone(),
+                    actual,
+                });
+            }
+        }
+        Ok(GoldenReport { total: self.vectors.len(), mismatches })
+    }
+}
+
+fn write_side(out: &mut String, bits: &[bool], fields: &[usize]) -> Result<(), GoldenError> {
+    let expected: usize = fields.iter().sum();
+    if bits.len() != expected {
+        return Err(GoldenError::FieldWidth { expected, actual: bits.len() });
+    }
+    let mut offset = 0;
+    for (i, &width) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        out.push_str(&render(&bits[offset..offset + width]));
+        offset += width;
+    }
+    Ok(())
+}
+
+/// 関数の出力を記録して、ゴールデンベクタファイルを作り直す
+///
+/// * `f` - 記録する関数
+/// * `inputs` - 入力ベクタの一覧（LSB→MSB順）
+/// * `input_fields` - 入力側をトークンに区切る幅（下位ビットから順に）
+/// * `output_fields` - 出力側をトークンに区切る幅（下位ビットから順に）
+///
+/// トークンは常に2進（0b...）で書き出す
+/// 入力または出力の幅がトークンの幅の合計と一致しない場合は、ビットを捨てずに FieldWidth を返す
+pub fn record(
+    f: impl Fn(&[bool]) -> Vec<bool>,
+    inputs: &[Vec<bool>],
+    input_fields: &[usize],
+    output_fields: &[usize],
+) -> Result<String, GoldenError> {
+    let mut out = String::new();
+    for input in inputs {
+        write_side(&mut out, input, input_fields)?;
+        out.push_str(" -> ");
+        write_side(&mut out, &f(input), output_fields)?;
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adder::n_bit_adder;
+    use crate::convert::bits_from_u64_n;
+
+    // 入力 a, b（各4ビット）-> 出力 sum（4ビット）, carry（1ビット）
+    fn adder4(v: &[bool]) -> Vec<bool> {
+        let (mut sum, carry) = n_bit_adder(&v[0..4], &v[4..8]);
+        sum.push(carry);
+        sum
+    }
+
+    fn adder4_inputs() -> Vec<Vec<bool>> {
+        let mut inputs = Vec::new();
+        for a in [0u64, 3, 7, 9, 15] {
+            for b in [0u64, 1, 8, 15] {
+                let mut v = bits_from_u64_n(a, 4);
+                v.extend(bits_from_u64_n(b, 4));
+                inputs.push(v);
+            }
+        }
+        inputs
+    }
+
+    const ADDER4_GOLDEN: &str = "\
+0b0011 0b0101 -> 0b1000 0b0
+0b1111 0b0001 -> 0b0000 0b1
+0x9 0x8 -> 0x1 0b1
+";
+
+    #[test]
+    fn test_adder4_golden_passes() {
+        let golden = GoldenVectors::load(ADDER4_GOLDEN).unwrap();
+        assert_eq!(golden.vectors.len(), 3);
+
+        let report = golden.run_against(adder4).unwrap();
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.total, 3);
+    }
+
+    #[test]
+    fn test_corrupted_line_is_reported() {
+        let text = "# 4ビット加算器\n0b0011 0b0101 -> 0b1000 0b0\n\n0b0111 0b0001 -> 0b1001 0b0\n";
+        let golden = GoldenVectors::load(text).unwrap();
+        let report = golden.run_against(adder4).unwrap();
+
+        assert_eq!(report.mismatches.len(), 1);
+        let mismatch = &report.mismatches[0];
+        assert_eq!(mismatch.line, 4);
+        assert_eq!(mismatch.expected, bits_from_u64_n(0b01001, 5));
+        assert_eq!(mismatch.actual, bits_from_u64_n(0b01000, 5));
+        assert!(report.to_string().contains("line 4: inputs 0b00010111 expected 0b01001 but got 0b01000"));
+    }
+
+    #[test]
+    fn test_width_errors() {
+        assert_eq!(
+            GoldenVectors::load("0b01 -> 0b1\n0b011 -> 0b1\n"),
+            Err(GoldenError::InconsistentWidth { line: 2, expected: 2, actual: 3 })
+        );
+        assert_eq!(
+            GoldenVectors::load("0b01 0b1\n"),
+            Err(GoldenError::MissingArrow { line: 1 })
+        );
+        assert_eq!(
+            GoldenVectors::load("0b012 -> 0b1\n"),
+            Err(GoldenError::InvalidToken { line: 1, token: "0b012".to_string() })
+        );
+
+        // 関数の出力幅がファイルと異なる場合は、比較を始める前にエラーにする
+        let golden = GoldenVectors::load(ADDER4_GOLDEN).unwrap();
+        assert_eq!(
+            golden.run_against(|v| n_bit_adder(&v[0..4], &v[4..8]).0),
+            Err(GoldenError::WidthMismatch { expected: 5, actual: 4 })
+        );
+
+        // 2本目以降のベクタでも幅を確かめる
+        let wrong_after_first = |v: &[bool]| if v[4] { adder4(v) } else { n_bit_adder(&v[0..4], &v[4..8]).0 };
+        assert_eq!(
+            golden.run_against(wrong_after_first),
+            Err(GoldenError::WidthMismatch { expected: 5, actual: 4 })
+        );
+
+        // load を経ずに組み立てたベクタでも、入力・期待値の幅を確かめる
+        let mut edited = golden.clone();
+        edited.vectors[2].inputs.pop();
+        assert_eq!(
+            edited.run_against(adder4),
+            Err(GoldenError::InconsistentWidth { line: 3, expected: 8, actual: 7 })
+        );
+        let mut edited = golden.clone();
+        edited.vectors[1].outputs.push(false);
+        assert_eq!(
+            edited.run_against(adder4),
+            Err(GoldenError::InconsistentWidth { line: 2, expected: 5, actual: 6 })
+        );
+    }
+
+    #[test]
+    fn test_record_reproduces_file() {
+        let original = "\
+0b0000 0b0000 -> 0b0000 0b0
+0b0011 0b0101 -> 0b1000 0b0
+0b0111 0b1001 -> 0b0000 0b1
+0b1111 0b1111 -> 0b1110 0b1
+";
+        let golden = GoldenVectors::load(original).unwrap();
+        let inputs: Vec<Vec<bool>> = golden.vectors.iter().map(|v| v.inputs.clone()).collect();
+        assert_eq!(record(adder4, &inputs, &[4, 4], &[4, 1]).unwrap(), original);
+
+        // 記録したファイルは、そのまま読み込んで比較できる
+        let text = record(adder4, &adder4_inputs(), &[4, 4], &[4, 1]).unwrap();
+        assert!(GoldenVectors::load(&text).unwrap().run_against(adder4).unwrap().passed());
+    }
+
+    #[test]
+    fn test_record_rejects_mismatched_fields() {
+        let inputs = adder4_inputs();
+        // 出力5ビットを4ビットのトークンに収められない（carryを捨てない）
+        assert_eq!(
+            record(adder4, &inputs, &[4, 4], &[4]),
+            Err(GoldenError::FieldWidth { expected: 4, actual: 5 })
+        );
+        // 入力8ビットに対してトークンの幅が広すぎる
+        assert_eq!(
+            record(adder4, &inputs, &[4, 8], &[4, 1]),
+            Err(GoldenError::FieldWidth { expected: 12, actual: 8 })
+        );
+    }
+}
@@ -1,3 +1,11 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+// ゲート・加算器・固定幅（const generics）の回路はフィーチャーなしでも使える
+// Vec を返すスライス版の回路は `alloc` フィーチャー（`std` に含まれる）が必要
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 /// 2入力 NAND ゲート
 ///
 /// *真理値表*  
@@ -28,6 +36,18 @@ pub fn or(a: bool, b: bool) -> bool {
     nand(na, nb)
 }
 
+/// NOR  ￢(A + B) = NOT(OR(A, B))
+///
+/// *真理値表*  
+/// A | B | NOR  
+/// 0 | 0 | 1  
+/// 0 | 1 | 0  
+/// 1 | 0 | 0  
+/// 1 | 1 | 0
+pub fn nor(a: bool, b: bool) -> bool {
+    not(or(a, b))
+}
+
 /// XOR  (A ⊕ B) = NAND(NAND(A, B), NAND(A, NAND(A, B)), NAND(B, NAND(A, B)))
 pub fn xor(a: bool, b: bool) -> bool {
     let t1 = nand(a, b);
@@ -36,6 +56,70 @@ pub fn xor(a: bool, b: bool) -> bool {
     nand(t2, t3)
 }
 
+/// XNOR  ￢(A ⊕ B) = NOT(XOR(A, B))
+///
+/// 2つの入力が等しいときにのみ1（一致検出）
+///
+/// *真理値表*  
+/// A | B | XNOR  
+/// 0 | 0 | 1  
+/// 0 | 1 | 0  
+/// 1 | 0 | 0  
+/// 1 | 1 | 1
+pub fn xnor(a: bool, b: bool) -> bool {
+    not(xor(a, b))
+}
+
+/// 3入力 AND  (A · B · C) = AND(AND(A, B), C)
+pub fn and3(a: bool, b: bool, c: bool) -> bool {
+    and(and(a, b), c)
+}
+
+/// 3入力 NAND  ￢(A · B · C) = NAND(AND(A, B), C)
+pub fn nand3(a: bool, b: bool, c: bool) -> bool {
+    nand(and(a, b), c)
+}
+
+/// 3入力 OR  (A + B + C) = OR(OR(A, B), C)
+pub fn or3(a: bool, b: bool, c: bool) -> bool {
+    or(or(a, b), c)
+}
+
+/// 3入力 XOR  (A ⊕ B ⊕ C) = XOR(XOR(A, B), C)
+///
+/// 1の入力が奇数個のときに1（全加算器の和と同じ）
+pub fn xor3(a: bool, b: bool, c: bool) -> bool {
+    xor(xor(a, b), c)
+}
+
+/// AOI21  ￢(A · B + C) = AND(NAND(A, B), NOT C)
+///
+/// NAND 4個（AND・OR・NOTをそのまま組み合わせると6個）
+pub fn aoi21(a: bool, b: bool, c: bool) -> bool {
+    and(nand(a, b), not(c))
+}
+
+/// AOI22  ￢(A · B + C · D) = AND(NAND(A, B), NAND(C, D))
+///
+/// NAND 4個（AND・OR・NOTをそのまま組み合わせると8個）
+pub fn aoi22(a: bool, b: bool, c: bool, d: bool) -> bool {
+    and(nand(a, b), nand(c, d))
+}
+
+/// OAI21  ￢((A + B) · C) = NAND(OR(A, B), C)
+///
+/// NAND 4個（OR・AND・NOTをそのまま組み合わせると6個）
+pub fn oai21(a: bool, b: bool, c: bool) -> bool {
+    nand(or(a, b), c)
+}
+
+/// OAI22  ￢((A + B) · (C + D)) = NAND(OR(A, B), OR(C, D))
+///
+/// NAND 7個（OR・AND・NOTをそのまま組み合わせると9個）
+pub fn oai22(a: bool, b: bool, c: bool, d: bool) -> bool {
+    nand(or(a, b), or(c, d))
+}
+
 /// 2:1 マルチプレクサ  
 /// sel が 0 なら a、1 なら b を出力
 pub fn mux(sel: bool, a: bool, b: bool) -> bool {
@@ -60,59 +144,190 @@ pub mod adder;
 // ALUモジュール
 pub mod alu;
 
-#[cfg(test)]
+// ゲートバックエンドモジュール
+pub mod backend;
+
+// BCD（2進化10進数）演算モジュール
+#[cfg(feature = "alloc")]
+pub mod bcd;
+
+// バス（nビット幅）ゲートモジュール
+pub mod bus;
+
+// キャッシュシミュレータモジュール
+#[cfg(feature = "alloc")]
+pub mod cache;
+
+// 部品の共通トレイト（ゲートレベルと整数演算の実装の差し替え）モジュール
+#[cfg(feature = "alloc")]
+pub mod chip;
+
+// 比較器モジュール
+#[cfg(feature = "alloc")]
+pub mod comparator;
+
+// 真理値表コントラクト検査モジュール
+#[cfg(feature = "alloc")]
+pub mod contract;
+
+// CPU制御ロジックモジュール
+#[cfg(feature = "alloc")]
+pub mod control;
+
+// 整数とビット列の変換モジュール
+#[cfg(feature = "alloc")]
+pub mod convert;
+
+// クロスバスイッチモジュール
+#[cfg(feature = "alloc")]
+pub mod crossbar;
+
+// デコーダ・エンコーダモジュール
+#[cfg(feature = "alloc")]
+pub mod decoder;
+
+// 7セグメント表示モジュール
+#[cfg(feature = "alloc")]
+pub mod display;
+
+// 除算器モジュール
+#[cfg(feature = "alloc")]
+pub mod divider;
+
+// 誤り検出・訂正付きメモリモジュール
+#[cfg(feature = "alloc")]
+pub mod ecc;
+
+// ビットパターン検出モジュール
+#[cfg(feature = "alloc")]
+pub mod detect;
+
+// 2進GCD（Stein のアルゴリズム）モジュール
+#[cfg(feature = "alloc")]
+pub mod gcd;
+
+// ゴールデンベクタ比較モジュール
+#[cfg(feature = "alloc")]
+pub mod golden;
+
+// NOR基底のゲートモジュール
+pub mod nor_basis;
+
+// プログラムファイル形式モジュール
+#[cfg(feature = "alloc")]
+pub mod program;
+
+// serde 用のビット列変換モジュール
+#[cfg(feature = "serde")]
+pub mod serde_bits;
+
+// テストベンチ生成モジュール
+#[cfg(feature = "alloc")]
+pub mod testbench;
+
+// シフタモジュール
+#[cfg(feature = "alloc")]
+pub mod shifter;
+
+// 順序回路モジュール
+pub mod sequential;
+
+// マスク・ビットフィールドモジュール
+#[cfg(feature = "alloc")]
+pub mod mask;
+
+// bits! マクロモジュール
+#[doc(hidden)]
+pub mod macros;
+
+// 多数決回路モジュール
+#[cfg(feature = "alloc")]
+pub mod majority;
+
+// 乗算器モジュール
+pub mod multiplier;
+
+// 16ビットワード型モジュール
+#[cfg(feature = "alloc")]
+pub mod word;
+
+// 配線（ビットの並べ替え）モジュール
+pub mod wiring;
+
+#[cfg(all(test, feature = "alloc"))]
+mod testutil;
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
+    use contract::*;
 
     #[test]
     fn nand_gate() {
-        assert_eq!(nand(false, false), true);
-        assert_eq!(nand(false, true),  true);
-        assert_eq!(nand(true,  false), true);
-        assert_eq!(nand(true,  true),  false);
+        assert_eq!(check_contract2(nand, &NAND_TABLE), Ok(()));
     }
 
     #[test]
     fn not_gate() {
-        assert_eq!(not(false), true);
-        assert_eq!(not(true),  false);
+        assert_eq!(check_contract1(not, &NOT_TABLE), Ok(()));
     }
 
     #[test]
     fn and_gate() {
-        assert_eq!(and(false, false), false);
-        assert_eq!(and(false, true),  false);
-        assert_eq!(and(true,  false), false);
-        assert_eq!(and(true,  true),  true);
+        assert_eq!(check_contract2(and, &AND_TABLE), Ok(()));
     }
 
     #[test]
     fn or_gate() {
-        assert_eq!(or(false, false), false);
-        assert_eq!(or(false, true),  true);
-        assert_eq!(or(true,  false), true);
-        assert_eq!(or(true,  true),  true);
+        assert_eq!(check_contract2(or, &OR_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn nor_gate() {
+        assert_eq!(check_contract2(nor, &NOR_TABLE), Ok(()));
     }
 
     #[test]
     fn xor_gate() {
-        assert_eq!(xor(false, false), false);
-        assert_eq!(xor(false, true),  true);
-        assert_eq!(xor(true,  false), true);
-        assert_eq!(xor(true,  true),  false);
+        assert_eq!(check_contract2(xor, &XOR_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn xnor_gate() {
+        assert_eq!(check_contract2(xnor, &XNOR_TABLE), Ok(()));
     }
 
     #[test]
     fn mux_gate() {
-        assert_eq!(mux(false, false, false), false);
-        assert_eq!(mux(false, false, true),  false);
+        assert_eq!(check_contract3(mux, &MUX_TABLE), Ok(()));
     }
 
     #[test]
     fn demux_gate() {
-        assert_eq!(demux(false, false), (false, false));
-        assert_eq!(demux(false, true),  (true, false));
-        assert_eq!(demux(true,  false), (false, false));
-        assert_eq!(demux(true,  true),  (false, true));
+        assert_eq!(check_contract_demux(demux, &DEMUX_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn three_input_gates() {
+        for v in 0..8u8 {
+            let (a, b, c) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+            assert_eq!(and3(a, b, c), a && b && c, "inputs = {:03b}", v);
+            assert_eq!(nand3(a, b, c), !(a && b && c), "inputs = {:03b}", v);
+            assert_eq!(or3(a, b, c), a || b || c, "inputs = {:03b}", v);
+            assert_eq!(xor3(a, b, c), v.count_ones() % 2 == 1, "inputs = {:03b}", v);
+        }
+    }
+
+    #[test]
+    fn aoi_oai_gates() {
+        for v in 0..16u8 {
+            let (a, b, c, d) = (v & 1 != 0, v & 2 != 0, v & 4 != 0, v & 8 != 0);
+            assert_eq!(aoi22(a, b, c, d), !((a && b) || (c && d)), "inputs = {:04b}", v);
+            assert_eq!(oai22(a, b, c, d), !((a || b) && (c || d)), "inputs = {:04b}", v);
+            if v < 8 {
+                assert_eq!(aoi21(a, b, c), !((a && b) || c), "inputs = {:03b}", v);
+                assert_eq!(oai21(a, b, c), !((a || b) && c), "inputs = {:03b}", v);
+            }
+        }
     }
 }
@@ -0,0 +1,193 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// ビット列（LSB→MSB順）を読みやすく書くためのマクロ
+///
+/// - `bits![1, 0, 0, 1]` - 並べた順をLSB→MSB順とみなし、`[bool; 4]` にする
+/// - `bits![msb: "1001_0110"]` - 人が書く順（左端がMSB）の2進数の文字列リテラルを `[bool; 8]` にする
+///   （`0b` 接頭辞と `_` の区切りを使える）
+/// - `bits![u16: 0x4A3C]` - 16ビットの値を `[bool; 16]` にする
+///
+/// 文字列リテラルの代わりに実行時の文字列（`&str` の式）を渡した場合は、
+/// 幅がコンパイル時に決まらないので `Vec<bool>` を返す（`alloc` フィーチャーが必要）
+///
+/// ```
+/// use logic_gates::bits;
+///
+/// assert_eq!(bits![1, 1, 0, 0], [true, true, false, false]);
+/// assert_eq!(bits![msb: "0011"], [true, true, false, false]);
+/// assert_eq!(bits![u16: 0x0003][..4], [true, true, false, false]);
+/// ```
+///
+/// リテラルの中の不正な数字はコンパイルエラーになる
+///
+/// ```compile_fail
+/// let _ = logic_gates::bits![msb: "10x1"];
+/// ```
+///
+/// ```compile_fail
+/// let _ = logic_gates::bits![1, 0, 2];
+/// ```
+#[macro_export]
+macro_rules! bits {
+    () => {
+        [false; 0]
+    };
+    (msb: $s:literal) => {{
+        const WIDTH: usize = $crate::macros::msb_width($s);
+        const BITS: [bool; WIDTH] = $crate::macros::msb_array::<WIDTH>($s);
+        BITS
+    }};
+    (msb: $s:expr) => {
+        $crate::macros::msb_vec($s)
+    };
+    (u16: $v:expr) => {
+        $crate::macros::u16_array($v)
+    };
+    ($($b:literal),+ $(,)?) => {
+        [$({
+            const BIT: bool = $crate::macros::digit($b);
+            BIT
+        }),+]
+    };
+}
+
+/// `bits![1, 0, ...]` の1桁を bool にする
+#[doc(hidden)]
+pub const fn digit(d: u8) -> bool {
+    match d {
+        0 => false,
+        1 => true,
+        _ => panic!("bits!: each digit must be 0 or 1"),
+    }
+}
+
+/// 接頭辞 `0b` を除いた数字部分
+const fn msb_digits(s: &str) -> &[u8] {
+    match s.as_bytes() {
+        [b'0', b'b', rest @ ..] => rest,
+        bytes => bytes,
+    }
+}
+
+/// 2進数の文字列の幅（`_` を除いた数字の個数）
+///
+/// 0と1と `_` 以外の文字があればパニックする（const 文脈ではコンパイルエラーになる）
+#[doc(hidden)]
+pub const fn msb_width(s: &str) -> usize {
+    let digits = msb_digits(s);
+    let mut width = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        match digits[i] {
+            b'0' | b'1' => width += 1,
+            b'_' => {}
+            _ => panic!("bits!: binary string may only contain '0', '1' and '_'"),
+        }
+        i += 1;
+    }
+    width
+}
+
+/// 2進数の文字列（左端がMSB）を幅Nの配列（LSB→MSB順）にする
+#[doc(hidden)]
+pub const fn msb_array<const N: usize>(s: &str) -> [bool; N] {
+    let digits = msb_digits(s);
+    let mut out = [false; N];
+    let mut bit = 0;
+    let mut i = digits.len();
+    while i > 0 {
+        i -= 1;
+        match digits[i] {
+            b'0' | b'1' => {
+                assert!(bit < N, "bits!: binary string is wider than the array");
+                out[bit] = digits[i] == b'1';
+                bit += 1;
+            }
+            b'_' => {}
+            _ => panic!("bits!: binary string may only contain '0', '1' and '_'"),
+        }
+    }
+    out
+}
+
+/// 実行時の2進数の文字列（左端がMSB）をビット列（LSB→MSB順）にする
+///
+/// 不正な文字があれば、その位置と文字列を示してパニックする
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub fn msb_vec(s: &str) -> Vec<bool> {
+    let digits = s.strip_prefix("0b").unwrap_or(s);
+    let mut out = Vec::with_capacity(digits.len());
+    for (position, ch) in digits.chars().enumerate() {
+        match ch {
+            '0' => out.push(false),
+            '1' => out.push(true),
+            '_' => {}
+            _ => panic!("bits!: invalid digit {:?} at position {} in {:?}", ch, position, s),
+        }
+    }
+    out.reverse();
+    out
+}
+
+/// 16ビットの値を [bool; 16]（LSB→MSB順）にする
+#[doc(hidden)]
+pub const fn u16_array(v: u16) -> [bool; 16] {
+    let mut out = [false; 16];
+    let mut i = 0;
+    while i < 16 {
+        out[i] = (v >> i) & 1 == 1;
+        i += 1;
+    }
+    out
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::convert::{bits_from_u16, parse_bits};
+
+    #[test]
+    fn test_bits_list_is_lsb_first() {
+        let b: [bool; 4] = bits![1, 0, 0, 1];
+        assert_eq!(b, [true, false, false, true]);
+        assert_eq!(bits![0, 1, 1,], [false, true, true]);
+        assert_eq!(bits![1], [true]);
+        assert_eq!(bits![], [false; 0]);
+    }
+
+    #[test]
+    fn test_bits_msb_literal() {
+        let b: [bool; 8] = bits![msb: "1001_0110"];
+        assert_eq!(b.to_vec(), parse_bits("1001_0110").unwrap());
+        assert_eq!(bits![msb: "0b01"], [true, false]);
+        assert_eq!(bits![msb: ""], [false; 0]);
+
+        // const 文脈でも使える
+        const WORD: [bool; 4] = bits![msb: "1100"];
+        assert_eq!(WORD, [false, false, true, true]);
+    }
+
+    #[test]
+    fn test_bits_msb_runtime_string() {
+        let s = String::from("10_01");
+        let b: Vec<bool> = bits![msb: s.as_str()];
+        assert_eq!(b, vec![true, false, false, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bits!: invalid digit '2' at position 2 in \"1020\"")]
+    fn test_bits_msb_runtime_string_malformed() {
+        let s = "1020";
+        let _ = bits![msb: s];
+    }
+
+    #[test]
+    fn test_bits_u16() {
+        for v in [0x0000u16, 0x0001, 0x4A3C, 0x8000, 0xFFFF] {
+            assert_eq!(bits![u16: v], bits_from_u16(v));
+        }
+        let b: [bool; 16] = bits![u16: 0x4A3C];
+        assert_eq!(b, bits![msb: "0100_1010_0011_1100"]);
+    }
+}
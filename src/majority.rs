@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+use crate::{and, not, or};
+use crate::adder::{n_bit_subtractor, popcount};
+
+/// 3入力多数決ゲート
+///
+/// 3つの入力のうち2つ以上が1なら1を出力する（三重化冗長 TMR の投票回路）
+///
+/// MAJ(A, B, C) = A·B + B·C + C·A
+pub fn maj3(a: bool, b: bool, c: bool) -> bool {
+    or(or(and(a, b), and(b, c)), and(c, a))
+}
+
+/// n入力多数決
+///
+/// 1の入力が半数を「超える」ときにtrueを返す
+///
+/// - 入力数が偶数で、ちょうど半数が1の場合（同数）はfalse
+/// - 空のスライスはfalse
+///
+/// 1の個数を加算器で数え、閾値 floor(n/2)+1 との減算で借りが出ないかを調べる
+pub fn majority(inputs: &[bool]) -> bool {
+    let count = popcount(inputs);
+
+    // 閾値は入力数だけで決まる定数なので、配線で与える
+    let threshold = inputs.len() / 2 + 1;
+    let threshold: Vec<bool> = (0..count.len()).map(|i| (threshold >> i) & 1 == 1).collect();
+
+    let (_difference, borrow) = n_bit_subtractor(&count, &threshold);
+    not(borrow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maj3() {
+        for v in 0..8u8 {
+            let (a, b, c) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+            assert_eq!(maj3(a, b, c), v.count_ones() >= 2, "inputs = {:03b}", v);
+            assert_eq!(majority(&[a, b, c]), maj3(a, b, c), "inputs = {:03b}", v);
+        }
+    }
+
+    #[test]
+    fn test_majority_five_inputs() {
+        assert_eq!(majority(&[true, true, true, false, false]), true);
+        assert_eq!(majority(&[false, true, false, true, false]), false);
+        assert_eq!(majority(&[true; 5]), true);
+        assert_eq!(majority(&[false, true, true, true, true]), true);
+    }
+
+    #[test]
+    fn test_majority_six_inputs_tie() {
+        // ちょうど半数は過半数ではない
+        assert_eq!(majority(&[true, true, true, false, false, false]), false);
+        assert_eq!(majority(&[false, true, false, true, false, true]), false);
+        assert_eq!(majority(&[true, true, true, true, false, false]), true);
+    }
+
+    #[test]
+    fn test_majority_edge_cases() {
+        assert_eq!(majority(&[]), false);
+        assert_eq!(majority(&[true]), true);
+        assert_eq!(majority(&[false]), false);
+        assert_eq!(majority(&[true, false]), false);
+        assert_eq!(majority(&[true, true]), true);
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::vec::Vec;
+
+use crate::adder::n_bit_subtractor;
+use crate::bus::{and_n, not_n, or_n};
+
+/// 定数 value を width ビットの2進数の配線（LSB→MSB順）にする
+fn constant(value: usize, width: usize) -> Vec<bool> {
+    (0..width).map(|i| i < usize::BITS as usize && (value >> i) & 1 == 1).collect()
+}
+
+/// 下位 k ビットが1のマスク
+///
+/// * `k` - 1にするビット数
+/// * `n` - マスクの幅
+///
+/// 戻り値はnビット（LSB→MSB順）で、ビット 0..k が1、それ以外が0
+/// k >= n ならすべて1、k = 0 ならすべて0
+pub fn make_mask(k: usize, n: usize) -> Vec<bool> {
+    let width = (usize::BITS - k.leading_zeros()) as usize;
+    make_mask_from_bits(&constant(k, width), n)
+}
+
+/// 下位 k ビットが1のマスク（kを2進数のバスで与える版）
+///
+/// 温度計デコーダ: 出力ビットiごとに比較器を置き、i < k のときに1にする
+/// （比較は定数 i から k を引いたときの借りで判定する）
+///
+/// * `k_bits` - 1にするビット数（LSB→MSB順の2進数）
+/// * `n` - マスクの幅
+pub fn make_mask_from_bits(k_bits: &[bool], n: usize) -> Vec<bool> {
+    let index_width = (usize::BITS - n.leading_zeros()) as usize;
+    let width = k_bits.len().max(index_width);
+    (0..n)
+        .map(|i| {
+            let (_difference, borrow) = n_bit_subtractor(&constant(i, width), k_bits);
+            borrow
+        })
+        .collect()
+}
+
+/// 16ビットのマスク
+///
+/// make_mask の16ビット固定幅版
+pub fn make_mask16(k: usize) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(make_mask(k, 16)) {
+        *o = bit;
+    }
+    out
+}
+
+/// 定数シフト（配線だけで、ゲートは使わない）
+///
+/// ビット i を i + distance（distance が負なら右）へ移し、あふれたビットは捨て、空いたビットは0にする
+fn rewire_shift(a: &[bool], distance: isize) -> Vec<bool> {
+    (0..a.len() as isize)
+        .map(|i| {
+            let source = i - distance;
+            source >= 0 && (source as usize) < a.len() && a[source as usize]
+        })
+        .collect()
+}
+
+/// ビットフィールドの取り出し
+///
+/// word のビット offset から width ビットを取り出す
+/// （定数シフトで右に offset ずらし、make_mask の width ビットのマスクと AND をとる）
+///
+/// * `word` - 入力ワード（LSB→MSB順）
+/// * `offset` - フィールドの最下位ビットの位置
+/// * `width` - フィールドの幅
+///
+/// フィールドが word の MSB をはみ出す場合は、はみ出した分を切り捨てる
+/// （出力の長さは width と word.len() - offset の小さい方、offset が範囲外なら空）
+pub fn extract_field(word: &[bool], offset: usize, width: usize) -> Vec<bool> {
+    let n = word.len();
+    let shifted = rewire_shift(word, -(offset.min(n) as isize));
+    let mut field = and_n(&shifted, &make_mask(width, n));
+    field.truncate(width.min(n.saturating_sub(offset)));
+    field
+}
+
+/// ビットフィールドの書き込み
+///
+/// word のビット offset から field.len() ビットを field で置き換える
+/// （フィールド位置のマスク m を作り、(word AND NOT m) OR (field << offset AND m) を計算する）
+///
+/// * `word` - 入力ワード（LSB→MSB順）
+/// * `field` - 書き込むフィールド（LSB→MSB順）
+/// * `offset` - フィールドの最下位ビットの位置
+///
+/// 出力の長さは word と同じ。word の MSB をはみ出すフィールドのビットは捨てる
+pub fn insert_field(word: &[bool], field: &[bool], offset: usize) -> Vec<bool> {
+    let n = word.len();
+
+    let mut placed = field.to_vec();
+    placed.resize(n, false);
+    let placed = rewire_shift(&placed, offset.min(n) as isize);
+
+    let upper = make_mask(offset.saturating_add(field.len()), n);
+    let lower = make_mask(offset, n);
+    let m = and_n(&upper, &not_n(&lower));
+
+    or_n(&and_n(word, &not_n(&m)), &and_n(&placed, &m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u16, bits_from_u64_n, u64_from_bits_slice};
+
+    #[test]
+    fn test_make_mask16() {
+        for k in 0..=17usize {
+            let expected = ((1u32 << k.min(16)) - 1) as u16;
+            assert_eq!(make_mask16(k), bits_from_u16(expected), "k = {}", k);
+            assert_eq!(make_mask(k, 16), bits_from_u16(expected).to_vec(), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn test_make_mask_from_bits() {
+        // kの値がマスクの幅を超える・kのバスが幅より狭い、のどちらでも正しく比較する
+        for k in 0..40u64 {
+            let expected = bits_from_u64_n((1u64 << k.min(20)) - 1, 20);
+            assert_eq!(make_mask_from_bits(&bits_from_u64_n(k, 6), 20), expected, "k = {}", k);
+        }
+        assert_eq!(make_mask_from_bits(&[true], 4), vec![true, false, false, false]);
+        assert_eq!(make_mask_from_bits(&[], 3), vec![false; 3]);
+        assert_eq!(make_mask(3, 0), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_extract_field() {
+        let word = bits_from_u64_n(0xBEEF, 16);
+        assert_eq!(u64_from_bits_slice(&extract_field(&word, 0, 4)), 0xF);
+        assert_eq!(u64_from_bits_slice(&extract_field(&word, 4, 8)), 0xEE);
+        assert_eq!(u64_from_bits_slice(&extract_field(&word, 12, 4)), 0xB);
+
+        // MSBをはみ出す分は切り捨てる
+        assert_eq!(extract_field(&word, 12, 8), bits_from_u64_n(0xB, 4));
+        assert_eq!(extract_field(&word, 16, 4), Vec::<bool>::new());
+        assert_eq!(extract_field(&word, 20, 4), Vec::<bool>::new());
+        assert_eq!(extract_field(&word, 3, 0), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_insert_field() {
+        let word = bits_from_u64_n(0xBEEF, 16);
+        assert_eq!(u64_from_bits_slice(&insert_field(&word, &bits_from_u64_n(0x0, 4), 0)), 0xBEE0);
+        assert_eq!(u64_from_bits_slice(&insert_field(&word, &bits_from_u64_n(0x12, 8), 4)), 0xB12F);
+        assert_eq!(u64_from_bits_slice(&insert_field(&word, &bits_from_u64_n(0x5, 4), 12)), 0x5EEF);
+
+        // MSBをはみ出すビットは捨てる。幅0のフィールドでは何も変わらない
+        assert_eq!(u64_from_bits_slice(&insert_field(&word, &bits_from_u64_n(0xA5, 8), 12)), 0x5EEF);
+        assert_eq!(insert_field(&word, &bits_from_u64_n(0xF, 4), 16), word);
+        assert_eq!(insert_field(&word, &[], 5), word);
+    }
+
+    #[test]
+    fn test_extract_then_insert_is_identity() {
+        let word = bits_from_u64_n(0x5A3C, 16);
+        for offset in 0..=16 {
+            for width in 0..=(16 - offset) {
+                let field = extract_field(&word, offset, width);
+                assert_eq!(field.len(), width);
+                assert_eq!(insert_field(&word, &field, offset), word, "offset = {}, width = {}", offset, width);
+            }
+        }
+    }
+}
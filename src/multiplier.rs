@@ -0,0 +1,814 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::adder::full_adder;
+#[cfg(feature = "alloc")]
+use crate::adder::{half_adder, n_bit_adder, n_bit_adder_full, n_bit_adder_with_carry, n_bit_subtractor};
+#[cfg(feature = "alloc")]
+use crate::bus::or_reduce;
+#[cfg(feature = "alloc")]
+use crate::shifter::{shift_left, shift_right_arithmetic};
+#[cfg(feature = "alloc")]
+use crate::wiring::{sign_extend, truncate_checked, zero_extend};
+#[cfg(feature = "alloc")]
+use crate::{and, not, or, xor};
+
+/// 4:2 コンプレッサ
+///
+/// 2つの全加算器を連結し、4つの入力ビットと前の桁からの桁上げ（cin）を
+/// 1つの和と2つの桁上げに圧縮する（Wallace木などの乗算器で使われる基本セル）
+///
+/// * `a`, `b`, `c`, `d` - 同じ桁の入力ビット
+/// * `cin` - 前の桁のコンプレッサからの cout
+///
+/// 戻り値は (sum, carry, cout) のタプル
+/// - sum + 2 × (carry + cout) は、trueの入力の個数に等しい
+/// - cout は cin を使わずに決まるので、次の桁の cin へそのまま配線できる
+pub fn compressor_4_2(a: bool, b: bool, c: bool, d: bool, cin: bool) -> (bool, bool, bool) {
+    let (s1, cout) = full_adder(a, b, c);
+    let (sum, carry) = full_adder(s1, d, cin);
+    (sum, carry, cout)
+}
+
+/// 4行の部分積に4:2コンプレッサを並べて適用する
+///
+/// 各桁にコンプレッサを置き、cout を1つ上の桁の cin へ連結する
+///
+/// * `a`, `b`, `c`, `d` - 4つのnビット行（LSB→MSB順、短い行は0で埋める）
+///
+/// 戻り値は (sum, carry) のタプル
+/// - どちらも n+1 ビット（LSB→MSB順）で、同じ桁の重みにそろえてある
+/// - sum + carry が4行の合計に等しい（最後に1回の加算で解決する）
+#[cfg(feature = "alloc")]
+pub fn compress_4_2_row(a: &[bool], b: &[bool], c: &[bool], d: &[bool]) -> (Vec<bool>, Vec<bool>) {
+    let n = a.len().max(b.len()).max(c.len()).max(d.len());
+    let bit = |row: &[bool], i: usize| if i < row.len() { row[i] } else { false };
+
+    let mut sum = Vec::with_capacity(n + 1);
+    let mut carry = Vec::with_capacity(n + 1);
+    carry.push(false);
+
+    let mut cin = false;
+    for i in 0..n {
+        let (s, cy, cout) = compressor_4_2(bit(a, i), bit(b, i), bit(c, i), bit(d, i), cin);
+        sum.push(s);
+        carry.push(cy);
+        cin = cout;
+    }
+    sum.push(cin);
+
+    (sum, carry)
+}
+
+/// 符号なし乗算器（シフト加算方式）
+///
+/// 乗数 b のビット i ごとに、被乗数 a を i ビット左にずらした部分積を累積値に加える
+/// 部分積の各ビットは AND(a[j], b[i]) で、b[i] が0なら部分積は0になる
+/// 左シフトは shifter::shift_left、加算はすべて n_bit_adder で行う
+///
+/// * `a` - 被乗数（LSB→MSB順）
+/// * `b` - 乗数（LSB→MSB順）
+///
+/// 戻り値は a.len() + b.len() ビットの積（LSB→MSB順）。この幅なら積が切り捨てられることはない
+#[cfg(feature = "alloc")]
+pub fn multiply_unsigned(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let width = a.len() + b.len();
+    let mut product = vec![false; width];
+    for (i, &multiplier_bit) in b.iter().enumerate() {
+        let gated: Vec<bool> = a.iter().map(|&bit| and(bit, multiplier_bit)).collect();
+        let (partial, _lost) = shift_left(&zero_extend(&gated, width), i);
+        // 幅 a.len() + b.len() では桁上げは出ない
+        let (sum, _carry) = n_bit_adder(&product, &partial);
+        product = sum;
+    }
+    product
+}
+
+/// 配列乗算器（組み合わせ回路）
+///
+/// 部分積 AND(a[j], b[i]) をすべて並べ、半加算器・全加算器のセルを格子状に並べて足し合わせる
+/// 格子の行 i（i >= 1）は、部分積の行 i と、前の行の出力の上位側を加える a.len() 個のセルで、
+/// 先頭のセルは半加算器、残りは前のセルの桁上げを受け取る全加算器。各行の最下位の出力が積のビット i になる
+///
+/// * `a` - 被乗数（LSB→MSB順）
+/// * `b` - 乗数（LSB→MSB順）
+///
+/// 戻り値は a.len() + b.len() ビットの積（LSB→MSB順）で、multiply_unsigned と同じ値になる
+#[cfg(feature = "alloc")]
+pub fn array_multiplier(a: &[bool], b: &[bool]) -> Vec<bool> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return vec![false; n + m];
+    }
+
+    let mut product = Vec::with_capacity(n + m);
+
+    // 行0は部分積そのもの
+    let row: Vec<bool> = a.iter().map(|&bit| and(bit, b[0])).collect();
+    product.push(row[0]);
+    // 次の行へ渡す上位側（重み 1..n の n ビット）
+    let mut upper: Vec<bool> = row[1..].to_vec();
+    upper.push(false);
+
+    for &multiplier_bit in &b[1..] {
+        let mut row = Vec::with_capacity(n);
+        let (first, mut carry) = half_adder(and(a[0], multiplier_bit), upper[0]);
+        row.push(first);
+        for j in 1..n {
+            let (sum, carry_out) = full_adder(and(a[j], multiplier_bit), upper[j], carry);
+            row.push(sum);
+            carry = carry_out;
+        }
+        product.push(row[0]);
+        upper = row[1..].to_vec();
+        upper.push(carry);
+    }
+
+    // 最後の行の上位側が積の上位 n ビット
+    product.extend(upper);
+    product
+}
+
+/// Wallace 木乗算の結果
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WallaceResult {
+    /// 積（a.len() + b.len() ビット、LSB→MSB順）
+    pub product: Vec<bool>,
+    /// 桁上げ保存加算による縮約の段数（最後の桁上げ伝搬加算は含まない）
+    pub layers: usize,
+}
+
+/// Wallace 木乗算器
+///
+/// 部分積を重みごとの列に並べ、各列のビットを3つずつ全加算器（3:2 コンプレッサ）に、
+/// 余った2つを半加算器に通す段を、どの列も2ビット以下になるまで重ねる
+/// 和は同じ列に、桁上げは1つ上の列に送る。最後に残った2行を n_bit_adder で1回だけ加算する
+///
+/// * `a` - 被乗数（LSB→MSB順）
+/// * `b` - 乗数（LSB→MSB順）
+///
+/// 幅が異なる入力も扱える。戻り値は a.len() + b.len() ビットの積（LSB→MSB順）
+#[cfg(feature = "alloc")]
+pub fn wallace_multiplier(a: &[bool], b: &[bool]) -> Vec<bool> {
+    wallace_multiplier_detailed(a, b).product
+}
+
+/// Wallace 木乗算器（縮約の段数を返す版）
+///
+/// 各段で列の高さはおよそ 2/3 になるので、段数は部分積の行数の対数に比例する
+#[cfg(feature = "alloc")]
+pub fn wallace_multiplier_detailed(a: &[bool], b: &[bool]) -> WallaceResult {
+    let width = a.len() + b.len();
+
+    let mut columns: Vec<Vec<bool>> = vec![Vec::new(); width];
+    for (i, &multiplier_bit) in b.iter().enumerate() {
+        for (j, &bit) in a.iter().enumerate() {
+            columns[i + j].push(and(bit, multiplier_bit));
+        }
+    }
+
+    let mut layers = 0;
+    while columns.iter().any(|column| column.len() > 2) {
+        let mut next: Vec<Vec<bool>> = vec![Vec::new(); width];
+        for (weight, column) in columns.iter().enumerate() {
+            let mut bits = column.chunks_exact(3);
+            let mut carries = Vec::new();
+            for group in &mut bits {
+                let (sum, carry) = full_adder(group[0], group[1], group[2]);
+                next[weight].push(sum);
+                carries.push(carry);
+            }
+            match bits.remainder() {
+                [x, y] => {
+                    let (sum, carry) = half_adder(*x, *y);
+                    next[weight].push(sum);
+                    carries.push(carry);
+                }
+                rest => next[weight].extend_from_slice(rest),
+            }
+            // 最上位の列からの桁上げは積の幅を超える（値は必ず0）ので捨てる
+            if weight + 1 < width {
+                next[weight + 1].extend(carries);
+            }
+        }
+        columns = next;
+        layers += 1;
+    }
+
+    let row = |k: usize| -> Vec<bool> { columns.iter().map(|column| column.get(k).copied().unwrap_or(false)).collect() };
+    let (product, _carry) = n_bit_adder(&row(0), &row(1));
+    WallaceResult { product, layers }
+}
+
+/// Booth 乗算の1ステップで行う操作
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoothOp {
+    /// 何もしない（ビットの組が 00 または 11）
+    None,
+    /// 被乗数を加える（ビットの組が 01。1の並びの終わり）
+    Add,
+    /// 被乗数を引く（ビットの組が 10。1の並びの始まり）
+    Subtract,
+}
+
+/// Booth 乗算の1ステップの記録
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoothStep {
+    /// 乗数のビットの組 (b[i], b[i-1]) から決めた操作
+    pub op: BoothOp,
+    /// このステップを終えた後の部分積（積と同じ幅、LSB→MSB順）
+    pub partial: Vec<bool>,
+}
+
+/// Booth 乗算の結果
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoothResult {
+    /// 積（a.len() + b.len() ビット、LSB→MSB順）
+    pub product: Vec<bool>,
+    /// 乗数のビットごとのステップ（steps[i] が b[i] のステップ）
+    pub steps: Vec<BoothStep>,
+}
+
+/// 符号付き乗算器（radix-2 Booth 法）
+///
+/// 2の補数の a × b を計算する。乗数 b のビットの組 (b[i], b[i-1])（b[-1] = 0）ごとに、
+/// 10 なら a を i ビット左にずらした値を引き、01 なら加え、00 と 11 なら何もしない
+///
+/// * `a` - 被乗数（2の補数、LSB→MSB順）
+/// * `b` - 乗数（2の補数、LSB→MSB順）
+///
+/// 戻り値は a.len() + b.len() ビットの積（2の補数、LSB→MSB順）。最小値どうしの積も表せる
+#[cfg(feature = "alloc")]
+pub fn multiply_signed(a: &[bool], b: &[bool]) -> Vec<bool> {
+    multiply_signed_detailed(a, b).product
+}
+
+/// 符号付き乗算器（各ステップの部分積を記録する版）
+///
+/// multiply_signed と同じ回路を評価し、各ステップの操作と部分積もあわせて返す
+///
+/// 加算と減算は1つの加減算器で行う。減算のときは被乗数の各ビットを XOR で反転し、
+/// 桁上げ入力を1にする（a + NOT(x) + 1 = a - x）
+#[cfg(feature = "alloc")]
+pub fn multiply_signed_detailed(a: &[bool], b: &[bool]) -> BoothResult {
+    let width = a.len() + b.len();
+    let multiplicand = sign_extend(a, width);
+
+    let mut product = vec![false; width];
+    let mut steps = Vec::with_capacity(b.len());
+    let mut previous = false;
+    for (i, &current) in b.iter().enumerate() {
+        let subtract = and(current, not(previous));
+        let add = and(not(current), previous);
+        let enable = or(add, subtract);
+
+        // a を i ビット左にずらして（配線）、有効なら加算・減算の入力にする
+        let operand: Vec<bool> = (0..width)
+            .map(|k| {
+                let bit = k >= i && multiplicand[k - i];
+                xor(and(bit, enable), subtract)
+            })
+            .collect();
+        // 幅 a.len() + b.len() で回り込むので、桁上げは捨てる
+        let (sum, _carry) = n_bit_adder_with_carry(&product, &operand, subtract);
+        product = sum;
+
+        let op = match (add, subtract) {
+            (true, _) => BoothOp::Add,
+            (_, true) => BoothOp::Subtract,
+            _ => BoothOp::None,
+        };
+        steps.push(BoothStep { op, partial: product.clone() });
+        previous = current;
+    }
+    BoothResult { product, steps }
+}
+
+/// 積和演算器（MAC、符号なし）
+///
+/// acc + a × b を計算する。積は multiply_unsigned、加算は n_bit_adder で求める
+///
+/// * `acc` - 累積値（LSB→MSB順）
+/// * `a`, `b` - 掛ける2つの値（LSB→MSB順）
+///
+/// 積（a.len() + b.len() ビット）と acc は、広い方の幅に0で拡張してから加える
+///
+/// 戻り値は (result, overflow) のタプル
+/// - result: acc と同じ幅の結果（下位 acc.len() ビット）
+/// - overflow: 符号なしの値が acc の幅に収まらない
+#[cfg(feature = "alloc")]
+pub fn mac(acc: &[bool], a: &[bool], b: &[bool]) -> (Vec<bool>, bool) {
+    let product = multiply_unsigned(a, b);
+    let width = acc.len().max(product.len());
+
+    let (mut sum, carry) = n_bit_adder(&zero_extend(acc, width), &zero_extend(&product, width));
+    let overflow = or(carry, or_reduce(&sum[acc.len()..]));
+    sum.truncate(acc.len());
+    (sum, overflow)
+}
+
+/// 積和演算器（MAC、2の補数）
+///
+/// acc + a × b を2の補数で計算する。積は multiply_signed（Booth 法）、加算は n_bit_adder_full で求める
+///
+/// 積と acc は、広い方の幅に符号拡張してから加える
+///
+/// 戻り値は (result, overflow) のタプル
+/// - result: acc と同じ幅の結果（下位 acc.len() ビット）
+/// - overflow: 加算の符号付きオーバーフロー、または結果が acc の幅の2の補数に収まらない
+#[cfg(feature = "alloc")]
+pub fn mac_signed(acc: &[bool], a: &[bool], b: &[bool]) -> (Vec<bool>, bool) {
+    let product = multiply_signed(a, b);
+    let width = acc.len().max(product.len());
+
+    let r = n_bit_adder_full(&sign_extend(acc, width), &sign_extend(&product, width), false);
+    let (result, changed) = truncate_checked(&r.sum, acc.len());
+    (result, or(r.overflow, changed))
+}
+
+/// 固定小数点数の乗算の丸め方
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// 捨てるビットをそのまま捨てる（算術右シフトなので、負の数も −∞ 方向に丸める）
+    Truncate,
+    /// 最も近い値に丸め、ちょうど 0.5 ulp なら +∞ 方向に丸める（捨てる最上位ビットを加える）
+    RoundHalfUp,
+}
+
+/// 固定小数点数の乗算器（2の補数、Q形式）
+///
+/// 小数部 frac_bits ビットの2つの固定小数点数を multiply_signed で掛け、
+/// 積を shift_right_arithmetic で frac_bits ビット右にずらして元の Q 形式に戻す
+/// RoundHalfUp では、捨てるビットのうち最上位のもの（0.5 ulp の重み）を桁上げ入力として加算器で加える
+///
+/// * `a`, `b` - 固定小数点数（2の補数、LSB→MSB順）。幅が異なる場合は広い方に符号拡張する
+/// * `frac_bits` - 小数部のビット数
+/// * `rounding` - 丸め方
+///
+/// 戻り値は (result, overflow) のタプル
+/// - result: 入力（広い方）と同じ幅の積
+/// - overflow: 丸めた積がその幅の2の補数に収まらない
+#[cfg(feature = "alloc")]
+pub fn fixed_mul(a: &[bool], b: &[bool], frac_bits: usize, rounding: Rounding) -> (Vec<bool>, bool) {
+    let width = a.len().max(b.len());
+    let product = multiply_signed(&sign_extend(a, width), &sign_extend(b, width));
+    let frac_bits = frac_bits.min(product.len());
+
+    // 右シフトした積（丸めの加算であふれないよう、1ビット符号拡張しておく）
+    let shifted = shift_right_arithmetic(&sign_extend(&product, product.len() + 1), frac_bits);
+    let half = frac_bits > 0 && and(product[frac_bits - 1], rounding == Rounding::RoundHalfUp);
+    let (rounded, _carry) = n_bit_adder_with_carry(&shifted, &[], half);
+
+    truncate_checked(&rounded, width)
+}
+
+/// 定数の正準符号付き桁表現（CSD）
+///
+/// k を各桁が -1, 0, 1 の2進数で表す（LSB→MSB順）。0でない桁が隣り合わず、0でない桁の数が最小になる
+/// 例えば 15 = 16 - 1 は [-1, 0, 0, 0, 1]
+///
+/// 定数の分解は回路を組み立てる前に（コンパイル時に）行うものなので、ここでは整数演算で計算する
+#[cfg(feature = "alloc")]
+pub fn csd_digits(k: u64) -> Vec<i8> {
+    let mut k = k as u128;
+    let mut digits = Vec::new();
+    while k > 0 {
+        let digit = if k.is_multiple_of(2) {
+            0
+        } else if k % 4 == 1 {
+            1
+        } else {
+            -1
+        };
+        k = (k as i128 - digit as i128) as u128 / 2;
+        digits.push(digit);
+    }
+    digits
+}
+
+/// 定数乗算の結果
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstMultiplyResult {
+    /// 積（a.len() + k のビット長 ビット、LSB→MSB順）
+    pub product: Vec<bool>,
+    /// 回路に使った加算器の数
+    pub adders: usize,
+    /// 回路に使った減算器の数
+    pub subtractors: usize,
+}
+
+/// 定数乗算器（シフトと加減算の固定の回路）
+///
+/// 定数 k を csd_digits で分解し、0でない桁ごとに a をその桁の位置までずらした値（配線だけ）を、
+/// 桁が1なら加算器で加え、-1 なら減算器で引く。一般の乗算器の代わりに、定数ごとに決まった回路になる
+///
+/// * `a` - 被乗数（LSB→MSB順）
+/// * `k` - 定数の乗数
+///
+/// 戻り値は a.len() + (k のビット長) ビットの積で、multiply_unsigned(a, k の2進数) と同じ値
+/// k = 0 なら0のワード、k = 1 なら a のコピー（どちらも加算器を使わない）
+#[cfg(feature = "alloc")]
+pub fn multiply_by_const(a: &[bool], k: u64) -> Vec<bool> {
+    multiply_by_const_detailed(a, k).product
+}
+
+/// 定数乗算器（使った加算器・減算器の数を返す版）
+///
+/// 加算器と減算器の数の合計は、k の CSD 表現の0でない桁の数より1少ない
+/// （例えば k = 15 は 1 + 2 + 4 + 8 の加算器3つではなく、16 - 1 の減算器1つで済む）
+#[cfg(feature = "alloc")]
+pub fn multiply_by_const_detailed(a: &[bool], k: u64) -> ConstMultiplyResult {
+    let width = a.len() + (u64::BITS - k.leading_zeros()) as usize;
+    let shifted = |distance: usize| -> Vec<bool> {
+        (0..width).map(|i| i >= distance && i - distance < a.len() && a[i - distance]).collect()
+    };
+
+    let mut result = ConstMultiplyResult { product: vec![false; width], adders: 0, subtractors: 0 };
+    if k == 0 {
+        return result;
+    }
+
+    // 最上位の0でない桁は常に1なので、その位置までずらした a から始める
+    let digits = csd_digits(k);
+    let top = digits.len() - 1;
+    result.product = shifted(top);
+    for (position, &digit) in digits[..top].iter().enumerate().rev() {
+        match digit {
+            1 => {
+                result.product = n_bit_adder(&result.product, &shifted(position)).0;
+                result.adders += 1;
+            }
+            -1 => {
+                result.product = n_bit_subtractor(&result.product, &shifted(position)).0;
+                result.subtractors += 1;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::adder::n_bit_adder;
+    use crate::convert::{bits_from_u64_n, from_signed, to_signed, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_compressor_4_2_exhaustive() {
+        for v in 0..32u32 {
+            let input = |i: u32| (v >> i) & 1 == 1;
+            let (sum, carry, cout) = compressor_4_2(input(0), input(1), input(2), input(3), input(4));
+            let total = sum as u32 + 2 * (carry as u32 + cout as u32);
+            assert_eq!(total, v.count_ones(), "inputs = {:05b}", v);
+        }
+    }
+
+    #[test]
+    fn test_compress_4_2_row() {
+        let mut rng = XorShift::new(0x4242);
+        for _ in 0..100 {
+            let rows: Vec<u64> = (0..4).map(|_| rng.next_bits(16)).collect();
+            let (sum, carry) = compress_4_2_row(
+                &bits_from_u64_n(rows[0], 16),
+                &bits_from_u64_n(rows[1], 16),
+                &bits_from_u64_n(rows[2], 16),
+                &bits_from_u64_n(rows[3], 16),
+            );
+            assert_eq!(sum.len(), 17);
+            assert_eq!(carry.len(), 17);
+            let (resolved, resolved_carry) = n_bit_adder(&sum, &carry);
+
+            // 同じ4行を18ビットに広げて n_bit_adder を3回使って加算する
+            let (ab, _) = n_bit_adder(&bits_from_u64_n(rows[0], 18), &bits_from_u64_n(rows[1], 18));
+            let (abc, _) = n_bit_adder(&ab, &bits_from_u64_n(rows[2], 18));
+            let (abcd, _) = n_bit_adder(&abc, &bits_from_u64_n(rows[3], 18));
+
+            let compressed_total = u64_from_bits_slice(&resolved) + ((resolved_carry as u64) << 17);
+            assert_eq!(compressed_total, u64_from_bits_slice(&abcd));
+            assert_eq!(compressed_total, rows.iter().sum::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_multiply_unsigned_exhaustive_4x4() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let product = multiply_unsigned(&bits_from_u64_n(x, 4), &bits_from_u64_n(y, 4));
+                assert_eq!(product.len(), 8);
+                assert_eq!(u64_from_bits_slice(&product), x * y, "{} * {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_unsigned_random() {
+        let mut rng = XorShift::new(0x3017);
+        for width in [8, 16] {
+            let all_ones = (1u64 << width) - 1;
+            let mut cases = vec![(0, 0), (0, all_ones), (all_ones, 0), (all_ones, all_ones), (1, all_ones)];
+            cases.extend((0..200).map(|_| (rng.next_bits(width), rng.next_bits(width))));
+            for (x, y) in cases {
+                let product = multiply_unsigned(&bits_from_u64_n(x, width), &bits_from_u64_n(y, width));
+                assert_eq!(product.len(), 2 * width);
+                assert_eq!(u64_from_bits_slice(&product), x * y, "{} * {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_unsigned_mixed_widths() {
+        // 幅の異なる入力でも a.len() + b.len() ビットになる
+        assert_eq!(u64_from_bits_slice(&multiply_unsigned(&bits_from_u64_n(255, 8), &bits_from_u64_n(7, 3))), 255 * 7);
+        assert_eq!(multiply_unsigned(&bits_from_u64_n(5, 3), &[]), vec![false; 3]);
+        assert_eq!(multiply_unsigned(&[], &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_multiply_signed_sign_combinations() {
+        for (x, y) in [(3i64, 5i64), (-3, 5), (3, -5), (-3, -5), (7, -8), (-8, 7), (-8, -8), (-1, -1)] {
+            let product = multiply_signed(&from_signed(x, 4), &from_signed(y, 4));
+            assert_eq!(product.len(), 8);
+            assert_eq!(to_signed(&product), x * y, "{} * {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_multiply_signed_exhaustive_4x4() {
+        for x in -8..8i64 {
+            for y in -8..8i64 {
+                let product = multiply_signed(&from_signed(x, 4), &from_signed(y, 4));
+                assert_eq!(to_signed(&product), x * y, "{} * {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_signed_identities() {
+        let mut rng = XorShift::new(0xB007);
+        for _ in 0..50 {
+            let x = rng.next_bits(16) as u16 as i16 as i64;
+            let a = from_signed(x, 16);
+            assert_eq!(to_signed(&multiply_signed(&a, &from_signed(0, 16))), 0);
+            assert_eq!(to_signed(&multiply_signed(&a, &from_signed(1, 16))), x);
+            assert_eq!(to_signed(&multiply_signed(&a, &from_signed(-1, 16))), -x);
+            assert_eq!(to_signed(&multiply_signed(&from_signed(-1, 16), &a)), -x);
+        }
+    }
+
+    #[test]
+    fn test_multiply_signed_random() {
+        let mut rng = XorShift::new(0x1032);
+        for (wa, wb) in [(8, 8), (16, 16), (12, 5)] {
+            for _ in 0..200 {
+                let x = to_signed(&bits_from_u64_n(rng.next_bits(wa), wa));
+                let y = to_signed(&bits_from_u64_n(rng.next_bits(wb), wb));
+                let product = multiply_signed(&from_signed(x, wa), &from_signed(y, wb));
+                assert_eq!(product.len(), wa + wb);
+                assert_eq!(to_signed(&product), (x as i32 * y as i32) as i64, "{} * {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_signed_steps() {
+        // 3 × 6 (乗数 0110): ビットの組は 00, 10, 11, 01 → なし, 減算, なし, 加算
+        let r = multiply_signed_detailed(&from_signed(3, 4), &from_signed(6, 4));
+        let ops: Vec<BoothOp> = r.steps.iter().map(|step| step.op).collect();
+        assert_eq!(ops, vec![BoothOp::None, BoothOp::Subtract, BoothOp::None, BoothOp::Add]);
+
+        let partials: Vec<i64> = r.steps.iter().map(|step| to_signed(&step.partial)).collect();
+        assert_eq!(partials, vec![0, -6, -6, 18]);
+        assert_eq!(r.steps.last().unwrap().partial, r.product);
+        assert_eq!(to_signed(&r.product), 18);
+    }
+
+    #[test]
+    fn test_array_multiplier_exhaustive_4x4() {
+        for x in 0..16u64 {
+            for y in 0..16u64 {
+                let (a, b) = (bits_from_u64_n(x, 4), bits_from_u64_n(y, 4));
+                assert_eq!(array_multiplier(&a, &b), multiply_unsigned(&a, &b), "{} * {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_multiplier_random_8x8() {
+        let mut rng = XorShift::new(0xA88A);
+        for _ in 0..300 {
+            let (a, b) = (bits_from_u64_n(rng.next_bits(8), 8), bits_from_u64_n(rng.next_bits(8), 8));
+            assert_eq!(array_multiplier(&a, &b), multiply_unsigned(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_array_multiplier_width() {
+        for (wa, wb) in [(1, 1), (1, 5), (5, 1), (3, 7), (8, 8), (0, 4), (4, 0)] {
+            let product = array_multiplier(&vec![true; wa], &vec![true; wb]);
+            assert_eq!(product.len(), wa + wb, "{} x {}", wa, wb);
+            assert_eq!(u64_from_bits_slice(&product), ((1u64 << wa) - 1) * ((1u64 << wb) - 1));
+        }
+    }
+
+    #[test]
+    fn test_wallace_multiplier_exhaustive() {
+        for (wa, wb) in [(4, 4), (5, 3)] {
+            for x in 0..1u64 << wa {
+                for y in 0..1u64 << wb {
+                    let (a, b) = (bits_from_u64_n(x, wa), bits_from_u64_n(y, wb));
+                    let product = wallace_multiplier(&a, &b);
+                    assert_eq!(product, array_multiplier(&a, &b), "{} * {}", x, y);
+                    assert_eq!(product, multiply_unsigned(&a, &b), "{} * {}", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wallace_multiplier_random_16x16() {
+        let mut rng = XorShift::new(0x3A11);
+        for _ in 0..200 {
+            let (x, y) = (rng.next_bits(16), rng.next_bits(16));
+            let product = wallace_multiplier(&bits_from_u64_n(x, 16), &bits_from_u64_n(y, 16));
+            assert_eq!(product.len(), 32);
+            assert_eq!(u64_from_bits_slice(&product), x * y, "{} * {}", x, y);
+        }
+        assert_eq!(wallace_multiplier(&[], &bits_from_u64_n(5, 3)), vec![false; 3]);
+    }
+
+    #[test]
+    fn test_wallace_multiplier_layers_are_logarithmic() {
+        // 列の高さ h は1段で ceil(2h/3) 程度になる: 2 → 0段、3 → 1段、4 → 2段、8 → 4段、16 → 6段、32 → 8段
+        for (width, layers) in [(2, 0), (3, 1), (4, 2), (8, 4), (16, 6), (32, 8)] {
+            let r = wallace_multiplier_detailed(&vec![true; width], &vec![true; width]);
+            assert_eq!(r.layers, layers, "{} x {}", width, width);
+        }
+        // 行数（乗数の幅）が同じなら、被乗数の幅は段数に影響しない
+        assert_eq!(wallace_multiplier_detailed(&[true; 24], &[true; 4]).layers, 2);
+    }
+
+    #[test]
+    fn test_mac_dot_product() {
+        let coefficients = [3u64, 0, 255, 17, 128, 1];
+        let samples = [200u64, 99, 255, 4, 2, 0];
+        let mut acc = bits_from_u64_n(0, 24);
+        for (&c, &x) in coefficients.iter().zip(&samples) {
+            let (next, overflow) = mac(&acc, &bits_from_u64_n(c, 8), &bits_from_u64_n(x, 8));
+            assert!(!overflow);
+            acc = next;
+        }
+        let expected: u64 = coefficients.iter().zip(&samples).map(|(c, x)| c * x).sum();
+        assert_eq!(u64_from_bits_slice(&acc), expected);
+
+        let coefficients = [-3i64, 7, -128, 127, -1];
+        let samples = [100i64, -128, -128, 127, 1];
+        let mut acc = from_signed(0, 24);
+        for (&c, &x) in coefficients.iter().zip(&samples) {
+            let (next, overflow) = mac_signed(&acc, &from_signed(c, 8), &from_signed(x, 8));
+            assert!(!overflow);
+            acc = next;
+        }
+        let expected: i64 = coefficients.iter().zip(&samples).map(|(c, x)| c * x).sum();
+        assert_eq!(to_signed(&acc), expected);
+    }
+
+    #[test]
+    fn test_mac_overflow_boundary() {
+        // 8ビットの累積値: 255 はちょうど収まり、256 であふれる
+        assert_eq!(mac(&bits_from_u64_n(240, 8), &bits_from_u64_n(3, 4), &bits_from_u64_n(5, 4)), (bits_from_u64_n(255, 8), false));
+        assert_eq!(mac(&bits_from_u64_n(241, 8), &bits_from_u64_n(3, 4), &bits_from_u64_n(5, 4)), (bits_from_u64_n(0, 8), true));
+
+        // 符号付き: 127 は収まり、128 であふれる。-128 は収まり、-129 であふれる
+        assert_eq!(mac_signed(&from_signed(112, 8), &from_signed(3, 4), &from_signed(5, 4)), (from_signed(127, 8), false));
+        assert!(mac_signed(&from_signed(113, 8), &from_signed(3, 4), &from_signed(5, 4)).1);
+        assert_eq!(mac_signed(&from_signed(-113, 8), &from_signed(-3, 4), &from_signed(5, 4)), (from_signed(-128, 8), false));
+        assert!(mac_signed(&from_signed(-114, 8), &from_signed(-3, 4), &from_signed(5, 4)).1);
+    }
+
+    #[test]
+    fn test_mac_width_mismatch() {
+        // 累積値が積より広い: 積を0拡張・符号拡張する
+        assert_eq!(mac(&bits_from_u64_n(1000, 16), &bits_from_u64_n(15, 4), &bits_from_u64_n(3, 2)), (bits_from_u64_n(1045, 16), false));
+        assert_eq!(
+            mac_signed(&from_signed(1000, 16), &from_signed(-8, 4), &from_signed(-2, 2)),
+            (from_signed(1016, 16), false)
+        );
+        assert_eq!(
+            mac_signed(&from_signed(1000, 16), &from_signed(-8, 4), &from_signed(1, 2)),
+            (from_signed(992, 16), false)
+        );
+
+        // 累積値が積より狭い: 積の上位ビットがあっても、合計が収まればあふれない
+        assert_eq!(mac(&bits_from_u64_n(0, 4), &bits_from_u64_n(3, 4), &bits_from_u64_n(5, 4)), (bits_from_u64_n(15, 4), false));
+        assert_eq!(mac(&bits_from_u64_n(0, 4), &bits_from_u64_n(4, 4), &bits_from_u64_n(4, 4)), (bits_from_u64_n(0, 4), true));
+        assert_eq!(mac_signed(&from_signed(-8, 4), &from_signed(3, 4), &from_signed(3, 4)), (from_signed(1, 4), false));
+        assert!(mac_signed(&from_signed(0, 4), &from_signed(3, 4), &from_signed(3, 4)).1);
+    }
+
+    #[test]
+    fn test_fixed_mul_q_formats() {
+        // Q4.4: 1.5 × 2.25 = 3.375（24 × 36 / 16 = 54）
+        let q44 = |v: f64| from_signed((v * 16.0) as i64, 8);
+        assert_eq!(fixed_mul(&q44(1.5), &q44(2.25), 4, Rounding::Truncate), (q44(3.375), false));
+        assert_eq!(fixed_mul(&q44(-1.5), &q44(2.25), 4, Rounding::Truncate), (q44(-3.375), false));
+
+        // Q8.8
+        let q88 = |v: f64| from_signed((v * 256.0) as i64, 16);
+        assert_eq!(fixed_mul(&q88(1.5), &q88(2.25), 8, Rounding::RoundHalfUp), (q88(3.375), false));
+        assert_eq!(fixed_mul(&q88(-0.75), &q88(-100.5), 8, Rounding::Truncate), (q88(75.375), false));
+    }
+
+    #[test]
+    fn test_fixed_mul_rounding_half_ulp() {
+        // Q4.4 で (1/16) × 0.5 = 0.5 ulp
+        let (ulp, half) = (from_signed(1, 8), from_signed(8, 8));
+        assert_eq!(fixed_mul(&ulp, &half, 4, Rounding::Truncate), (from_signed(0, 8), false));
+        assert_eq!(fixed_mul(&ulp, &half, 4, Rounding::RoundHalfUp), (from_signed(1, 8), false));
+
+        // -0.5 ulp: 切り捨ては −∞ 方向、RoundHalfUp は +∞ 方向
+        let minus_ulp = from_signed(-1, 8);
+        assert_eq!(fixed_mul(&minus_ulp, &half, 4, Rounding::Truncate), (from_signed(-1, 8), false));
+        assert_eq!(fixed_mul(&minus_ulp, &half, 4, Rounding::RoundHalfUp), (from_signed(0, 8), false));
+
+        // 0.5 ulp 未満は RoundHalfUp でも切り捨てる（(1/16) × (7/16) = 7/16 ulp）
+        assert_eq!(fixed_mul(&ulp, &from_signed(7, 8), 4, Rounding::RoundHalfUp), (from_signed(0, 8), false));
+    }
+
+    #[test]
+    fn test_fixed_mul_overflow() {
+        // Q4.4 の範囲は -8 〜 7.9375
+        let q44 = |v: f64| from_signed((v * 16.0) as i64, 8);
+        assert!(fixed_mul(&q44(4.0), &q44(4.0), 4, Rounding::Truncate).1);
+        assert!(fixed_mul(&q44(-8.0), &q44(-1.0), 4, Rounding::Truncate).1);
+        assert_eq!(fixed_mul(&q44(-4.0), &q44(2.0), 4, Rounding::Truncate), (q44(-8.0), false));
+        assert_eq!(fixed_mul(&q44(3.5), &q44(2.0), 4, Rounding::Truncate), (q44(7.0), false));
+
+        // 丸めの繰り上がりで範囲を超える: 7.5 × (17/16) = 7.96875 は切り捨てなら 7.9375 に収まるが、丸めると 8.0
+        let (a, b) = (from_signed(120, 8), from_signed(17, 8));
+        assert_eq!(fixed_mul(&a, &b, 4, Rounding::Truncate), (from_signed(127, 8), false));
+        assert!(fixed_mul(&a, &b, 4, Rounding::RoundHalfUp).1);
+    }
+
+    #[test]
+    fn test_csd_digits() {
+        assert_eq!(csd_digits(0), Vec::<i8>::new());
+        assert_eq!(csd_digits(1), vec![1]);
+        assert_eq!(csd_digits(15), vec![-1, 0, 0, 0, 1]);
+        assert_eq!(csd_digits(10), vec![0, 1, 0, 1]);
+        for k in [0u64, 1, 3, 7, 15, 0x5555, 0xDEAD_BEEF, u64::MAX] {
+            let digits = csd_digits(k);
+            let total: i128 = digits.iter().enumerate().map(|(i, &d)| (d as i128) << i).sum();
+            assert_eq!(total, k as i128);
+            assert!(digits.windows(2).all(|w| w[0] == 0 || w[1] == 0), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn test_multiply_by_const_exhaustive_8bit() {
+        for k in 0..=64u64 {
+            let k_bits = bits_from_u64_n(k, (u64::BITS - k.leading_zeros()) as usize);
+            for x in 0..256u64 {
+                let a = bits_from_u64_n(x, 8);
+                assert_eq!(multiply_by_const(&a, k), multiply_unsigned(&a, &k_bits), "{} * {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiply_by_const_random() {
+        let mut rng = XorShift::new(0xC0C5);
+        for _ in 0..200 {
+            let k = rng.next_bits(16);
+            let x = rng.next_bits(16);
+            let product = multiply_by_const(&bits_from_u64_n(x, 16), k);
+            assert_eq!(product.len(), 16 + (u64::BITS - k.leading_zeros()) as usize);
+            assert_eq!(u64_from_bits_slice(&product), x * k, "{} * {}", x, k);
+        }
+    }
+
+    #[test]
+    fn test_multiply_by_const_network_size() {
+        let a = bits_from_u64_n(0xAB, 8);
+        // k = 0 と k = 1 は加算器を使わない
+        let r = multiply_by_const_detailed(&a, 0);
+        assert_eq!((r.product, r.adders, r.subtractors), (vec![false; 8], 0, 0));
+        let r = multiply_by_const_detailed(&a, 1);
+        assert_eq!((u64_from_bits_slice(&r.product), r.adders, r.subtractors), (0xAB, 0, 0));
+
+        // 15 = 16 - 1: 減算器1つ
+        let r = multiply_by_const_detailed(&a, 15);
+        assert_eq!((u64_from_bits_slice(&r.product), r.adders, r.subtractors), (0xAB * 15, 0, 1));
+        // 10 = 8 + 2: 加算器1つ。2の累乗は配線だけ
+        assert_eq!(multiply_by_const_detailed(&a, 10).adders, 1);
+        let r = multiply_by_const_detailed(&a, 32);
+        assert_eq!((u64_from_bits_slice(&r.product), r.adders, r.subtractors), (0xAB * 32, 0, 0));
+    }
+}
@@ -0,0 +1,118 @@
+/// 2入力 NOR ゲート
+///
+/// このモジュールの唯一のプリミティブ。以下のゲートはすべてこれだけで組み立てる
+/// （lib.rs の NAND版の関数は呼ばない）
+///
+/// *真理値表*  
+/// A | B | NOR  
+/// 0 | 0 | 1  
+/// 0 | 1 | 0  
+/// 1 | 0 | 0  
+/// 1 | 1 | 0
+pub fn nor(a: bool, b: bool) -> bool {
+    !(a || b)
+}
+
+/// NOT  (￢A) = NOR(A, A)
+pub fn not(a: bool) -> bool {
+    nor(a, a)
+}
+
+/// OR   (A + B) = NOT (NOR(A, B))
+pub fn or(a: bool, b: bool) -> bool {
+    let n = nor(a, b);
+    nor(n, n)
+}
+
+/// AND  (A · B) = NOR(NOT A, NOT B)
+pub fn and(a: bool, b: bool) -> bool {
+    let na = nor(a, a);
+    let nb = nor(b, b);
+    nor(na, nb)
+}
+
+/// XOR  (A ⊕ B) = NOT (NOR(NOR(A, NOR(A, B)), NOR(B, NOR(A, B))))
+///
+/// 内側の4個の NOR で XNOR を作り、最後に反転する
+pub fn xor(a: bool, b: bool) -> bool {
+    let t1 = nor(a, b);
+    let t2 = nor(a, t1);
+    let t3 = nor(b, t1);
+    let xnor = nor(t2, t3);
+    nor(xnor, xnor)
+}
+
+/// 2:1 マルチプレクサ  
+/// sel が 0 なら a、1 なら b を出力
+pub fn mux(sel: bool, a: bool, b: bool) -> bool {
+    let not_sel = not(sel);
+    let a_and_not_sel = and(a, not_sel);
+    let b_and_sel = and(b, sel);
+    or(a_and_not_sel, b_and_sel)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::contract::*;
+
+    /// 1つのゲート基底（not, and, or, xor, mux の組）
+    struct Basis {
+        not: fn(bool) -> bool,
+        and: fn(bool, bool) -> bool,
+        or: fn(bool, bool) -> bool,
+        xor: fn(bool, bool) -> bool,
+        mux: fn(bool, bool, bool) -> bool,
+    }
+
+    const NAND_BASIS: Basis = Basis {
+        not: crate::not,
+        and: crate::and,
+        or: crate::or,
+        xor: crate::xor,
+        mux: crate::mux,
+    };
+
+    const NOR_BASIS: Basis = Basis {
+        not: super::not,
+        and: super::and,
+        or: super::or,
+        xor: super::xor,
+        mux: super::mux,
+    };
+
+    /// 同じ真理値表で基底のすべてのゲートを検査する
+    fn check_basis(basis: &Basis) {
+        assert_eq!(check_contract1(basis.not, &NOT_TABLE), Ok(()));
+        assert_eq!(check_contract2(basis.and, &AND_TABLE), Ok(()));
+        assert_eq!(check_contract2(basis.or, &OR_TABLE), Ok(()));
+        assert_eq!(check_contract2(basis.xor, &XOR_TABLE), Ok(()));
+        assert_eq!(check_contract3(basis.mux, &MUX_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn test_nor_primitive() {
+        assert_eq!(check_contract2(super::nor, &NOR_TABLE), Ok(()));
+    }
+
+    #[test]
+    fn test_nand_basis() {
+        check_basis(&NAND_BASIS);
+    }
+
+    #[test]
+    fn test_nor_basis() {
+        check_basis(&NOR_BASIS);
+    }
+
+    #[test]
+    fn test_bases_agree() {
+        for v in 0..8u8 {
+            let (a, b, c) = (v & 1 != 0, v & 2 != 0, v & 4 != 0);
+            assert_eq!((NAND_BASIS.not)(a), (NOR_BASIS.not)(a));
+            assert_eq!((NAND_BASIS.and)(a, b), (NOR_BASIS.and)(a, b));
+            assert_eq!((NAND_BASIS.or)(a, b), (NOR_BASIS.or)(a, b));
+            assert_eq!((NAND_BASIS.xor)(a, b), (NOR_BASIS.xor)(a, b));
+            assert_eq!((NAND_BASIS.mux)(a, b, c), (NOR_BASIS.mux)(a, b, c));
+        }
+    }
+}
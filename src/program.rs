@@ -0,0 +1,196 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+/// バイナリ形式のプログラムファイルの先頭に置くマジックバイト
+pub const PROGRAM_MAGIC: [u8; 4] = *b"HACK";
+
+/// プログラムファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramFormat {
+    /// .hack テキスト形式（1行に16文字の0/1、MSBが左）
+    HackText,
+    /// バイナリ形式（マジックバイト + ビッグエンディアンの16ビットワード列）
+    Binary,
+}
+
+/// プログラムの読み込みエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramParseError {
+    /// line行目（1始まり）が16文字ではない
+    WrongLength { line: usize, length: usize },
+    /// line行目のcolumn文字目（1始まり）が0/1以外
+    InvalidChar { line: usize, column: usize, ch: char },
+    /// テキスト形式がUTF-8として読めない
+    InvalidUtf8,
+    /// バイナリ形式の先頭がマジックバイトではない
+    BadMagic,
+    /// バイナリ形式の本体のバイト数が奇数
+    TruncatedWord { length: usize },
+}
+
+impl fmt::Display for ProgramParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramParseError::WrongLength { line, length } => {
+                write!(f, "line {}: expected 16 binary digits, found {} characters", line, length)
+            }
+            ProgramParseError::InvalidChar { line, column, ch } => {
+                write!(f, "line {}, column {}: invalid character {:?}", line, column, ch)
+            }
+            ProgramParseError::InvalidUtf8 => write!(f, "program text is not valid UTF-8"),
+            ProgramParseError::BadMagic => write!(f, "binary program does not start with the HACK magic"),
+            ProgramParseError::TruncatedWord { length } => {
+                write!(f, "binary program body has odd length {}", length)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ProgramParseError {}
+
+/// プログラムを .hack テキスト形式に変換する
+///
+/// 各ワードを16文字の0/1（MSBが左）で1行ずつ出力する（各行は改行で終わる）
+pub fn program_to_hack_text(program: &[u16]) -> String {
+    let mut text = String::with_capacity(program.len() * 17);
+    for word in program {
+        text.push_str(&format!("{:016b}\n", word));
+    }
+    text
+}
+
+/// .hack テキスト形式のプログラムを読み込む
+///
+/// 前後の空白は無視し、空行は読み飛ばす。エラーには1始まりの行番号が入る
+pub fn program_from_hack_text(text: &str) -> Result<Vec<u16>, ProgramParseError> {
+    let mut program = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let length = trimmed.chars().count();
+        if length != 16 {
+            return Err(ProgramParseError::WrongLength { line, length });
+        }
+
+        let mut word = 0u16;
+        for (column, ch) in trimmed.chars().enumerate() {
+            let bit = match ch {
+                '0' => 0,
+                '1' => 1,
+                _ => return Err(ProgramParseError::InvalidChar { line, column: column + 1, ch }),
+            };
+            word = (word << 1) | bit;
+        }
+        program.push(word);
+    }
+    Ok(program)
+}
+
+/// プログラムをバイナリ形式に変換する
+///
+/// 先頭に PROGRAM_MAGIC（"HACK" の4バイト）を置き、続けて各ワードをビッグエンディアンで並べる
+pub fn program_to_bytes(program: &[u16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(PROGRAM_MAGIC.len() + program.len() * 2);
+    bytes.extend_from_slice(&PROGRAM_MAGIC);
+    for word in program {
+        bytes.extend_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
+
+/// バイナリ形式のプログラムを読み込む
+pub fn program_from_bytes(bytes: &[u8]) -> Result<Vec<u16>, ProgramParseError> {
+    let body = bytes.strip_prefix(&PROGRAM_MAGIC[..]).ok_or(ProgramParseError::BadMagic)?;
+    if body.len() % 2 != 0 {
+        return Err(ProgramParseError::TruncatedWord { length: body.len() });
+    }
+    Ok(body.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect())
+}
+
+/// ファイルの内容からプログラムの形式を判定する
+///
+/// 先頭がマジックバイトならバイナリ形式、そうでなければテキスト形式とみなす
+pub fn detect_format(bytes: &[u8]) -> ProgramFormat {
+    if bytes.starts_with(&PROGRAM_MAGIC) {
+        ProgramFormat::Binary
+    } else {
+        ProgramFormat::HackText
+    }
+}
+
+/// 形式を自動判定してプログラムを読み込む
+pub fn load_program(bytes: &[u8]) -> Result<Vec<u16>, ProgramParseError> {
+    match detect_format(bytes) {
+        ProgramFormat::Binary => program_from_bytes(bytes),
+        ProgramFormat::HackText => {
+            let text = core::str::from_utf8(bytes).map_err(|_| ProgramParseError::InvalidUtf8)?;
+            program_from_hack_text(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: [u16; 5] = [0x0002, 0xEC10, 0x0003, 0xE090, 0x8000];
+
+    #[test]
+    fn test_hack_text_round_trip() {
+        let text = program_to_hack_text(&PROGRAM);
+        assert_eq!(text.lines().next(), Some("0000000000000010"));
+        assert_eq!(program_from_hack_text(&text), Ok(PROGRAM.to_vec()));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let bytes = program_to_bytes(&PROGRAM);
+        assert_eq!(&bytes[..6], b"HACK\x00\x02");
+        assert_eq!(program_from_bytes(&bytes), Ok(PROGRAM.to_vec()));
+    }
+
+    #[test]
+    fn test_hack_text_errors() {
+        let text = "0000000000000010\n000000000000001\n";
+        assert_eq!(
+            program_from_hack_text(text),
+            Err(ProgramParseError::WrongLength { line: 2, length: 15 })
+        );
+
+        let text = "0000000000000010\n\n00000000200000010\n";
+        assert_eq!(
+            program_from_hack_text(text),
+            Err(ProgramParseError::WrongLength { line: 3, length: 17 })
+        );
+
+        let text = "0000000000000010\n0000000020000001\n";
+        assert_eq!(
+            program_from_hack_text(text),
+            Err(ProgramParseError::InvalidChar { line: 2, column: 9, ch: '2' })
+        );
+    }
+
+    #[test]
+    fn test_bytes_errors() {
+        assert_eq!(program_from_bytes(b"KCAH\x00\x01"), Err(ProgramParseError::BadMagic));
+        assert_eq!(
+            program_from_bytes(b"HACK\x00\x01\x02"),
+            Err(ProgramParseError::TruncatedWord { length: 3 })
+        );
+    }
+
+    #[test]
+    fn test_auto_detection() {
+        let text = program_to_hack_text(&PROGRAM);
+        let bytes = program_to_bytes(&PROGRAM);
+
+        assert_eq!(detect_format(text.as_bytes()), ProgramFormat::HackText);
+        assert_eq!(detect_format(&bytes), ProgramFormat::Binary);
+        assert_eq!(load_program(text.as_bytes()), Ok(PROGRAM.to_vec()));
+        assert_eq!(load_program(&bytes), Ok(PROGRAM.to_vec()));
+    }
+}
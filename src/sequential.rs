@@ -0,0 +1,169 @@
+use crate::{and, not, or, xor};
+use crate::adder::full_adder;
+
+/// Dフリップフロップ
+///
+/// クロック（tick）ごとに入力 d を取り込み、次のクロックまで保持する
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dff {
+    state: bool,
+}
+
+impl Dff {
+    /// 0を保持した状態で作る
+    pub fn new() -> Self {
+        Dff { state: false }
+    }
+
+    /// 現在保持している値
+    pub fn out(&self) -> bool {
+        self.state
+    }
+
+    /// クロックを1つ進めて d を取り込み、取り込む前に保持していた値を返す
+    pub fn tick(&mut self, d: bool) -> bool {
+        let previous = self.state;
+        self.state = d;
+        previous
+    }
+}
+
+/// ビットシリアル加算器
+///
+/// 1つの全加算器と、桁上げを保持する1つの Dff から成る
+/// オペランドをLSBから1ビットずつクロックごとに与えると、和を1ビットずつ出力する
+///
+/// 新しい加算を始める前には reset_carry で桁上げを0に戻すこと
+/// （戻さないと、前回の加算の最終桁上げが次の加算の最下位ビットに足される）
+#[derive(Debug, Clone, Default)]
+pub struct SerialAdder {
+    carry: Dff,
+}
+
+impl SerialAdder {
+    pub fn new() -> Self {
+        SerialAdder { carry: Dff::new() }
+    }
+
+    /// 現在の桁上げ
+    pub fn carry(&self) -> bool {
+        self.carry.out()
+    }
+
+    /// 桁上げを0に戻す
+    pub fn reset_carry(&mut self) {
+        self.carry = Dff::new();
+    }
+
+    /// 1ビット分の加算を行い、その桁の和を返す
+    pub fn tick(&mut self, a: bool, b: bool) -> bool {
+        let (sum, carry_out) = full_adder(a, b, self.carry.out());
+        self.carry.tick(carry_out);
+        sum
+    }
+}
+
+/// ビットシリアル減算器
+///
+/// 借り（borrow）を保持する Dff を持ち、a - b を LSB から1ビットずつ計算する
+///
+/// - 差: a XOR b XOR borrow
+/// - 次の借り: (NOT a AND b) OR (NOT (a XOR b) AND borrow)
+#[derive(Debug, Clone, Default)]
+pub struct SerialSubtractor {
+    borrow: Dff,
+}
+
+impl SerialSubtractor {
+    pub fn new() -> Self {
+        SerialSubtractor { borrow: Dff::new() }
+    }
+
+    /// 現在の借り
+    pub fn borrow(&self) -> bool {
+        self.borrow.out()
+    }
+
+    /// 借りを0に戻す
+    pub fn reset_borrow(&mut self) {
+        self.borrow = Dff::new();
+    }
+
+    /// 1ビット分の減算を行い、その桁の差を返す
+    pub fn tick(&mut self, a: bool, b: bool) -> bool {
+        let borrow_in = self.borrow.out();
+        let a_xor_b = xor(a, b);
+        let difference = xor(a_xor_b, borrow_in);
+        let borrow_out = or(and(not(a), b), and(not(a_xor_b), borrow_in));
+        self.borrow.tick(borrow_out);
+        difference
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::adder::{n_bit_adder, n_bit_subtractor};
+    use crate::convert::{bits_from_u64_n, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    fn run_adder(adder: &mut SerialAdder, a: &[bool], b: &[bool]) -> Vec<bool> {
+        a.iter().zip(b).map(|(&x, &y)| adder.tick(x, y)).collect()
+    }
+
+    #[test]
+    fn test_dff() {
+        let mut dff = Dff::new();
+        assert_eq!(dff.tick(true), false);
+        assert_eq!(dff.out(), true);
+        assert_eq!(dff.tick(false), true);
+        assert_eq!(dff.out(), false);
+    }
+
+    #[test]
+    fn test_serial_adder_matches_n_bit_adder() {
+        let mut rng = XorShift::new(0x5E71A1);
+        let mut adder = SerialAdder::new();
+        for _ in 0..200 {
+            let a = bits_from_u64_n(rng.next_bits(16), 16);
+            let b = bits_from_u64_n(rng.next_bits(16), 16);
+
+            adder.reset_carry();
+            let sum = run_adder(&mut adder, &a, &b);
+            assert_eq!((sum, adder.carry()), n_bit_adder(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_serial_adder_without_reset() {
+        let mut adder = SerialAdder::new();
+
+        // 0xFFFF + 0x0001 は最終桁上げが1になる
+        let sum = run_adder(&mut adder, &bits_from_u64_n(0xFFFF, 16), &bits_from_u64_n(0x0001, 16));
+        assert_eq!(u64_from_bits_slice(&sum), 0);
+        assert_eq!(adder.carry(), true);
+
+        // リセットを忘れると、残った桁上げが次の加算に足される: 2 + 3 + 1 = 6
+        let sum = run_adder(&mut adder, &bits_from_u64_n(2, 16), &bits_from_u64_n(3, 16));
+        assert_eq!(u64_from_bits_slice(&sum), 6);
+
+        // リセットすれば正しく 2 + 3 = 5
+        adder.reset_carry();
+        let sum = run_adder(&mut adder, &bits_from_u64_n(2, 16), &bits_from_u64_n(3, 16));
+        assert_eq!(u64_from_bits_slice(&sum), 5);
+    }
+
+    #[test]
+    fn test_serial_subtractor_matches_n_bit_subtractor() {
+        let mut rng = XorShift::new(0x5B);
+        let mut subtractor = SerialSubtractor::new();
+        for _ in 0..200 {
+            let a = bits_from_u64_n(rng.next_bits(16), 16);
+            let b = bits_from_u64_n(rng.next_bits(16), 16);
+
+            subtractor.reset_borrow();
+            let difference: Vec<bool> = a.iter().zip(&b).map(|(&x, &y)| subtractor.tick(x, y)).collect();
+            assert_eq!((difference, subtractor.borrow()), n_bit_subtractor(&a, &b));
+        }
+    }
+}
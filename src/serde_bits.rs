@@ -0,0 +1,63 @@
+use alloc::{format, vec::Vec};
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::Serializer;
+
+use crate::convert::{format_bits, parse_bits};
+
+/// ビット列（LSB→MSB順）を、最上位ビットを左にした2進数の文字列（"0110" など）として書き出す
+///
+/// `#[serde(with = "logic_gates::serde_bits")]` または
+/// `#[serde(serialize_with = "logic_gates::serde_bits::serialize_bits")]` で `Vec<bool>` のフィールドに使う
+pub fn serialize_bits<S: Serializer>(bits: &[bool], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_bits(bits))
+}
+
+/// serialize_bits で書き出した文字列をビット列（LSB→MSB順）に読み戻す
+///
+/// 文字列は parse_bits の形式（接頭辞 `0b` と `_` を使える）。幅は数字の個数になる
+pub fn deserialize_bits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<bool>, D::Error> {
+    struct BitsVisitor;
+
+    impl Visitor<'_> for BitsVisitor {
+        type Value = Vec<bool>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a binary string such as \"0110\"")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Vec<bool>, E> {
+            parse_bits(s).map_err(|e| E::custom(format!("invalid bit string {:?}: {}", s, e)))
+        }
+    }
+
+    deserializer.deserialize_str(BitsVisitor)
+}
+
+pub use self::deserialize_bits as deserialize;
+pub use self::serialize_bits as serialize;
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Vector {
+        #[serde(with = "crate::serde_bits")]
+        inputs: Vec<bool>,
+    }
+
+    #[test]
+    fn test_bits_round_trip() {
+        let v = Vector { inputs: vec![false, true, true, false, true] };
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"inputs":"10110"}"#);
+        assert_eq!(serde_json::from_str::<Vector>(&json).unwrap(), v);
+    }
+
+    #[test]
+    fn test_bits_malformed() {
+        let err = serde_json::from_str::<Vector>(r#"{"inputs":"10a1"}"#).unwrap_err();
+        assert!(err.to_string().contains("invalid bit string"), "{}", err);
+    }
+}
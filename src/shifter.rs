@@ -0,0 +1,313 @@
+use alloc::vec::Vec;
+
+use crate::{and, or};
+use crate::bus::mux_bus;
+
+/// 1ビット左シフト（配線だけ）
+///
+/// ビット i をビット i + 1 へつなぎ、最下位ビットを0にする
+/// 戻り値は (shifted, out)（out は押し出された最上位ビット）
+fn shift_left_one(a: &[bool]) -> (Vec<bool>, bool) {
+    let Some((&out, rest)) = a.split_last() else {
+        return (Vec::new(), false);
+    };
+    let mut shifted = Vec::with_capacity(a.len());
+    shifted.push(false);
+    shifted.extend_from_slice(rest);
+    (shifted, out)
+}
+
+/// 1ビット右シフト（配線だけ）
+///
+/// ビット i + 1 をビット i へつなぎ、最上位ビットを fill にする
+/// 戻り値は (shifted, out)（out は押し出された最下位ビット）
+fn shift_right_one(a: &[bool], fill: bool) -> (Vec<bool>, bool) {
+    let Some((&out, rest)) = a.split_first() else {
+        return (Vec::new(), false);
+    };
+    let mut shifted = Vec::with_capacity(a.len());
+    shifted.extend_from_slice(rest);
+    shifted.push(fill);
+    (shifted, out)
+}
+
+/// 論理左シフト
+///
+/// MSB 方向へ k ビットずらし、空いた下位ビットを0で埋める。幅は変えない
+/// 1ビット左シフトの配線を k 段つないだ回路で、押し出されたビットを OR で集める
+/// （k が幅以上なら、幅の段数で結果はすべて0になるので、そこで止める）
+///
+/// * `a` - 入力（LSB→MSB順）
+/// * `k` - シフト量
+///
+/// 戻り値は (shifted, lost) のタプル
+/// - shifted: 入力と同じ幅のシフト結果
+/// - lost: 1のビットが1つでも押し出された（情報が失われた）
+pub fn shift_left(a: &[bool], k: usize) -> (Vec<bool>, bool) {
+    let mut shifted = a.to_vec();
+    let mut lost = false;
+    for _ in 0..k.min(a.len()) {
+        let (next, out) = shift_left_one(&shifted);
+        shifted = next;
+        lost = or(lost, out);
+    }
+    (shifted, lost)
+}
+
+/// 論理右シフト
+///
+/// LSB 方向へ k ビットずらし、空いた上位ビットを0で埋める。幅は変えない
+/// shift_left と同じく、1ビット右シフトの配線を k 段（幅が上限）つないだ回路
+///
+/// * `a` - 入力（LSB→MSB順）
+/// * `k` - シフト量
+///
+/// 戻り値は (shifted, lost) のタプル
+/// - shifted: 入力と同じ幅のシフト結果
+/// - lost: 1のビットが1つでも押し出された（符号なしの割り算で余りが出た）
+pub fn shift_right_logical(a: &[bool], k: usize) -> (Vec<bool>, bool) {
+    let mut shifted = a.to_vec();
+    let mut lost = false;
+    for _ in 0..k.min(a.len()) {
+        let (next, out) = shift_right_one(&shifted, false);
+        shifted = next;
+        lost = or(lost, out);
+    }
+    (shifted, lost)
+}
+
+/// 算術右シフト
+///
+/// LSB 方向へ k ビットずらし、空いた上位ビットを元の符号ビット（MSB）で埋める。幅は変えない
+/// 埋めるビットは、どの段でも元の符号ビットからの配線で与える
+/// （2の補数の値を −∞ 方向に丸めて 2^k で割るのと同じ）
+///
+/// * `a` - 2の補数の入力（LSB→MSB順）
+/// * `k` - シフト量
+///
+/// k が幅以上なら、すべてのビットが符号ビットになる（0 か −1）
+pub fn shift_right_arithmetic(a: &[bool], k: usize) -> Vec<bool> {
+    let sign = a.last().copied().unwrap_or(false);
+    let mut shifted = a.to_vec();
+    for _ in 0..k.min(a.len()) {
+        shifted = shift_right_one(&shifted, sign).0;
+    }
+    shifted
+}
+
+/// バレルシフタのシフト方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftDirection {
+    /// MSB 方向
+    Left,
+    /// LSB 方向
+    Right,
+}
+
+/// バレルシフタの空いたビットの埋め方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftMode {
+    /// 0で埋める
+    Logical,
+    /// 右シフトでは元の符号ビット（MSB）で埋める。左シフトでは Logical と同じく0で埋める
+    /// （左シフトで空くのは下位ビットなので、符号で埋める意味がない）
+    Arithmetic,
+}
+
+/// 固定の距離のシフト（配線だけ）
+///
+/// 範囲外から来るビットは fill になる。distance が幅以上なら、すべて fill
+fn rewire(a: &[bool], distance: usize, direction: ShiftDirection, fill: bool) -> Vec<bool> {
+    (0..a.len())
+        .map(|i| {
+            let source = match direction {
+                ShiftDirection::Left => i.checked_sub(distance),
+                ShiftDirection::Right => i.checked_add(distance).filter(|&source| source < a.len()),
+            };
+            source.map_or(fill, |source| a[source])
+        })
+        .collect()
+}
+
+/// バレルシフタ
+///
+/// シフト量を2進数のバス amount で与える対数段の回路。段 i は、固定の 2^i ビットのシフト（配線）と
+/// シフトしない値のどちらかを、amount[i] を選択信号にした mux_bus で選ぶ
+///
+/// * `a` - 入力（LSB→MSB順）
+/// * `amount` - シフト量（LSB→MSB順の2進数）
+/// * `direction` - シフト方向
+/// * `mode` - 空いたビットの埋め方（ShiftMode を参照）
+///
+/// 出力は入力と同じ幅。シフト量が幅以上なら、すべて0（算術右シフトでは、すべて符号ビット）になる
+pub fn barrel_shift(a: &[bool], amount: &[bool], direction: ShiftDirection, mode: ShiftMode) -> Vec<bool> {
+    let sign = a.last().copied().unwrap_or(false);
+    let arithmetic_right = mode == ShiftMode::Arithmetic && direction == ShiftDirection::Right;
+    let fill = and(sign, arithmetic_right);
+
+    let mut shifted = a.to_vec();
+    for (i, &select) in amount.iter().enumerate() {
+        // 2^i が幅以上の段は、すべてを fill にする
+        let distance = 1usize.checked_shl(i as u32).unwrap_or(usize::MAX).min(a.len());
+        shifted = mux_bus(select, &shifted, &rewire(&shifted, distance, direction, fill));
+    }
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_u64_n, to_signed, u64_from_bits_slice};
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_shift_left_identity() {
+        for x in [0u64, 1, 0x8000, 0xA5A5, 0xFFFF] {
+            assert_eq!(shift_left(&bits_from_u64_n(x, 16), 0), (bits_from_u64_n(x, 16), false));
+        }
+        assert_eq!(shift_left(&[], 3), (Vec::<bool>::new(), false));
+    }
+
+    #[test]
+    fn test_shift_left_beyond_width() {
+        for k in [16, 17, 100] {
+            assert_eq!(shift_left(&bits_from_u64_n(0x0001, 16), k), (bits_from_u64_n(0, 16), true));
+            assert_eq!(shift_left(&bits_from_u64_n(0, 16), k), (bits_from_u64_n(0, 16), false));
+        }
+        // 押し出されるのが0だけなら失われない
+        assert_eq!(shift_left(&bits_from_u64_n(0x00FF, 16), 8), (bits_from_u64_n(0xFF00, 16), false));
+        assert_eq!(shift_left(&bits_from_u64_n(0x01FF, 16), 8), (bits_from_u64_n(0xFF00, 16), true));
+    }
+
+    #[test]
+    fn test_shift_left_matches_u16() {
+        let mut rng = XorShift::new(0x5417);
+        for _ in 0..200 {
+            let x = rng.next_bits(16) as u16;
+            for k in 0..16 {
+                let (shifted, lost) = shift_left(&bits_from_u64_n(x as u64, 16), k);
+                assert_eq!(u64_from_bits_slice(&shifted), (x << k) as u64, "{:#06x} << {}", x, k);
+                assert_eq!(lost, (x as u32) << k > 0xFFFF, "{:#06x} << {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_right_logical_edges() {
+        for x in [0u64, 1, 0x8000, 0xA5A5, 0xFFFF] {
+            assert_eq!(shift_right_logical(&bits_from_u64_n(x, 16), 0), (bits_from_u64_n(x, 16), false));
+        }
+        // k が幅と等しい、または幅を超える
+        for k in [16, 17, 100] {
+            assert_eq!(shift_right_logical(&bits_from_u64_n(0x8000, 16), k), (bits_from_u64_n(0, 16), true));
+            assert_eq!(shift_right_logical(&bits_from_u64_n(0, 16), k), (bits_from_u64_n(0, 16), false));
+        }
+        assert_eq!(shift_right_logical(&bits_from_u64_n(0xFF00, 16), 8), (bits_from_u64_n(0x00FF, 16), false));
+        assert_eq!(shift_right_logical(&bits_from_u64_n(0xFF80, 16), 8), (bits_from_u64_n(0x00FF, 16), true));
+    }
+
+    #[test]
+    fn test_shift_right_logical_matches_u16() {
+        let mut rng = XorShift::new(0x5A17);
+        for _ in 0..200 {
+            let x = rng.next_bits(16) as u16;
+            for k in 0..16 {
+                let (shifted, lost) = shift_right_logical(&bits_from_u64_n(x as u64, 16), k);
+                assert_eq!(u64_from_bits_slice(&shifted), (x >> k) as u64, "{:#06x} >> {}", x, k);
+                assert_eq!(lost, x & ((1 << k) - 1) != 0, "{:#06x} >> {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_left_then_right_masks_low_bits() {
+        // 左に k ずらしてから右に k ずらすと、上位 k ビットが0になる
+        let mut rng = XorShift::new(0x1A2B);
+        for _ in 0..50 {
+            let x = rng.next_bits(16);
+            for k in 0..=16 {
+                let (left, _) = shift_left(&bits_from_u64_n(x, 16), k);
+                let (back, _) = shift_right_logical(&left, k);
+                let mask = if k >= 16 { 0 } else { 0xFFFF >> k };
+                assert_eq!(u64_from_bits_slice(&back), x & mask, "{:#06x}, k = {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_right_arithmetic_matches_i16() {
+        let mut rng = XorShift::new(0xA5A1);
+        let mut values = vec![0i16, 1, -2, 0x7FFF, i16::MIN, -1];
+        values.extend((0..100).map(|_| rng.next_bits(16) as u16 as i16));
+        for x in values {
+            for k in 0..=16usize {
+                let shifted = shift_right_arithmetic(&bits_from_u64_n(x as u16 as u64, 16), k);
+                assert_eq!(to_signed(&shifted), (x >> k.min(15)) as i64, "{} >> {}", x, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_right_arithmetic_sign_fill() {
+        // -1 はどれだけずらしても -1
+        for k in [0, 1, 15, 16, 40] {
+            assert_eq!(shift_right_arithmetic(&bits_from_u64_n(0xFFFF, 16), k), bits_from_u64_n(0xFFFF, 16));
+        }
+        // 最小値は幅以上ずらすと -1、正の数は0
+        assert_eq!(shift_right_arithmetic(&bits_from_u64_n(0x8000, 16), 15), bits_from_u64_n(0xFFFF, 16));
+        assert_eq!(shift_right_arithmetic(&bits_from_u64_n(0x8000, 16), 20), bits_from_u64_n(0xFFFF, 16));
+        assert_eq!(shift_right_arithmetic(&bits_from_u64_n(0x7FFF, 16), 16), bits_from_u64_n(0, 16));
+        assert_eq!(shift_right_arithmetic(&[], 2), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_barrel_shift_matches_fixed_shifters() {
+        let mut rng = XorShift::new(0xBA11);
+        for _ in 0..20 {
+            let a = bits_from_u64_n(rng.next_bits(8), 8);
+            // amount は4ビットのバス（0〜15、幅を超える量も含む）
+            for k in 0..16usize {
+                let amount = bits_from_u64_n(k as u64, 4);
+                let left = shift_left(&a, k).0;
+                assert_eq!(barrel_shift(&a, &amount, ShiftDirection::Left, ShiftMode::Logical), left);
+                assert_eq!(barrel_shift(&a, &amount, ShiftDirection::Left, ShiftMode::Arithmetic), left);
+                assert_eq!(
+                    barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Logical),
+                    shift_right_logical(&a, k).0
+                );
+                assert_eq!(
+                    barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Arithmetic),
+                    shift_right_arithmetic(&a, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_barrel_shift_random_16bit() {
+        let mut rng = XorShift::new(0xBA16);
+        for _ in 0..300 {
+            let x = rng.next_bits(16) as u16;
+            let k = rng.next_bits(5) as usize;
+            let (a, amount) = (bits_from_u64_n(x as u64, 16), bits_from_u64_n(k as u64, 5));
+            let left = u64_from_bits_slice(&barrel_shift(&a, &amount, ShiftDirection::Left, ShiftMode::Logical));
+            let right = u64_from_bits_slice(&barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Logical));
+            let arithmetic = to_signed(&barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Arithmetic));
+            assert_eq!(left, x.checked_shl(k as u32).unwrap_or(0) as u64, "{:#06x} << {}", x, k);
+            assert_eq!(right, x.checked_shr(k as u32).unwrap_or(0) as u64, "{:#06x} >> {}", x, k);
+            assert_eq!(arithmetic, ((x as i16) >> k.min(15)) as i64, "{:#06x} >> {}", x, k);
+        }
+    }
+
+    #[test]
+    fn test_barrel_shift_saturates() {
+        // 幅より広いシフト量のバス（上位ビットだけが1）
+        let a = bits_from_u64_n(0x80F0, 16);
+        let amount = bits_from_u64_n(1 << 40, 64);
+        assert_eq!(barrel_shift(&a, &amount, ShiftDirection::Left, ShiftMode::Logical), bits_from_u64_n(0, 16));
+        assert_eq!(barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Logical), bits_from_u64_n(0, 16));
+        assert_eq!(barrel_shift(&a, &amount, ShiftDirection::Right, ShiftMode::Arithmetic), bits_from_u64_n(0xFFFF, 16));
+        // シフト量のバスが空なら、そのまま
+        assert_eq!(barrel_shift(&a, &[], ShiftDirection::Left, ShiftMode::Logical), a);
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::{format, string::{String, ToString}, vec::Vec};
+
+use crate::alu::{hack_alu_spec, AluOp};
+
+/// 真理値表
+///
+/// 各行は (入力, 出力) の組で、入力は関数の引数の順に並べる
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruthTable {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub rows: Vec<(Vec<bool>, Vec<bool>)>,
+}
+
+impl TruthTable {
+    /// 入力のすべての組み合わせに関数を適用して真理値表を作る
+    ///
+    /// 行の順序は、入力を2進数（先頭の引数がMSB）として見たときの昇順
+    pub fn from_fn(inputs: usize, f: impl Fn(&[bool]) -> Vec<bool>) -> Self {
+        let mut rows = Vec::with_capacity(1 << inputs);
+        for v in 0..(1usize << inputs) {
+            let input: Vec<bool> = (0..inputs).rev().map(|i| (v >> i) & 1 == 1).collect();
+            let output = f(&input);
+            rows.push((input, output));
+        }
+        let outputs = rows.first().map_or(0, |(_, output)| output.len());
+        TruthTable { inputs, outputs, rows }
+    }
+}
+
+/// 1つの #[test] 関数にまとめる行数の上限
+///
+/// これより大きな表は、この行数ごとに1つのテスト関数にまとめる
+pub const ROWS_PER_TEST: usize = 16;
+
+fn format_args(values: &[bool]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn format_expected(values: &[bool]) -> String {
+    if values.len() == 1 {
+        values[0].to_string()
+    } else {
+        format!("({})", format_args(values))
+    }
+}
+
+/// 真理値表からRustのテストモジュールのソースコードを生成する
+///
+/// * `name` - 生成するモジュール名（Rustの識別子であること）
+/// * `table` - 期待値となる真理値表
+/// * `fn_path` - テスト対象の関数のパス（例: `logic_gates::xor`）
+///
+/// 表が ROWS_PER_TEST 行以下なら1行につき1つの #[test]、それより大きい場合は
+/// ROWS_PER_TEST 行ずつまとめた #[test] を出力する。出力は rustfmt の整形済みの形になる
+pub fn generate_test_module(name: &str, table: &TruthTable, fn_path: &str) -> String {
+    let mut src = String::new();
+    src.push_str("#[cfg(test)]\n");
+    src.push_str(&format!("mod {} {{\n", name));
+
+    let grouped = table.rows.len() > ROWS_PER_TEST;
+    let chunk = if grouped { ROWS_PER_TEST } else { 1 };
+
+    for (i, rows) in table.rows.chunks(chunk).enumerate() {
+        let first = i * chunk;
+        let test_name = if grouped {
+            format!("rows_{}_{}", first, first + rows.len() - 1)
+        } else {
+            format!("row_{}", first)
+        };
+
+        if i > 0 {
+            src.push('\n');
+        }
+        src.push_str("    #[test]\n");
+        src.push_str(&format!("    fn {}() {{\n", test_name));
+        for (input, output) in rows {
+            src.push_str(&format!(
+                "        assert_eq!({}({}), {});\n",
+                fn_path,
+                format_args(input),
+                format_expected(output)
+            ));
+        }
+        src.push_str("    }\n");
+    }
+
+    src.push_str("}\n");
+    src
+}
+
+/// generate_alu_test_module が各関数に使う (x, y) の組
+pub const ALU_TEST_INPUTS: [(i16, i16); 4] = [(7, 5), (-3, 12), (0, 0), (i16::MAX, i16::MIN)];
+
+/// AluOp の名前を snake_case にする（XPlusY → x_plus_y）
+fn snake_case(op: AluOp) -> String {
+    let mut name = String::new();
+    for (i, ch) in format!("{:?}", op).chars().enumerate() {
+        if ch.is_ascii_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(ch.to_ascii_lowercase());
+    }
+    name
+}
+
+/// ALUの関数の一覧から、alu16_op の適合テストのモジュールのソースコードを生成する
+///
+/// * `name` - 生成するモジュール名（Rustの識別子であること）
+/// * `ops` - テストする関数（1つにつき1つの #[test] を出力する）
+///
+/// 各テストは ALU_TEST_INPUTS のそれぞれの (x, y) で `logic_gates::alu::alu16_op` を呼び、
+/// (out, zr, ng) を hack_alu_spec の参照実装から求めた期待値と比べる
+/// 出力は generate_test_module と同じく rustfmt の整形済みの形になる
+pub fn generate_alu_test_module(name: &str, ops: &[AluOp]) -> String {
+    let spec = hack_alu_spec();
+    let mut src = String::new();
+    src.push_str("#[cfg(test)]\n");
+    src.push_str(&format!("mod {} {{\n", name));
+    src.push_str("    use logic_gates::alu::{alu16_op, AluOp};\n");
+    src.push_str("    use logic_gates::convert::{bits_from_i16, i16_from_bits};\n");
+
+    for &op in ops {
+        let &(_, _, reference) = spec
+            .iter()
+            .find(|(ctl, _, _)| *ctl == op.control_bits())
+            .expect("hack_alu_spec covers every AluOp");
+        src.push('\n');
+        src.push_str("    #[test]\n");
+        src.push_str(&format!("    fn {}() {{\n", snake_case(op)));
+        for (x, y) in ALU_TEST_INPUTS {
+            let out = reference(x, y);
+            src.push_str(&format!(
+                "        let (out, zr, ng) = alu16_op(&bits_from_i16({}), &bits_from_i16({}), AluOp::{:?});\n",
+                x, y, op
+            ));
+            src.push_str(&format!(
+                "        assert_eq!((i16_from_bits(&out), zr, ng), ({}, {}, {}));\n",
+                out,
+                out == 0,
+                out < 0
+            ));
+        }
+        src.push_str("    }\n");
+    }
+
+    src.push_str("}\n");
+    src
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mux, xor};
+
+    #[test]
+    fn test_truth_table_from_fn() {
+        let table = TruthTable::from_fn(2, |v| vec![xor(v[0], v[1])]);
+        assert_eq!(table.inputs, 2);
+        assert_eq!(table.outputs, 1);
+        assert_eq!(table.rows[1], (vec![false, true], vec![true]));
+        assert_eq!(table.rows[3], (vec![true, true], vec![false]));
+    }
+
+    #[test]
+    fn test_generate_xor_module() {
+        let table = TruthTable::from_fn(2, |v| vec![xor(v[0], v[1])]);
+        let src = generate_test_module("xor_truth_table", &table, "crate::xor");
+
+        assert_eq!(src.matches("assert_eq!").count(), 4);
+        assert_eq!(src.matches("#[test]").count(), 4);
+        assert!(src.contains("assert_eq!(crate::xor(true, false), true);"));
+
+        let file = syn::parse_file(&src).expect("generated module should parse");
+        assert_eq!(file.items.len(), 1);
+    }
+
+    #[test]
+    fn test_generate_grouped_module() {
+        // 5入力（32行）の表は16行ずつ2つのテストにまとめる
+        let table = TruthTable::from_fn(5, |v| vec![mux(v[0], v[1], v[2]), xor(v[3], v[4])]);
+        let src = generate_test_module("wide", &table, "check");
+
+        assert_eq!(src.matches("assert_eq!").count(), 32);
+        assert_eq!(src.matches("#[test]").count(), 2);
+        assert!(src.contains("fn rows_16_31()"));
+        assert!(src.contains("), (false, true));"));
+        syn::parse_file(&src).expect("generated module should parse");
+    }
+
+    #[test]
+    fn test_generate_alu_module() {
+        let src = generate_alu_test_module("alu_conformance", &[AluOp::XPlusY, AluOp::NotX, AluOp::YMinusX]);
+
+        assert_eq!(src.matches("#[test]").count(), 3);
+        assert_eq!(src.matches("assert_eq!").count(), 3 * ALU_TEST_INPUTS.len());
+        assert!(src.contains("fn x_plus_y()"));
+        assert!(src.contains("fn y_minus_x()"));
+        assert!(src.contains("alu16_op(&bits_from_i16(7), &bits_from_i16(5), AluOp::XPlusY);"));
+        assert!(src.contains("assert_eq!((i16_from_bits(&out), zr, ng), (12, false, false));"));
+        // !(-3) = 2
+        assert!(src.contains("AluOp::NotX);\n        assert_eq!((i16_from_bits(&out), zr, ng), (2, false, false));"));
+
+        let file = syn::parse_file(&src).expect("generated module should parse");
+        assert_eq!(file.items.len(), 1);
+
+        // すべての関数でも構文として正しい
+        let src = generate_alu_test_module("all_ops", &AluOp::ALL);
+        assert_eq!(src.matches("#[test]").count(), 18);
+        syn::parse_file(&src).expect("generated module should parse");
+    }
+}
@@ -0,0 +1,26 @@
+//! テスト用の補助関数群
+//!
+//! 整数とビット列の変換は convert モジュールの関数を使う
+
+/// 再現性のある乱数列を生成する xorshift64
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        XorShift(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// 下位nビットだけを残した乱数
+    pub fn next_bits(&mut self, n: usize) -> u64 {
+        if n >= 64 { self.next_u64() } else { self.next_u64() & ((1 << n) - 1) }
+    }
+}
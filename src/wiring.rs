@@ -0,0 +1,301 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use crate::{mux, xor};
+#[cfg(feature = "alloc")]
+use crate::bus::or_reduce;
+
+/// ビット列の幅に関するエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitSliceError {
+    /// 幅が8の倍数ではない（バイト単位の操作ができない）
+    NotByteMultiple { width: usize },
+    /// 出力先のバッファが短い（needed ビット必要だが len ビットしかない）
+    BufferTooShort { needed: usize, len: usize },
+}
+
+impl fmt::Display for BitSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitSliceError::NotByteMultiple { width } => {
+                write!(f, "width {} is not a multiple of 8", width)
+            }
+            BitSliceError::BufferTooShort { needed, len } => {
+                write!(f, "output buffer has {} bits, needs {}", len, needed)
+            }
+        }
+    }
+}
+
+impl core::error::Error for BitSliceError {}
+
+/// ビットの並べ替え（配線だけの回路）
+///
+/// 出力ビット i に、入力ビット sources[i] をつなぐ
+/// 各出力は選択信号を1に固定した mux で受け取る。選択信号が定数なので mux は1本の配線と同じになり、
+/// 並べ替えだけの回路は論理ゲートを必要としない
+///
+/// * `a` - 入力（LSB→MSB順）
+/// * `sources` - 出力ビットごとの入力ビットの位置
+///
+/// 出力の長さは sources.len()。範囲外の位置を指す出力は0になる
+#[cfg(feature = "alloc")]
+pub fn permute(a: &[bool], sources: &[usize]) -> Vec<bool> {
+    sources
+        .iter()
+        .map(|&source| mux(true, false, source < a.len() && a[source]))
+        .collect()
+}
+
+/// バイトスワップ
+///
+/// ワードのバイトの並びを逆にする（バイト内のビットの並びはそのまま）。permute による配線だけの回路
+///
+/// 幅が8の倍数でない場合は BitSliceError::NotByteMultiple を返す
+#[cfg(feature = "alloc")]
+pub fn byte_swap(a: &[bool]) -> Result<Vec<bool>, BitSliceError> {
+    if !a.len().is_multiple_of(8) {
+        return Err(BitSliceError::NotByteMultiple { width: a.len() });
+    }
+
+    let bytes = a.len() / 8;
+    let sources: Vec<usize> = (0..a.len()).map(|i| (bytes - 1 - i / 8) * 8 + i % 8).collect();
+    Ok(permute(a, &sources))
+}
+
+/// 16ビットのバイトスワップ
+///
+/// 下位バイト（ビット0〜7）と上位バイト（ビット8〜15）を入れ替える
+#[cfg(feature = "alloc")]
+pub fn byte_swap16(a: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(byte_swap(a).unwrap()) {
+        *o = bit;
+    }
+    out
+}
+
+/// ビット反転
+///
+/// ビットの並びを逆にする（ビット0 ↔ ビットn-1）。permute による配線だけの回路
+///
+/// LSB→MSB順とMSB→LSB順の変換にも使える
+#[cfg(feature = "alloc")]
+pub fn bit_reverse(a: &[bool]) -> Vec<bool> {
+    let sources: Vec<usize> = (0..a.len()).rev().collect();
+    permute(a, &sources)
+}
+
+/// 16ビットのビット反転
+///
+/// bit_reverse の16ビット固定幅版
+#[cfg(feature = "alloc")]
+pub fn bit_reverse16(a: &[bool; 16]) -> [bool; 16] {
+    let mut out = [false; 16];
+    for (o, bit) in out.iter_mut().zip(bit_reverse(a)) {
+        *o = bit;
+    }
+    out
+}
+
+/// 16ビットワードを2つのバイトに分ける
+///
+/// 戻り値は (lo, hi) のタプル
+/// - lo: 下位バイト（ワードのビット0〜7）
+/// - hi: 上位バイト（ワードのビット8〜15）
+///
+/// どちらのバイトもLSB→MSB順（lo[0] がワードのビット0、hi[0] がワードのビット8）
+pub fn split16(a: &[bool; 16]) -> ([bool; 8], [bool; 8]) {
+    let mut lo = [false; 8];
+    let mut hi = [false; 8];
+    lo.copy_from_slice(&a[..8]);
+    hi.copy_from_slice(&a[8..]);
+    (lo, hi)
+}
+
+/// 2つのバイトを16ビットワードにつなぐ（split16 の逆）
+///
+/// lo がワードのビット0〜7、hi がビット8〜15 になる
+pub fn join16(lo: &[bool; 8], hi: &[bool; 8]) -> [bool; 16] {
+    let mut out = [false; 16];
+    out[..8].copy_from_slice(lo);
+    out[8..].copy_from_slice(hi);
+    out
+}
+
+/// バスを位置 mid で2つに分ける
+///
+/// 戻り値は (low, high) のタプル
+/// - low: ビット 0..mid（下位側）
+/// - high: ビット mid.. （上位側、high[0] が元のビット mid）
+///
+/// mid がバスの幅を超える場合は、すべてを low に入れる
+#[cfg(feature = "alloc")]
+pub fn split_at_bus(a: &[bool], mid: usize) -> (Vec<bool>, Vec<bool>) {
+    let (low, high) = a.split_at(mid.min(a.len()));
+    (low.to_vec(), high.to_vec())
+}
+
+/// 複数のバスをつなぐ
+///
+/// parts[0] が最下位側になり、続くバスが順に上位側へ並ぶ（split_at_bus の逆）
+#[cfg(feature = "alloc")]
+pub fn concat_bus(parts: &[&[bool]]) -> Vec<bool> {
+    parts.concat()
+}
+
+/// ゼロ拡張
+///
+/// nビットに広げ、増えた上位ビットを0にする（符号なしの値を保つ）
+/// n が元の幅より小さい場合は、下位nビットだけを残す
+#[cfg(feature = "alloc")]
+pub fn zero_extend(a: &[bool], n: usize) -> Vec<bool> {
+    (0..n).map(|i| i < a.len() && a[i]).collect()
+}
+
+/// 符号拡張
+///
+/// nビットに広げ、増えた上位ビットを元のMSBの複製にする（2の補数の値を保つ）
+/// 空のスライスは0とみなし、すべて0になる。n が元の幅より小さい場合は、下位nビットだけを残す
+#[cfg(feature = "alloc")]
+pub fn sign_extend(a: &[bool], n: usize) -> Vec<bool> {
+    let msb = a.last().copied().unwrap_or(false);
+    (0..n).map(|i| if i < a.len() { a[i] } else { msb }).collect()
+}
+
+/// 検査付きの切り詰め
+///
+/// 下位nビットだけを残す
+///
+/// 戻り値は (truncated, changed) のタプル
+/// - changed: 2の補数として値が変わる場合にtrue。捨てたビットのどれかが、残したMSBと異なれば値が変わる
+///   （n = 0 の場合は値0になるので、元の値が0でなければtrue）
+///
+/// n が元の幅以上なら、そのまま返して changed はfalse
+#[cfg(feature = "alloc")]
+pub fn truncate_checked(a: &[bool], n: usize) -> (Vec<bool>, bool) {
+    if n >= a.len() {
+        return (a.to_vec(), false);
+    }
+
+    let truncated = a[..n].to_vec();
+    let msb = truncated.last().copied().unwrap_or(false);
+    let differs: Vec<bool> = a[n..].iter().map(|&bit| xor(bit, msb)).collect();
+    (truncated, or_reduce(&differs))
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::convert::{bits_from_i16, bits_from_u16, bits_from_u64_n};
+
+    #[test]
+    fn test_byte_swap16() {
+        assert_eq!(byte_swap16(&bits_from_u16(0x0012)), bits_from_u16(0x1200));
+        assert_eq!(byte_swap16(&bits_from_u16(0x1234)), bits_from_u16(0x3412));
+        for v in [0x0000u16, 0xFFFF, 0x00FF, 0xA55A, 0x8001] {
+            assert_eq!(byte_swap16(&bits_from_u16(v)), bits_from_u16(v.swap_bytes()));
+            assert_eq!(byte_swap16(&byte_swap16(&bits_from_u16(v))), bits_from_u16(v));
+        }
+    }
+
+    #[test]
+    fn test_byte_swap_widths() {
+        assert_eq!(byte_swap(&bits_from_u64_n(0x12_3456, 24)), Ok(bits_from_u64_n(0x56_3412, 24)));
+        assert_eq!(byte_swap(&bits_from_u64_n(0xAB, 8)), Ok(bits_from_u64_n(0xAB, 8)));
+        assert_eq!(byte_swap(&[]), Ok(vec![]));
+        assert_eq!(byte_swap(&bits_from_u64_n(0xABC, 12)), Err(BitSliceError::NotByteMultiple { width: 12 }));
+    }
+
+    #[test]
+    fn test_permute() {
+        let a = bits_from_u64_n(0b0110, 4);
+        assert_eq!(permute(&a, &[1, 2, 3, 0]), vec![true, true, false, false]);
+        // 同じ入力を複数の出力へつなぐこともできる。範囲外の位置は0
+        assert_eq!(permute(&a, &[2, 2, 9]), vec![true, true, false]);
+        assert_eq!(permute(&a, &[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_bit_reverse() {
+        for n in [1usize, 3, 8, 16, 21] {
+            let a = bits_from_u64_n(0x15_A5C3 & ((1 << n) - 1), n);
+            assert_eq!(bit_reverse(&bit_reverse(&a)), a, "n = {}", n);
+
+            // LSBだけが1のワードを反転すると、MSBだけが1になる
+            let mut msb_only = vec![false; n];
+            msb_only[n - 1] = true;
+            assert_eq!(bit_reverse(&bits_from_u64_n(1, n)), msb_only, "n = {}", n);
+        }
+        assert_eq!(bit_reverse(&[]), Vec::<bool>::new());
+        assert_eq!(bit_reverse(&[true]), vec![true]);
+        assert_eq!(bit_reverse16(&bits_from_u16(0x0001)), bits_from_u16(0x8000));
+        assert_eq!(bit_reverse16(&bits_from_u16(0x1234)), bits_from_u16(0x1234u16.reverse_bits()));
+    }
+
+    #[test]
+    fn test_split16_join16() {
+        let (lo, hi) = split16(&bits_from_u16(0xBEEF));
+        assert_eq!(lo.to_vec(), bits_from_u64_n(0xEF, 8));
+        assert_eq!(hi.to_vec(), bits_from_u64_n(0xBE, 8));
+        for v in [0x0000u16, 0xFFFF, 0x1234, 0x8001] {
+            let (lo, hi) = split16(&bits_from_u16(v));
+            assert_eq!(join16(&lo, &hi), bits_from_u16(v));
+        }
+    }
+
+    #[test]
+    fn test_split_at_bus() {
+        let a = bits_from_u64_n(0b1011_0110, 8);
+        assert_eq!(split_at_bus(&a, 3), (bits_from_u64_n(0b110, 3), bits_from_u64_n(0b10110, 5)));
+        assert_eq!(split_at_bus(&a, 0), (vec![], a.clone()));
+        assert_eq!(split_at_bus(&a, 20), (a.clone(), vec![]));
+
+        let (low, high) = split_at_bus(&a, 5);
+        assert_eq!(concat_bus(&[&low, &high]), a);
+    }
+
+    #[test]
+    fn test_concat_bus_unequal_parts() {
+        // 3ビット + 1ビット + 5ビット: 最初の部分が下位側
+        let parts: [&[bool]; 3] = [&bits_from_u64_n(0b101, 3), &[true], &bits_from_u64_n(0b10011, 5)];
+        assert_eq!(concat_bus(&parts), bits_from_u64_n(0b1_0011_1101, 9));
+        assert_eq!(concat_bus(&[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn test_zero_extend() {
+        assert_eq!(zero_extend(&bits_from_u64_n(0b1011, 4), 8), bits_from_u64_n(0b1011, 8));
+        assert_eq!(zero_extend(&bits_from_u64_n(0b1011, 4), 2), bits_from_u64_n(0b11, 2));
+        assert_eq!(zero_extend(&[], 3), vec![false; 3]);
+    }
+
+    #[test]
+    fn test_sign_extend() {
+        // 4ビットの -3（0b1101）を16ビットに符号拡張すると、i16 の -3 と同じ
+        assert_eq!(sign_extend(&bits_from_u64_n(0b1101, 4), 16), bits_from_i16(-3).to_vec());
+        assert_eq!(sign_extend(&bits_from_u64_n(0b0101, 4), 16), bits_from_u16(5).to_vec());
+        assert_eq!(sign_extend(&[], 4), vec![false; 4]);
+        assert_eq!(sign_extend(&[true], 3), vec![true; 3]);
+    }
+
+    #[test]
+    fn test_truncate_checked() {
+        // 300 は8ビットに収まらないが、100 は収まる
+        assert_eq!(truncate_checked(&bits_from_u16(300), 8), (bits_from_u64_n(300 & 0xFF, 8), true));
+        assert_eq!(truncate_checked(&bits_from_u16(100), 8), (bits_from_u64_n(100, 8), false));
+
+        // 負数: -100 は8ビットに収まり、-200 は収まらない
+        assert_eq!(truncate_checked(&bits_from_i16(-100), 8).1, false);
+        assert_eq!(truncate_checked(&bits_from_i16(-200), 8).1, true);
+
+        // 128 は8ビットでは -128 になるので値が変わる
+        assert_eq!(truncate_checked(&bits_from_u16(128), 8).1, true);
+
+        assert_eq!(truncate_checked(&bits_from_u64_n(5, 4), 8), (bits_from_u64_n(5, 4), false));
+        assert_eq!(truncate_checked(&bits_from_u64_n(0, 4), 0), (vec![], false));
+        assert_eq!(truncate_checked(&bits_from_u64_n(1, 4), 0), (vec![], true));
+    }
+}
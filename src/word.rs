@@ -0,0 +1,206 @@
+use core::fmt;
+use core::ops::{Add, BitAnd, BitOr, BitXor, Not};
+
+use crate::adder::n_bit_adder;
+use crate::bus::{and16, not16, or16, xor16};
+use crate::convert::{bits_from_i16, bits_from_u16, format_bits, format_bits_hex, u16_from_bits};
+
+/// 16ビットワード（LSB→MSB順）
+///
+/// 演算子はすべてゲートレベルの実装を通して計算する（ホストの整数演算は使わない）
+/// - `a & b`: and16
+/// - `a | b`: or16
+/// - `a ^ b`: xor16
+/// - `!a`: not16
+/// - `a + b`: n_bit_adder（16ビットで回り込み、最終桁上げは捨てる）
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Word16(pub [bool; 16]);
+
+impl Word16 {
+    /// ALUなどに渡すためのビット列
+    pub fn as_bits(&self) -> &[bool; 16] {
+        &self.0
+    }
+}
+
+impl From<u16> for Word16 {
+    fn from(v: u16) -> Self {
+        Word16(bits_from_u16(v))
+    }
+}
+
+impl From<i16> for Word16 {
+    fn from(v: i16) -> Self {
+        Word16(bits_from_i16(v))
+    }
+}
+
+impl From<Word16> for u16 {
+    fn from(w: Word16) -> Self {
+        u16_from_bits(&w.0)
+    }
+}
+
+impl BitAnd for Word16 {
+    type Output = Word16;
+
+    fn bitand(self, rhs: Word16) -> Word16 {
+        Word16(and16(&self.0, &rhs.0))
+    }
+}
+
+impl BitOr for Word16 {
+    type Output = Word16;
+
+    fn bitor(self, rhs: Word16) -> Word16 {
+        Word16(or16(&self.0, &rhs.0))
+    }
+}
+
+impl BitXor for Word16 {
+    type Output = Word16;
+
+    fn bitxor(self, rhs: Word16) -> Word16 {
+        Word16(xor16(&self.0, &rhs.0))
+    }
+}
+
+impl Not for Word16 {
+    type Output = Word16;
+
+    fn not(self) -> Word16 {
+        Word16(not16(&self.0))
+    }
+}
+
+impl Add for Word16 {
+    type Output = Word16;
+
+    fn add(self, rhs: Word16) -> Word16 {
+        let (sum, _carry) = n_bit_adder(&self.0, &rhs.0);
+        let mut out = [false; 16];
+        out.copy_from_slice(&sum);
+        Word16(out)
+    }
+}
+
+/// 2進数（MSBが左、16桁）で表示する
+impl fmt::Display for Word16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_bits(&self.0))
+    }
+}
+
+/// 16進数（Word16(0x....)）で表示する
+impl fmt::Debug for Word16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Word16(0x{})", format_bits_hex(&self.0))
+    }
+}
+
+/// serde 対応（`serde` フィーチャー）
+///
+/// "0x1f2a" のような、接頭辞付きの4桁の16進数の文字列として書き出す
+/// 読み込みでは16進（`0x` + 4桁）または2進（16桁、接頭辞 `0b` と `_` を使える）の文字列を受け付け、
+/// 幅が16ビットでなければエラーにする
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use alloc::format;
+
+    use serde::de::{self, Deserializer, Visitor};
+    use serde::{Deserialize, Serialize, Serializer};
+
+    use super::Word16;
+    use crate::convert::{format_bits_hex, parse_bits, parse_bits_hex};
+
+    impl Serialize for Word16 {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&format!("0x{}", format_bits_hex(&self.0)))
+        }
+    }
+
+    struct Word16Visitor;
+
+    impl Visitor<'_> for Word16Visitor {
+        type Value = Word16;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a 16-bit word such as \"0x1f2a\" or a 16-digit binary string")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Word16, E> {
+            let is_hex = s.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("0x"));
+            let parsed = if is_hex { parse_bits_hex(s) } else { parse_bits(s) };
+            let bits = parsed.map_err(|e| E::custom(format!("invalid word {:?}: {}", s, e)))?;
+            if bits.len() != 16 {
+                return Err(E::custom(format!("invalid word {:?}: expected 16 bits, got {}", s, bits.len())));
+            }
+            let mut out = [false; 16];
+            out.copy_from_slice(&bits);
+            Ok(Word16(out))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Word16 {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Word16, D::Error> {
+            deserializer.deserialize_str(Word16Visitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::XorShift;
+
+    #[test]
+    fn test_word16_operators() {
+        let mut rng = XorShift::new(0x3016);
+        for _ in 0..200 {
+            let (a, b) = (rng.next_bits(16) as u16, rng.next_bits(16) as u16);
+            let (wa, wb) = (Word16::from(a), Word16::from(b));
+            assert_eq!(u16::from(wa & wb), a & b);
+            assert_eq!(u16::from(wa | wb), a | b);
+            assert_eq!(u16::from(wa ^ wb), a ^ b);
+            assert_eq!(u16::from(!wa), !a);
+            assert_eq!(u16::from(wa + wb), a.wrapping_add(b));
+        }
+    }
+
+    #[test]
+    fn test_word16_operator_chain() {
+        for (a, b) in [(0xFFFFu16, 0x0000u16), (0x1234, 0x00FF), (0xF0F0, 0xFFFF)] {
+            let result = (Word16::from(a) & !Word16::from(b)) + Word16::from(1u16);
+            assert_eq!(u16::from(result), (a & !b).wrapping_add(1), "a = {:#06x}, b = {:#06x}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_word16_conversions_and_format() {
+        assert_eq!(Word16::from(-1i16), Word16::from(0xFFFFu16));
+        assert_eq!(Word16::from(5u16).as_bits(), &bits_from_u16(5));
+        assert_eq!(Word16::from(5u16).to_string(), "0000000000000101");
+        assert_eq!(format!("{:?}", Word16::from(0xBEEFu16)), "Word16(0xbeef)");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_word16_serde_round_trip() {
+        for v in [0x0000u16, 0x1F2A, 0xFFFF] {
+            let json = serde_json::to_string(&Word16::from(v)).unwrap();
+            assert_eq!(json, format!("\"{:#06x}\"", v));
+            assert_eq!(serde_json::from_str::<Word16>(&json).unwrap(), Word16::from(v));
+        }
+        let binary = serde_json::from_str::<Word16>("\"0000_0000_0000_0101\"").unwrap();
+        assert_eq!(binary, Word16::from(5u16));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_word16_serde_rejects_bad_width() {
+        let err = serde_json::from_str::<Word16>("\"10000000000000000\"").unwrap_err();
+        assert!(err.to_string().contains("expected 16 bits, got 17"), "{}", err);
+        assert!(serde_json::from_str::<Word16>("\"0x1F2\"").is_err());
+        assert!(serde_json::from_str::<Word16>("\"0xZZZZ\"").is_err());
+    }
+}
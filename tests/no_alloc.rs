@@ -0,0 +1,69 @@
+// `alloc` なしでも使える回路のテスト
+//
+// `cargo test --no-default-features` でもビルドできることを確かめるため、
+// ここでは Vec を返す関数を使わず、固定幅（配列）の API だけを呼ぶ
+
+use logic_gates::adder::{add16, add_into, full_adder, half_adder, incrementer_wrapping_n, n_bit_adder_const};
+use logic_gates::alu::{alu16_v2, alu_const, AluControl};
+use logic_gates::bus::{and_bus, or_reduce};
+use logic_gates::{and, mux, nand, not, or, xor};
+
+fn word<const N: usize>(v: u64) -> [bool; N] {
+    core::array::from_fn(|i| (v >> i) & 1 == 1)
+}
+
+fn value(bits: &[bool]) -> u64 {
+    bits.iter().rev().fold(0, |acc, &b| (acc << 1) | b as u64)
+}
+
+#[test]
+fn test_gates() {
+    for a in [false, true] {
+        assert_eq!(not(a), !a);
+        for b in [false, true] {
+            assert_eq!(nand(a, b), !(a && b));
+            assert_eq!(and(a, b), a && b);
+            assert_eq!(or(a, b), a || b);
+            assert_eq!(xor(a, b), a != b);
+            for sel in [false, true] {
+                assert_eq!(mux(sel, a, b), if sel { b } else { a });
+            }
+        }
+    }
+}
+
+#[test]
+fn test_adders() {
+    assert_eq!(half_adder(true, true), (false, true));
+    assert_eq!(full_adder(true, false, true), (false, true));
+    assert_eq!(full_adder(true, true, true), (true, true));
+
+    let (sum, carry) = n_bit_adder_const::<8>(&word(200), &word(100));
+    assert_eq!((value(&sum), carry), (44, true));
+    assert_eq!(value(&incrementer_wrapping_n::<4>(&word(15))), 0);
+
+    let (sum, carry) = add16(&word(0x7FFF), &word(0), true);
+    assert_eq!((value(&sum), carry), (0x8000, false));
+
+    let mut out = [false; 10];
+    let carry = add_into(&word::<8>(255), &word::<4>(1), false, &mut out).unwrap();
+    assert_eq!((value(&out), carry), (0, true));
+}
+
+#[test]
+fn test_bus_and_alu() {
+    assert_eq!(value(&and_bus::<8>(&word(0b1100_1010), &word(0b1010_0110))), 0b1000_0010);
+    assert!(!or_reduce(&[false; 8]));
+
+    // x + y
+    let (out, zr, ng) = alu_const::<12>(&word(1000), &word(24), false, false, false, false, true, false);
+    assert_eq!((value(&out), zr, ng), (1024, false, false));
+
+    // x - y（結果は2の補数で負）
+    let r = alu16_v2(&word(3), &word(5), AluControl::X_MINUS_Y);
+    assert_eq!((value(&r.out), r.zr, r.ng), (0xFFFE, false, true));
+
+    // x + y の桁上げ
+    let r = alu16_v2(&word(0xFFFF), &word(1), AluControl::X_PLUS_Y);
+    assert_eq!((value(&r.out), r.zr, r.carry, r.overflow), (0, true, true, false));
+}